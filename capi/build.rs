@@ -0,0 +1,25 @@
+use std::{env, path::PathBuf};
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("cargo sets the dir"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("cargo sets the out dir"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("failed to read the cbindgen config");
+
+    // the build doesn't write to the source tree; the tests check that the committed header is
+    // the generated one
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("failed to generate the bindings")
+        .write_to_file(out_dir.join("reginae.h"));
+
+    // the C test program is built for the same target as the crate
+    println!(
+        "cargo:rustc-env=TARGET={}",
+        env::var("TARGET").expect("cargo sets the target")
+    );
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
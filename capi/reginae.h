@@ -0,0 +1,112 @@
+#ifndef REGINAE_H
+#define REGINAE_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The operation succeeded.
+#define REGINAE_OK 0
+
+// The board was solved.
+#define REGINAE_SOLVED 0
+
+// The board was exhausted without a solution.
+#define REGINAE_UNSOLVABLE 1
+
+// The jump budget was depleted before the board was either solved or exhausted.
+#define REGINAE_INTERRUPTED 2
+
+// A null pointer was provided for a required argument.
+#define REGINAE_ERR_NULL -1
+
+// The index is out of the bounds of the board.
+#define REGINAE_ERR_OUT_OF_BOUNDS -2
+
+// The name doesn't match any of the built-in evaluators.
+#define REGINAE_ERR_UNKNOWN_EVALUATOR -3
+
+// The string argument isn't valid UTF-8.
+#define REGINAE_ERR_INVALID_UTF8 -4
+
+// The library panicked; the message is available via `reginae_last_error`.
+#define REGINAE_ERR_PANIC -5
+
+// Opaque handle of a board.
+typedef struct ReginaeBoard ReginaeBoard;
+
+// Opaque handle of a solver.
+typedef struct ReginaeSolver ReginaeSolver;
+
+// Options of a single solve call.
+typedef struct ReginaeSolveOptions {
+  // Maximum number of jumps of the call; `0` means unbounded.
+  size_t max_jumps;
+} ReginaeSolveOptions;
+
+// Returns the message of the last error of the current thread, or null if no error occurred.
+//
+// The pointer is valid until the next failing call on the same thread.
+const char *reginae_last_error(void);
+
+// Creates an empty board with the given width. Returns null on failure.
+struct ReginaeBoard *reginae_board_new(size_t width);
+
+// Toggles the queen of the given index.
+//
+// # Safety
+//
+// `board` must be a valid handle created by `reginae_board_new`.
+int32_t reginae_board_toggle(struct ReginaeBoard *board, size_t index);
+
+// Writes up to `out_len` sorted queen indexes to `out_ptr`, and the total number of queens of
+// the board to `out_count`.
+//
+// # Safety
+//
+// `board` must be a valid handle created by `reginae_board_new`, `out_ptr` must be valid for
+// `out_len` writes, and `out_count` for one write. `out_ptr` may be null if `out_len` is `0`.
+int32_t reginae_board_queens(const struct ReginaeBoard *board,
+                             size_t *out_ptr,
+                             size_t out_len,
+                             size_t *out_count);
+
+// Frees a board handle. Null is a no-op.
+//
+// # Safety
+//
+// `board` must be either null or a valid handle created by `reginae_board_new` that wasn't
+// freed before.
+void reginae_board_free(struct ReginaeBoard *board);
+
+// Creates a solver without evaluators. Returns null on failure.
+struct ReginaeSolver *reginae_solver_new(void);
+
+// Injects a built-in evaluator, by its name, with the given weight.
+//
+// # Safety
+//
+// `solver` must be a valid handle created by `reginae_solver_new`, and `name` a valid
+// nul-terminated string.
+int32_t reginae_solver_add_builtin(struct ReginaeSolver *solver, const char *name, double weight);
+
+// Solves the board in place, returning the outcome code or a negative error code.
+//
+// # Safety
+//
+// `solver` and `board` must be valid handles. `options` may be null for the defaults.
+int32_t reginae_solver_solve(struct ReginaeSolver *solver,
+                             struct ReginaeBoard *board,
+                             const struct ReginaeSolveOptions *options);
+
+// Frees a solver handle. Null is a no-op.
+//
+// # Safety
+//
+// `solver` must be either null or a valid handle created by `reginae_solver_new` that wasn't
+// freed before.
+void reginae_solver_free(struct ReginaeSolver *solver);
+
+#endif /* REGINAE_H */
@@ -0,0 +1,296 @@
+use reginae_solver::{Board, Outcome, Solver};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+/// The operation succeeded.
+pub const REGINAE_OK: i32 = 0;
+/// The board was solved.
+pub const REGINAE_SOLVED: i32 = 0;
+/// The board was exhausted without a solution.
+pub const REGINAE_UNSOLVABLE: i32 = 1;
+/// The jump budget was depleted before the board was either solved or exhausted.
+pub const REGINAE_INTERRUPTED: i32 = 2;
+/// A null pointer was provided for a required argument.
+pub const REGINAE_ERR_NULL: i32 = -1;
+/// The index is out of the bounds of the board.
+pub const REGINAE_ERR_OUT_OF_BOUNDS: i32 = -2;
+/// The name doesn't match any of the built-in evaluators.
+pub const REGINAE_ERR_UNKNOWN_EVALUATOR: i32 = -3;
+/// The string argument isn't valid UTF-8.
+pub const REGINAE_ERR_INVALID_UTF8: i32 = -4;
+/// The library panicked; the message is available via `reginae_last_error`.
+pub const REGINAE_ERR_PANIC: i32 = -5;
+
+/// Opaque handle of a board.
+pub struct ReginaeBoard {
+    board: Board,
+}
+
+/// Opaque handle of a solver.
+pub struct ReginaeSolver {
+    solver: Solver,
+}
+
+/// Options of a single solve call.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReginaeSolveOptions {
+    /// Maximum number of jumps of the call; `0` means unbounded.
+    pub max_jumps: usize,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // cbindgen parses the crate with syn 1, which doesn't support the C string literals
+    let message = CString::new(message).unwrap_or_else(|_| {
+        CString::new("invalid error message").expect("the message has no nul byte")
+    });
+    LAST_ERROR.with(|e| e.replace(Some(message)));
+}
+
+/// Runs `f`, converting both errors and panics into error codes with a last-error message.
+fn guard<F>(f: F) -> i32
+where
+    F: FnOnce() -> Result<i32, (i32, String)>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(code)) => code,
+        Ok(Err((code, message))) => {
+            set_last_error(message);
+            code
+        }
+        Err(e) => {
+            let message = e
+                .downcast_ref::<&str>()
+                .map(|m| m.to_string())
+                .or_else(|| e.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_last_error(format!("panicked: {message}"));
+            REGINAE_ERR_PANIC
+        }
+    }
+}
+
+fn null_error(argument: &str) -> (i32, String) {
+    (REGINAE_ERR_NULL, format!("the {argument} pointer is null"))
+}
+
+/// Returns the message of the last error of the current thread, or null if no error occurred.
+///
+/// The pointer is valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn reginae_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// Creates an empty board with the given width. Returns null on failure.
+#[no_mangle]
+pub extern "C" fn reginae_board_new(width: usize) -> *mut ReginaeBoard {
+    let mut board = ptr::null_mut();
+    guard(|| {
+        board = Box::into_raw(Box::new(ReginaeBoard {
+            board: Board::new(width),
+        }));
+        Ok(REGINAE_OK)
+    });
+    board
+}
+
+/// Toggles the queen of the given index.
+///
+/// # Safety
+///
+/// `board` must be a valid handle created by `reginae_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn reginae_board_toggle(board: *mut ReginaeBoard, index: usize) -> i32 {
+    guard(|| {
+        let board = &mut board.as_mut().ok_or_else(|| null_error("board"))?.board;
+        let cells = board.width() * board.width();
+        board.try_toggle(index).map_err(|_| {
+            (
+                REGINAE_ERR_OUT_OF_BOUNDS,
+                format!("the index {index} is out of bounds for {cells} cells"),
+            )
+        })?;
+        Ok(REGINAE_OK)
+    })
+}
+
+/// Writes up to `out_len` sorted queen indexes to `out_ptr`, and the total number of queens of
+/// the board to `out_count`.
+///
+/// # Safety
+///
+/// `board` must be a valid handle created by `reginae_board_new`, `out_ptr` must be valid for
+/// `out_len` writes, and `out_count` for one write. `out_ptr` may be null if `out_len` is `0`.
+#[no_mangle]
+pub unsafe extern "C" fn reginae_board_queens(
+    board: *const ReginaeBoard,
+    out_ptr: *mut usize,
+    out_len: usize,
+    out_count: *mut usize,
+) -> i32 {
+    guard(|| {
+        let board = &board.as_ref().ok_or_else(|| null_error("board"))?.board;
+        let out_count = out_count.as_mut().ok_or_else(|| null_error("count"))?;
+        if out_len > 0 {
+            if out_ptr.is_null() {
+                return Err(null_error("output"));
+            }
+            let out = slice::from_raw_parts_mut(out_ptr, out_len);
+            out.iter_mut()
+                .zip(board.sorted_queens())
+                .for_each(|(o, q)| *o = q);
+        }
+        *out_count = board.sorted_queens().count();
+        Ok(REGINAE_OK)
+    })
+}
+
+/// Frees a board handle. Null is a no-op.
+///
+/// # Safety
+///
+/// `board` must be either null or a valid handle created by `reginae_board_new` that wasn't
+/// freed before.
+#[no_mangle]
+pub unsafe extern "C" fn reginae_board_free(board: *mut ReginaeBoard) {
+    if !board.is_null() {
+        drop(Box::from_raw(board));
+    }
+}
+
+/// Creates a solver without evaluators. Returns null on failure.
+#[no_mangle]
+pub extern "C" fn reginae_solver_new() -> *mut ReginaeSolver {
+    let mut solver = ptr::null_mut();
+    guard(|| {
+        solver = Box::into_raw(Box::new(ReginaeSolver {
+            solver: Solver::default(),
+        }));
+        Ok(REGINAE_OK)
+    });
+    solver
+}
+
+/// Injects a built-in evaluator, by its name, with the given weight.
+///
+/// # Safety
+///
+/// `solver` must be a valid handle created by `reginae_solver_new`, and `name` a valid
+/// nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn reginae_solver_add_builtin(
+    solver: *mut ReginaeSolver,
+    name: *const c_char,
+    weight: f64,
+) -> i32 {
+    guard(|| {
+        let solver = &mut solver.as_mut().ok_or_else(|| null_error("solver"))?.solver;
+        if name.is_null() {
+            return Err(null_error("name"));
+        }
+        let name = CStr::from_ptr(name).to_str().map_err(|e| {
+            (
+                REGINAE_ERR_INVALID_UTF8,
+                format!("the evaluator name is invalid: {e}"),
+            )
+        })?;
+        let f = reginae_evaluators::by_name(name).ok_or_else(|| {
+            (
+                REGINAE_ERR_UNKNOWN_EVALUATOR,
+                format!("unknown evaluator `{name}`"),
+            )
+        })?;
+        solver.with_evaluator(f, weight);
+        Ok(REGINAE_OK)
+    })
+}
+
+/// Solves the board in place, returning the outcome code or a negative error code.
+///
+/// # Safety
+///
+/// `solver` and `board` must be valid handles. `options` may be null for the defaults.
+#[no_mangle]
+pub unsafe extern "C" fn reginae_solver_solve(
+    solver: *mut ReginaeSolver,
+    board: *mut ReginaeBoard,
+    options: *const ReginaeSolveOptions,
+) -> i32 {
+    guard(|| {
+        let solver = &mut solver.as_mut().ok_or_else(|| null_error("solver"))?.solver;
+        let board = &mut board.as_mut().ok_or_else(|| null_error("board"))?.board;
+        let options = options.as_ref().copied().unwrap_or_default();
+
+        let max_jumps = match options.max_jumps {
+            0 => usize::MAX,
+            m => m,
+        };
+        let solution = solver.with_max_jumps(max_jumps).solve(board.clone());
+        let outcome = solution.outcome();
+        *board = solution.board;
+
+        Ok(match outcome {
+            Outcome::Solved => REGINAE_SOLVED,
+            Outcome::Unsolvable => REGINAE_UNSOLVABLE,
+            Outcome::Interrupted => REGINAE_INTERRUPTED,
+        })
+    })
+}
+
+/// Frees a solver handle. Null is a no-op.
+///
+/// # Safety
+///
+/// `solver` must be either null or a valid handle created by `reginae_solver_new` that wasn't
+/// freed before.
+#[no_mangle]
+pub unsafe extern "C" fn reginae_solver_free(solver: *mut ReginaeSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+#[test]
+fn errors_are_reported() {
+    unsafe {
+        let board = reginae_board_new(4);
+        assert_eq!(reginae_board_toggle(board, 16), REGINAE_ERR_OUT_OF_BOUNDS);
+        assert!(!reginae_last_error().is_null());
+
+        let solver = reginae_solver_new();
+        let unknown = CString::new("unknown").expect("the name has no nul byte");
+        assert_eq!(
+            reginae_solver_add_builtin(solver, unknown.as_ptr(), 1.0),
+            REGINAE_ERR_UNKNOWN_EVALUATOR
+        );
+        assert_eq!(
+            reginae_solver_solve(ptr::null_mut(), board, ptr::null()),
+            REGINAE_ERR_NULL
+        );
+
+        let mut queens = [0; 4];
+        let mut count = 0;
+        assert_eq!(reginae_board_toggle(board, 1), REGINAE_OK);
+        assert_eq!(
+            reginae_board_queens(board, queens.as_mut_ptr(), 0, &mut count),
+            REGINAE_OK
+        );
+        assert_eq!(count, 1);
+        assert_eq!(
+            reginae_board_queens(board, queens.as_mut_ptr(), 4, ptr::null_mut()),
+            REGINAE_ERR_NULL
+        );
+
+        reginae_solver_free(solver);
+        reginae_board_free(board);
+    }
+}
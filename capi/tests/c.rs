@@ -0,0 +1,48 @@
+#![cfg(unix)]
+
+use std::{env, path::PathBuf, process::Command};
+
+#[test]
+fn c_program_builds_and_runs() {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let deps = env::current_exe()
+        .expect("the test binary has a path")
+        .parent()
+        .expect("the test binary is in the deps dir")
+        .to_path_buf();
+    let target = deps.parent().expect("deps is in the target dir");
+    let output = env::temp_dir().join(format!("reginae-capi-smoke-{}", std::process::id()));
+
+    let compiler = cc::Build::new()
+        .target(env!("TARGET"))
+        .host(env!("TARGET"))
+        .opt_level(0)
+        .cargo_metadata(false)
+        .get_compiler();
+
+    let status = compiler
+        .to_command()
+        .arg(manifest.join("tests").join("smoke.c"))
+        .arg("-I")
+        .arg(env!("OUT_DIR"))
+        .arg("-L")
+        .arg(target)
+        .arg("-lreginae_capi")
+        .arg("-o")
+        .arg(&output)
+        .status()
+        .expect("failed to spawn the C compiler");
+    assert!(status.success(), "failed to compile the C program");
+
+    let output = Command::new(&output)
+        .env("LD_LIBRARY_PATH", target)
+        .env("DYLD_LIBRARY_PATH", target)
+        .output()
+        .expect("failed to run the C program");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
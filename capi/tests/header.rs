@@ -0,0 +1,16 @@
+use std::{fs, path::Path};
+
+#[test]
+fn committed_header_is_generated() {
+    let generated = Path::new(env!("OUT_DIR")).join("reginae.h");
+    let committed = Path::new(env!("CARGO_MANIFEST_DIR")).join("reginae.h");
+
+    let generated_header = fs::read_to_string(&generated).expect("the build generates the header");
+    let committed_header = fs::read_to_string(&committed).expect("the header is committed");
+    assert!(
+        generated_header == committed_header,
+        "{} is stale; copy {} over it",
+        committed.display(),
+        generated.display()
+    );
+}
@@ -0,0 +1,40 @@
+#include <stdio.h>
+#include "reginae.h"
+
+#define CHECK(cond)                                                  \
+  do {                                                               \
+    if (!(cond)) {                                                   \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,         \
+              __LINE__, #cond);                                      \
+      return 1;                                                      \
+    }                                                                \
+  } while (0)
+
+int main(void) {
+  size_t queens[8];
+  size_t count = 0;
+  ReginaeBoard *board = reginae_board_new(8);
+  ReginaeSolver *solver = reginae_solver_new();
+  CHECK(board != NULL && solver != NULL);
+
+  CHECK(reginae_board_toggle(board, 64) == REGINAE_ERR_OUT_OF_BOUNDS);
+  CHECK(reginae_last_error() != NULL);
+  CHECK(reginae_solver_add_builtin(solver, "unknown", 1.0) == REGINAE_ERR_UNKNOWN_EVALUATOR);
+  CHECK(reginae_solver_add_builtin(solver, "overlapping", 10.0) == REGINAE_OK);
+
+  CHECK(reginae_board_toggle(board, 1) == REGINAE_OK);
+  CHECK(reginae_solver_solve(solver, board, NULL) == REGINAE_SOLVED);
+  CHECK(reginae_board_queens(board, queens, 8, &count) == REGINAE_OK);
+  CHECK(count == 8);
+  CHECK(queens[0] == 1);
+
+  ReginaeSolveOptions options = {.max_jumps = 1};
+  ReginaeBoard *large = reginae_board_new(20);
+  CHECK(reginae_solver_solve(solver, large, &options) == REGINAE_INTERRUPTED);
+
+  reginae_board_free(large);
+  reginae_board_free(board);
+  reginae_solver_free(solver);
+  printf("ok\n");
+  return 0;
+}
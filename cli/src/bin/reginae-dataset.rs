@@ -0,0 +1,92 @@
+use reginae_cli::{
+    builtin_evaluators,
+    dataset::{self, DatasetOptions},
+};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufWriter, Write},
+    str::FromStr,
+};
+
+fn main() -> io::Result<()> {
+    let mut options = DatasetOptions::default();
+    let mut csv = false;
+    let mut output = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("a value must be provided to the argument {arg}"),
+            )
+        })?;
+
+        match arg.as_str() {
+            "--widths" => {
+                // either a single width, or an inclusive `min-max` range
+                let (min, max) = value.split_once('-').unwrap_or((&value, &value));
+                options.widths = parse(&arg, min)?..=parse(&arg, max)?;
+            }
+            "--per-width" => options.per_width = parse(&arg, &value)?,
+            "--max-rows" => options.max_rows = Some(parse(&arg, &value)?),
+            "--seed" => options.seed = parse(&arg, &value)?,
+            "--max-jumps" => options.max_jumps = parse(&arg, &value)?,
+            "-e" => options.evaluators.extend(builtin_evaluators(&value)?),
+            "--format" => {
+                csv = match value.as_str() {
+                    "jsonl" => false,
+                    "csv" => true,
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("unknown format `{value}`; expected `jsonl` or `csv`"),
+                        ))
+                    }
+                }
+            }
+            "--output" => {
+                output.replace(value);
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown argument {arg}"),
+                ))
+            }
+        }
+    }
+
+    let rows = dataset::generate(&options);
+
+    let writer: Box<dyn Write> = match output.as_deref() {
+        None | Some("-") => Box::new(io::stdout().lock()),
+        Some(path) => Box::new(File::create(path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("error while creating the dataset file `{path}`: {e}"),
+            )
+        })?),
+    };
+    let writer = BufWriter::new(writer);
+
+    if csv {
+        dataset::write_csv(&rows, writer)
+    } else {
+        dataset::write_jsonl(&rows, writer)
+    }
+}
+
+fn parse<T>(arg: &str, value: &str) -> io::Result<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    value.parse().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid value `{value}` for the argument {arg}: {e}"),
+        )
+    })
+}
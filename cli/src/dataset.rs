@@ -0,0 +1,287 @@
+//! Generation of labeled search data for move-ordering experiments.
+//!
+//! Every row is a candidate move scored by the solver, with the queens of the board it was
+//! scored on, its [`features`], and the eventual outcome of its subtree, as recorded by
+//! [`Solver::record_tree`].
+
+use crate::Builtin;
+use reginae_solver::{
+    features::{self, Features, FEATURE_NAMES},
+    Board, NodeResult, Solver,
+};
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    ops::RangeInclusive,
+};
+
+/// The settings of a dataset.
+#[derive(Debug, Clone)]
+pub struct DatasetOptions {
+    /// The widths of the solved boards.
+    pub widths: RangeInclusive<usize>,
+    /// The maximum number of rows sampled from the solves of every width.
+    pub per_width: usize,
+    /// The maximum number of rows of the whole dataset.
+    pub max_rows: Option<usize>,
+    /// The seed of the sampling of the rows.
+    pub seed: u64,
+    /// The jump budget of every solve.
+    pub max_jumps: usize,
+    pub evaluators: Vec<(Builtin, f64)>,
+}
+
+impl Default for DatasetOptions {
+    fn default() -> Self {
+        Self {
+            widths: 4..=8,
+            per_width: 1000,
+            max_rows: None,
+            seed: 0,
+            max_jumps: 10_000,
+            evaluators: Vec::new(),
+        }
+    }
+}
+
+/// The eventual outcome of the subtree of a candidate move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowOutcome {
+    /// The move is part of the found solution.
+    Solution,
+    /// Every path of the move was exhausted without a solution.
+    DeadEnd,
+    /// The move wasn't fully explored, either because a solution was found first or because the
+    /// budget was depleted.
+    Unexplored,
+}
+
+impl RowOutcome {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Solution => "solution",
+            Self::DeadEnd => "dead_end",
+            Self::Unexplored => "unexplored",
+        }
+    }
+}
+
+/// A candidate move scored by the solver.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Row {
+    pub width: usize,
+    /// The sorted queens of the board the candidate was scored on.
+    pub queens: Vec<usize>,
+    pub candidate: usize,
+    /// The weighted score of the evaluators for the candidate.
+    pub score: u64,
+    /// The features of the candidate on the board of the queens; see [`features::extract`].
+    pub features: Features,
+    pub outcome: RowOutcome,
+}
+
+/// Solves the boards with a queen on every column of the first row for every width, sampling
+/// the scored candidates with the seed of the options.
+pub fn generate(options: &DatasetOptions) -> Vec<Row> {
+    let mut rng = SplitMix64(options.seed);
+    let mut rows = Vec::new();
+    for width in options.widths.clone() {
+        let mut candidates = (0..width)
+            .flat_map(|column| solve_rows(options, width, column))
+            .collect::<Vec<_>>();
+        sample(&mut candidates, options.per_width, &mut rng);
+        rows.extend(candidates);
+    }
+    if let Some(max) = options.max_rows {
+        rows.truncate(max);
+    }
+    rows
+}
+
+/// Writes the rows as JSON Lines.
+pub fn write_jsonl<W: Write>(rows: &[Row], mut writer: W) -> io::Result<()> {
+    for row in rows {
+        serde_json::to_writer(&mut writer, row).map_err(io::Error::other)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Writes the rows as CSV, with a header, the queens separated by spaces, and a column per
+/// feature.
+pub fn write_csv<W: Write>(rows: &[Row], mut writer: W) -> io::Result<()> {
+    writeln!(
+        writer,
+        "width,queens,candidate,score,{},outcome",
+        FEATURE_NAMES.join(",")
+    )?;
+    for row in rows {
+        let queens = row
+            .queens
+            .iter()
+            .map(|q| q.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let features = row
+            .features
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+            writer,
+            "{},{queens},{},{},{features},{}",
+            row.width,
+            row.candidate,
+            row.score,
+            row.outcome.as_str()
+        )?;
+    }
+    writer.flush()
+}
+
+/// Solves the board with a queen on the given column of the first row, labeling every recorded
+/// candidate by the outcome of its subtree.
+fn solve_rows(options: &DatasetOptions, width: usize, column: usize) -> Vec<Row> {
+    let mut solver = Solver::default();
+    for (f, weight) in &options.evaluators {
+        solver.with_evaluator(*f, *weight);
+    }
+
+    // the jump budget bounds the tree, so it is never truncated
+    solver
+        .record_tree(true)
+        .with_tree_cap(usize::MAX)
+        .with_max_jumps(options.max_jumps);
+
+    let mut board = Board::new(width);
+    board.toggle(column);
+    let preset = board.sorted_queens().collect::<Vec<_>>();
+
+    let Some(tree) = solver.solve(board).tree else {
+        return Vec::new();
+    };
+    let nodes = tree.nodes();
+
+    // the children are recorded after their parents, so the outcomes are resolved backwards. an
+    // expanded node is a dead end only if all its children are, as the children cut by the
+    // budget are never expanded
+    let mut outcomes = vec![RowOutcome::Unexplored; nodes.len()];
+    let mut exhausted = vec![true; nodes.len()];
+    for (id, node) in nodes.iter().enumerate().rev() {
+        outcomes[id] = match node.result {
+            NodeResult::Solved => RowOutcome::Solution,
+            NodeResult::Depleted => RowOutcome::DeadEnd,
+            NodeResult::Expanded if exhausted[id] => RowOutcome::DeadEnd,
+            NodeResult::Expanded | NodeResult::Pruned => RowOutcome::Unexplored,
+        };
+        if let Some(parent) = node.parent {
+            exhausted[parent] &= outcomes[id] == RowOutcome::DeadEnd;
+        }
+    }
+    if let Some(mut id) = nodes.iter().position(|n| n.result == NodeResult::Solved) {
+        while let Some(parent) = nodes[id].parent {
+            outcomes[parent] = RowOutcome::Solution;
+            id = parent;
+        }
+    }
+
+    // the root is the preset board, so it isn't a candidate
+    nodes
+        .iter()
+        .zip(outcomes)
+        .filter_map(|(node, outcome)| {
+            let mut queens = preset.clone();
+            let mut parent = node.parent?;
+            while let Some(p) = nodes[parent].parent {
+                queens.push(nodes[parent].index);
+                parent = p;
+            }
+            queens.sort_unstable();
+            let mut board = Board::new(width);
+            queens.iter().for_each(|q| {
+                board.toggle(*q);
+            });
+            Some(Row {
+                width,
+                features: features::extract(&board, node.index),
+                queens,
+                candidate: node.index,
+                score: node.score,
+                outcome,
+            })
+        })
+        .collect()
+}
+
+/// Keeps `count` random rows, preserving their order.
+fn sample(rows: &mut Vec<Row>, count: usize, rng: &mut SplitMix64) {
+    if rows.len() <= count {
+        return;
+    }
+
+    // partial Fisher-Yates shuffle of the indexes
+    let mut indexes = (0..rows.len()).collect::<Vec<_>>();
+    for i in 0..count {
+        let j = i + (rng.next() % (rows.len() - i) as u64) as usize;
+        indexes.swap(i, j);
+    }
+
+    let mut keep = vec![false; rows.len()];
+    indexes[..count].iter().for_each(|i| keep[*i] = true);
+    let mut keep = keep.into_iter();
+    rows.retain(|_| keep.next().unwrap_or(false));
+}
+
+/// A small deterministic generator, so the datasets are reproducible across platforms.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[test]
+fn solution_rows_lead_to_the_solution() {
+    let options = DatasetOptions {
+        widths: 6..=6,
+        per_width: usize::MAX,
+        ..Default::default()
+    };
+    let rows = solve_rows(&options, 6, 1);
+    assert!(rows.iter().any(|r| r.outcome == RowOutcome::DeadEnd));
+
+    // the solution rows form a single path, from the preset queen to a solved board
+    let mut solution = rows
+        .iter()
+        .filter(|r| r.outcome == RowOutcome::Solution)
+        .collect::<Vec<_>>();
+    solution.sort_by_key(|r| r.queens.len());
+    assert_eq!(solution.len(), 5);
+    for (depth, row) in solution.iter().enumerate() {
+        assert_eq!(row.queens.len(), depth + 1);
+        assert!(row.queens.contains(&1));
+    }
+
+    let last = solution.last().expect("the board is solvable");
+    let mut board = Board::new(6);
+    last.queens.iter().chain([&last.candidate]).for_each(|q| {
+        board.toggle(*q);
+    });
+    assert!(board.is_solved());
+
+    // a tight budget leaves the moves unexplored rather than dead ends
+    let options = DatasetOptions {
+        max_jumps: 2,
+        ..options
+    };
+    assert!(solve_rows(&options, 6, 1)
+        .iter()
+        .all(|r| r.outcome == RowOutcome::Unexplored));
+}
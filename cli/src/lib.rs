@@ -0,0 +1,100 @@
+//! Parsing of the board input and evaluators of the CLI, shared with the fuzz targets and the
+//! dataset generator.
+
+pub mod dataset;
+
+use reginae_solver::Board;
+use std::io;
+
+/// The widest board accepted as input, so the cells of the board are allocated only for the
+/// widths the search can handle.
+pub const MAX_WIDTH: usize = 1024;
+
+/// Parses the input as the compact text of a board, `width:queen,queen,...`, as by the
+/// [`std::str::FromStr`] of [`Board`]; a width alone is an empty board.
+///
+/// The width is checked against [`MAX_WIDTH`] before the board is built.
+pub fn parse_input(input: &str) -> io::Result<Board> {
+    // the malformed widths are reported by the parsing of the board
+    let width = input.split(':').next().and_then(|w| w.trim().parse::<usize>().ok());
+    if let Some(width) = width.filter(|w| *w > MAX_WIDTH) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the width of the board must be at most {MAX_WIDTH}, not {width}"),
+        ));
+    }
+
+    let board = input.parse::<Board>().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid board provided: {e}"),
+        )
+    })?;
+
+    if board.width() == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the width of the board must be positive".to_string(),
+        ));
+    }
+    Ok(board)
+}
+
+/// A built-in evaluator of `reginae_evaluators`.
+pub type Builtin = fn(&Board, usize) -> f64;
+
+/// Parses the weight of an evaluator.
+pub fn parse_weight(weight: &str) -> io::Result<f64> {
+    weight.parse::<f64>().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("failed parsing the weight: {e}"),
+        )
+    })
+}
+
+/// Parses a `name:weight,...` spec of built-in evaluators, with the weights defaulting to `1.0`.
+pub fn builtin_evaluators(spec: &str) -> io::Result<Vec<(Builtin, f64)>> {
+    spec.split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| {
+            let (name, weight) = match s.trim().split_once(':') {
+                Some((name, weight)) => (name, parse_weight(weight)?),
+                None => (s.trim(), 1.0),
+            };
+            let f = builtin(name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown evaluator `{name}`"),
+                )
+            })?;
+            Ok((f, weight))
+        })
+        .collect()
+}
+
+/// The built-in evaluator with the given name.
+pub fn builtin(name: &str) -> Option<Builtin> {
+    reginae_evaluators::by_name(name)
+}
+
+#[test]
+fn parse_input_works() {
+    assert_eq!(parse_input("8\n").unwrap(), Board::new(8));
+    assert_eq!(
+        parse_input(" 8: 1,\n11 ")
+            .unwrap()
+            .sorted_queens()
+            .collect::<Vec<_>>(),
+        vec![1, 11]
+    );
+    assert!(parse_input("").is_err());
+    assert!(parse_input("0").is_err());
+    assert!(parse_input("8:,1").is_err());
+    assert!(parse_input("99999999999999999999999").is_err());
+    assert!(parse_input("4294967296:").is_err());
+    assert!(parse_input(&format!("{}:", MAX_WIDTH + 1)).is_err());
+    assert_eq!(parse_input(&format!("{MAX_WIDTH}:")).unwrap().width(), MAX_WIDTH);
+    assert!(parse_input("4:16").is_err());
+    assert!(parse_input("4:1,1").is_err());
+}
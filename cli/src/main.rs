@@ -1,22 +1,196 @@
-use reginae_solver::{Board, Solution, Solver};
+use reginae_cli::{builtin_evaluators, parse_weight};
+use reginae_plugin_abi::{EvaluatorFn, Manifest, ManifestFn, VersionFn, ABI_VERSION};
+use reginae_solver::{
+    render::{self, RenderOptions},
+    Solution, Solver,
+};
 use std::{
     env,
-    io::{self, Read},
+    fs::{self, File},
+    io::{self, BufWriter, IsTerminal, Read, Write},
+    path::Path,
 };
 use tracing_subscriber::filter::EnvFilter;
 
+#[cfg(feature = "scripting")]
+mod script;
+
+#[cfg(feature = "wasm-plugins")]
+mod wasm_plugin;
+
 fn main() -> io::Result<()> {
     let mut libraries = Vec::new();
+    #[cfg(feature = "scripting")]
+    let mut scripts = Vec::new();
     let mut solver = Solver::default();
+    let mut dump_tree = None;
+    let mut render = None;
+    let mut print_board = false;
+    let mut json = false;
+
+    // the evaluators of the environment are applied only if none is provided as argument
+    let mut evaluators = false;
+
+    // colors are enabled only for terminals, or forced via `CLICOLOR_FORCE`, unless explicitly
+    // disabled via `NO_COLOR`
+    let forced = env::var_os("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty() && v != "0");
+    let mut ansi = (io::stderr().is_terminal() || forced)
+        && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty());
 
     // load dynamic libraries
     let mut args = env::args().skip(1);
     while let Some(arg) = args.next() {
-        if &arg != "-l" {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                format!("unknown argument {arg}"),
-            ));
+        match arg.as_str() {
+            "--no-color" => {
+                ansi = false;
+                continue;
+            }
+            "--max-jumps" => {
+                let value = args.next().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "a value must be provided to the max jumps argument".to_string(),
+                    )
+                })?;
+                let max_jumps = value.parse::<usize>().map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("invalid max jumps `{value}`: {e}"),
+                    )
+                })?;
+                solver.with_max_jumps(max_jumps);
+                continue;
+            }
+            "--output" => {
+                let value = args.next().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "a format must be provided to the output argument".to_string(),
+                    )
+                })?;
+                json = match value.as_str() {
+                    "text" => false,
+                    "json" => true,
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("unknown output format `{value}`, expected `text` or `json`"),
+                        ))
+                    }
+                };
+                continue;
+            }
+            "--board" => {
+                print_board = true;
+                continue;
+            }
+            "--dump-tree" => {
+                let path = args.next().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "a path must be provided to the tree dump argument".to_string(),
+                    )
+                })?;
+                solver.record_tree(true);
+                dump_tree.replace(path);
+                continue;
+            }
+            "--render" => {
+                let path = args.next().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "a path must be provided to the render argument".to_string(),
+                    )
+                })?;
+                render.replace(path);
+                continue;
+            }
+            "--events" => {
+                let path = args.next().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "a path, or `-` for stderr, must be provided to the events argument"
+                            .to_string(),
+                    )
+                })?;
+
+                let writer: Box<dyn Write + Send> = match path.as_str() {
+                    "-" => Box::new(io::stderr()),
+                    path => Box::new(File::create(path).map_err(|e| {
+                        io::Error::new(
+                            e.kind(),
+                            format!("error while creating the events file `{path}`: {e}"),
+                        )
+                    })?),
+                };
+
+                // one json object per line; flushed when the solver is dropped
+                let mut writer = BufWriter::new(writer);
+                solver.set_event_sink(move |event| {
+                    serde_json::to_writer(&mut writer, &event)
+                        .ok()
+                        .and_then(|_| writer.write_all(b"\n").ok());
+                });
+                continue;
+            }
+            "-e" => {
+                let value = args.next().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "a value must be provided to an evaluator argument".to_string(),
+                    )
+                })?;
+
+                for (f, weight) in builtin_evaluators(&value)? {
+                    solver.with_evaluator(f, weight);
+                }
+                evaluators = true;
+                continue;
+            }
+            "-l" => (),
+            #[cfg(feature = "wasm-plugins")]
+            "-w" => {
+                let value = args.next().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "a value must be provided to a wasm plugin argument".to_string(),
+                    )
+                })?;
+
+                let (path, weight) = path_and_weight(&value)?;
+                let evaluator = wasm_plugin::WasmEvaluator::load(path)?;
+                solver.with_evaluator_fn(
+                    move |board, last_move| evaluator.score(board, last_move),
+                    weight,
+                );
+                evaluators = true;
+                continue;
+            }
+            #[cfg(feature = "scripting")]
+            "--script" => {
+                let value = args.next().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "a value must be provided to a script argument".to_string(),
+                    )
+                })?;
+
+                let (path, weight) = path_and_weight(&value)?;
+                let evaluator = std::sync::Arc::new(script::ScriptEvaluator::load(path)?);
+                let e = std::sync::Arc::clone(&evaluator);
+                solver.with_evaluator_fn(move |board, last_move| e.score(board, last_move), weight);
+
+                // keep a handle so the errors of the script can be reported after the solve
+                scripts.push((path.to_string(), evaluator));
+                evaluators = true;
+                continue;
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown argument {arg}"),
+                ))
+            }
         }
 
         let value = args.next().ok_or_else(|| {
@@ -42,17 +216,7 @@ fn main() -> io::Result<()> {
             )
         })?;
 
-        let weight = parts
-            .next()
-            .map(|p| p.parse::<f64>())
-            .transpose()
-            .map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("failed parsing the weight: {e}"),
-                )
-            })?
-            .unwrap_or(0.0);
+        let weight = parts.next().map(parse_weight).transpose()?;
 
         let lib = unsafe {
             libloading::Library::new(path).map_err(|e| {
@@ -63,36 +227,38 @@ fn main() -> io::Result<()> {
             })?
         };
 
-        let function: libloading::Symbol<fn(&Board, usize) -> f64> = unsafe {
-            lib.get(function.as_bytes()).map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("error while finding the function symbol name: {e}"),
-                )
-            })?
-        };
+        let (evaluator, default_weight) = plugin_evaluator(&lib, path, function)?;
+        let weight = weight.unwrap_or(default_weight);
 
-        solver.with_evaluator(*function, weight);
+        // safety: the library is kept loaded until the end of the execution
+        unsafe {
+            solver.with_plugin_evaluator(evaluator, weight);
+        }
 
         // avoid dropping the library so the function pointer will be valid until execution
         libraries.push(lib);
+        evaluators = true;
+    }
+
+    if !evaluators {
+        if let Some(spec) = env::var_os("REGINAE_EVALUATORS") {
+            let spec = spec.into_string().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the `REGINAE_EVALUATORS` variable isn't valid unicode".to_string(),
+                )
+            })?;
+
+            for (f, weight) in builtin_evaluators(&spec)? {
+                solver.with_evaluator(f, weight);
+            }
+        }
     }
 
     let mut input = String::new();
 
     io::stdin().read_to_string(&mut input)?;
-    input.retain(|c| c.is_ascii_digit() || c == ',');
-    let mut inputs = input.split(',');
-
-    let width = inputs
-        .next()
-        .expect("no width provided")
-        .parse::<usize>()
-        .expect("invalid width provided");
-    let queens = inputs
-        .map(|i| i.parse::<usize>())
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let board = reginae_cli::parse_input(&input)?;
 
     let filter = match env::var_os("RUST_LOG") {
         Some(_) => EnvFilter::try_from_default_env().expect("Invalid `RUST_LOG` provided"),
@@ -102,26 +268,127 @@ fn main() -> io::Result<()> {
     tracing_subscriber::fmt::Subscriber::builder()
         .with_writer(io::stderr)
         .with_env_filter(filter)
-        .with_ansi(true)
+        .with_ansi(ansi)
         .with_level(true)
         .with_line_number(true)
         .init();
 
-    let mut board = Board::new(width);
-    queens.into_iter().for_each(|q| {
-        board.toggle(q);
-    });
+    let solution = solver.solve(board);
+
+    if let Some(path) = dump_tree {
+        fs::write(&path, solution.tree_dot()).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("error while writing the search tree to `{path}`: {e}"),
+            )
+        })?;
+    }
+
+    if let Some(path) = render {
+        // the format is picked from the extension, defaulting to svg
+        let options = RenderOptions::default();
+        let image = match Path::new(&path).extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("png") => render::to_png(&solution.board, &options),
+            _ => render::to_svg(&solution.board, &options).into_bytes(),
+        };
+        fs::write(&path, image).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("error while writing the board image to `{path}`: {e}"),
+            )
+        })?;
+    }
+
+    #[cfg(feature = "scripting")]
+    for (path, script) in &scripts {
+        if let Some(e) = script.take_error() {
+            return Err(io::Error::other(format!(
+                "error while evaluating the script `{path}`: {e}"
+            )));
+        }
+    }
+
+    // the solution as serialized by `reginae_solver`, on a single line
+    if json {
+        let line = serde_json::to_string(&solution).map_err(io::Error::other)?;
+        println!("{line}");
+        return Ok(());
+    }
 
     let Solution {
         board,
         success,
+        interrupted,
         jumps,
-    } = solver.solve(board);
+        ..
+    } = solution;
 
+    // the budget was hit before the board was either solved or exhausted
+    let interrupted = if interrupted { " (interrupted)" } else { "" };
     println!(
-        "{success} with {jumps} jumps: {:?}",
+        "{success} with {jumps} jumps{interrupted}: {:?}",
         board.sorted_queens().collect::<Vec<_>>().as_slice()
     );
+    if print_board {
+        print!("{board:#}");
+    }
 
     Ok(())
 }
+
+/// Parses a `path:weight` argument, with the weight defaulting to `1.0`.
+#[cfg(any(feature = "scripting", feature = "wasm-plugins"))]
+fn path_and_weight(value: &str) -> io::Result<(&str, f64)> {
+    match value.rsplit_once(':') {
+        Some((path, weight)) => Ok((path, parse_weight(weight)?)),
+        None => Ok((value, 1.0)),
+    }
+}
+
+/// Finds an evaluator of a plugin and its default weight, refusing libraries built against
+/// another ABI version.
+fn plugin_evaluator(
+    lib: &libloading::Library,
+    path: &str,
+    function: &str,
+) -> io::Result<(EvaluatorFn, f64)> {
+    let version: libloading::Symbol<VersionFn> =
+        unsafe { lib.get(reginae_plugin_abi::VERSION_SYMBOL) }.map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("the library `{path}` isn't a reginae plugin: {e}"),
+            )
+        })?;
+
+    let version = unsafe { version() };
+    if version != ABI_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "the library `{path}` was built against the plugin ABI version {version}, but \
+                 this binary expects version {ABI_VERSION}; rebuild it with a matching \
+                 `reginae-plugin-abi`"
+            ),
+        ));
+    }
+
+    let manifest: libloading::Symbol<ManifestFn> =
+        unsafe { lib.get(reginae_plugin_abi::MANIFEST_SYMBOL) }.map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("error while finding the plugin manifest: {e}"),
+            )
+        })?;
+
+    // safety: the version was checked, so the manifest has the expected layout
+    let manifest: &Manifest = unsafe { &*manifest() };
+
+    let entry = unsafe { manifest.entry(function) }.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the library `{path}` doesn't export the evaluator `{function}`"),
+        )
+    })?;
+
+    Ok((entry.evaluator, entry.default_weight))
+}
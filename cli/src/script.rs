@@ -0,0 +1,149 @@
+use reginae_solver::Board;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, ParseError, Scope, AST, INT};
+use std::{
+    fs, io,
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// Maximum number of operations of a single evaluation, so a runaway script can't hang the solve.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// An evaluator implemented as a `rhai` script.
+///
+/// The script is evaluated with the variables `width`, `last_move`, and `queens`, and the
+/// functions `is_queen(i)`, `is_attacked(i)`, `row(i)`, and `col(i)`. It must return a float.
+pub struct ScriptEvaluator {
+    engine: Engine,
+    ast: AST,
+    board: Arc<RwLock<Board>>,
+    error: Mutex<Option<String>>,
+}
+
+impl ScriptEvaluator {
+    pub fn load(path: &str) -> io::Result<Self> {
+        let script = fs::read_to_string(path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("error while reading the script `{path}`: {e}"),
+            )
+        })?;
+
+        Self::compile(&script).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("error while compiling the script `{path}`: {e}"),
+            )
+        })
+    }
+
+    pub fn compile(script: &str) -> Result<Self, ParseError> {
+        let board = Arc::new(RwLock::new(Board::new(0)));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        let b = Arc::clone(&board);
+        engine.register_fn("is_queen", move |i: INT| {
+            let board = b.read().unwrap_or_else(|e| e.into_inner());
+            cell(&board, i).is_some_and(|c| c.is_queen())
+        });
+        let b = Arc::clone(&board);
+        engine.register_fn("is_attacked", move |i: INT| {
+            let board = b.read().unwrap_or_else(|e| e.into_inner());
+            cell(&board, i).is_some_and(|c| c.is_attacked())
+        });
+        let b = Arc::clone(&board);
+        engine.register_fn("row", move |i: INT| {
+            let board = b.read().unwrap_or_else(|e| e.into_inner());
+            i / board.width().max(1) as INT
+        });
+        let b = Arc::clone(&board);
+        engine.register_fn("col", move |i: INT| {
+            let board = b.read().unwrap_or_else(|e| e.into_inner());
+            i % board.width().max(1) as INT
+        });
+
+        let ast = engine.compile(script)?;
+
+        Ok(Self {
+            engine,
+            ast,
+            board,
+            error: Mutex::new(None),
+        })
+    }
+
+    /// Scores the board, falling back to `0.0` and recording the error if the script fails.
+    pub fn score(&self, board: &Board, last_move: usize) -> f64 {
+        self.evaluate(board, last_move).unwrap_or_else(|e| {
+            self.error
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get_or_insert_with(|| e.to_string());
+            0.0
+        })
+    }
+
+    /// Takes the first error raised by the script, if any.
+    pub fn take_error(&self) -> Option<String> {
+        self.error.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+
+    fn evaluate(&self, board: &Board, last_move: usize) -> Result<f64, Box<EvalAltResult>> {
+        *self.board.write().unwrap_or_else(|e| e.into_inner()) = board.clone();
+
+        let queens: Array = board
+            .sorted_queens()
+            .map(|q| Dynamic::from(q as INT))
+            .collect();
+
+        let mut scope = Scope::new();
+        scope.push("width", board.width() as INT);
+        scope.push("last_move", last_move as INT);
+        scope.push("queens", queens);
+
+        self.engine.eval_ast_with_scope(&mut scope, &self.ast)
+    }
+}
+
+fn cell(board: &Board, index: INT) -> Option<reginae_solver::Cell> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|i| board.cells().nth(i))
+        .copied()
+}
+
+#[test]
+fn center_script_matches_native_scores() {
+    fn center(board: &Board, last_move: usize) -> f64 {
+        let middle = (board.width() - 1) as f64 / 2.0;
+        let row = (last_move / board.width()) as f64;
+        let column = (last_move % board.width()) as f64;
+        1.0 - ((row - middle).abs() + (column - middle).abs()) / (2.0 * middle)
+    }
+
+    let script = ScriptEvaluator::compile(
+        r#"
+        let middle = (width - 1) / 2.0;
+        let distance = abs(row(last_move) - middle) + abs(col(last_move) - middle);
+        1.0 - distance / (2.0 * middle)
+        "#,
+    )
+    .expect("the script is valid");
+
+    let mut board = Board::new(8);
+    for index in [0, 10, 27, 63] {
+        board.toggle(index);
+        assert_eq!(script.score(&board, index), center(&board, index));
+    }
+    assert!(script.take_error().is_none());
+}
+
+#[test]
+fn script_errors_are_reported() {
+    assert!(ScriptEvaluator::compile("let = ;").is_err());
+
+    let runaway = ScriptEvaluator::compile("loop { } 1.0").expect("the script is valid");
+    assert_eq!(runaway.score(&Board::new(4), 0), 0.0);
+    assert!(runaway.take_error().is_some());
+    assert!(runaway.take_error().is_none());
+}
@@ -0,0 +1,68 @@
+use reginae_plugin_abi::wasm;
+use reginae_solver::Board;
+use std::{io, sync::Mutex};
+use wasmtime::{Config, Engine, Instance, Memory, Module, Store, TypedFunc};
+
+/// Fuel granted to every evaluation, so a buggy plugin can't hang the solve.
+const FUEL_PER_CALL: u64 = 10_000_000;
+
+/// An evaluator plugin compiled to WebAssembly, instantiated in a sandbox.
+pub struct WasmEvaluator {
+    store: Mutex<Store<()>>,
+    memory: Memory,
+    buffer: TypedFunc<u32, u32>,
+    evaluate: TypedFunc<u32, f64>,
+}
+
+impl WasmEvaluator {
+    pub fn load(path: &str) -> io::Result<Self> {
+        let invalid = |e: wasmtime::Error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("error while loading the wasm plugin `{path}`: {e}"),
+            )
+        };
+
+        let engine = Engine::new(Config::new().consume_fuel(true)).map_err(invalid)?;
+        let module = Module::from_file(&engine, path).map_err(invalid)?;
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[]).map_err(invalid)?;
+
+        let memory = instance.get_memory(&mut store, "memory").ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("the wasm plugin `{path}` doesn't export its memory"),
+            )
+        })?;
+        let buffer = instance
+            .get_typed_func(&mut store, wasm::BUFFER_EXPORT)
+            .map_err(invalid)?;
+        let evaluate = instance
+            .get_typed_func(&mut store, wasm::EVALUATE_EXPORT)
+            .map_err(invalid)?;
+
+        Ok(Self {
+            store: Mutex::new(store),
+            memory,
+            buffer,
+            evaluate,
+        })
+    }
+
+    /// Scores the board, falling back to `0.0` if the plugin traps or runs out of fuel.
+    pub fn score(&self, board: &Board, last_move: usize) -> f64 {
+        self.evaluate(board, last_move).unwrap_or(0.0)
+    }
+
+    fn evaluate(&self, board: &Board, last_move: usize) -> wasmtime::Result<f64> {
+        let mut store = self.store.lock().unwrap_or_else(|e| e.into_inner());
+        store.set_fuel(FUEL_PER_CALL)?;
+
+        let mut encoded = vec![0; wasm::encoded_len(board.sorted_queens().count())];
+        wasm::encode(board.width(), board.sorted_queens(), &mut encoded);
+
+        let offset = self.buffer.call(&mut *store, encoded.len() as u32)?;
+        self.memory.write(&mut *store, offset as usize, &encoded)?;
+        self.evaluate.call(&mut *store, last_move as u32)
+    }
+}
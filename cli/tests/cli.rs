@@ -0,0 +1,281 @@
+use std::{
+    env::{
+        self,
+        consts::{DLL_PREFIX, DLL_SUFFIX},
+    },
+    io::{ErrorKind, Write},
+    process::{Command, Output, Stdio},
+};
+
+fn run(args: &[&str], input: &str) -> Output {
+    run_with_env(args, &[], input)
+}
+
+fn run_with_env(args: &[&str], vars: &[(&str, &str)], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_reginae-cli"))
+        .args(args)
+        .env("RUST_LOG", "trace")
+        .env_remove("REGINAE_EVALUATORS")
+        .env_remove("NO_COLOR")
+        .env_remove("CLICOLOR_FORCE")
+        .envs(vars.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn the cli");
+
+    // the cli may fail on its arguments before reading stdin, closing the pipe
+    let written = child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input.as_bytes());
+    if let Err(e) = written {
+        assert_eq!(
+            e.kind(),
+            ErrorKind::BrokenPipe,
+            "failed to write to stdin: {e}"
+        );
+    }
+
+    child.wait_with_output().expect("failed to wait the cli")
+}
+
+#[test]
+fn no_color_disables_escape_sequences() {
+    // the pipes aren't terminals, so the colors are forced for the flags to have an effect
+    let forced = [("CLICOLOR_FORCE", "1")];
+    let output = run_with_env(&[], &forced, "5");
+    assert!(output.status.success());
+    assert!(output.stderr.contains(&0x1b));
+
+    let output = run_with_env(&["--no-color"], &forced, "5");
+    assert!(output.status.success());
+    assert!(!output.stdout.contains(&0x1b));
+    assert!(!output.stderr.contains(&0x1b));
+
+    let output = run_with_env(&[], &[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")], "5");
+    assert!(output.status.success());
+    assert!(!output.stdout.contains(&0x1b));
+    assert!(!output.stderr.contains(&0x1b));
+
+    // without a terminal, the colors are off by default
+    let output = run(&[], "5");
+    assert!(!output.stderr.contains(&0x1b));
+}
+
+#[test]
+fn plugin_evaluators_are_loaded() {
+    let deps = env::current_exe()
+        .expect("the test binary has a path")
+        .parent()
+        .expect("the test binary is in the deps dir")
+        .to_path_buf();
+    let lib = deps.join(format!("{DLL_PREFIX}reginae_evaluators{DLL_SUFFIX}"));
+    let lib = lib.to_str().expect("the target dir is valid utf-8");
+
+    let output = run(&["-l", &format!("{lib}:overlapping:10")], "8");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("true"));
+
+    let output = run(&["-l", &format!("{lib}:unknown")], "8");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("doesn't export the evaluator"));
+}
+
+#[test]
+fn template_plugin_is_loaded() {
+    let deps = env::current_exe()
+        .expect("the test binary has a path")
+        .parent()
+        .expect("the test binary is in the deps dir")
+        .to_path_buf();
+    let lib = deps.join(format!(
+        "{DLL_PREFIX}reginae_evaluator_template{DLL_SUFFIX}"
+    ));
+    let lib = lib.to_str().expect("the target dir is valid utf-8");
+
+    // the weight defaults to the one of the manifest
+    let default = run(&["-l", &format!("{lib}:centrality")], "8");
+    let explicit = run(&["-l", &format!("{lib}:centrality:1.0")], "8");
+    assert!(
+        default.status.success(),
+        "{}",
+        String::from_utf8_lossy(&default.stderr)
+    );
+    assert!(String::from_utf8_lossy(&default.stdout).starts_with("true"));
+    assert_eq!(default.stdout, explicit.stdout);
+    assert_ne!(default.stdout, run(&[], "8").stdout);
+}
+
+#[test]
+fn dump_tree_writes_dot() {
+    let path = env::temp_dir().join(format!("reginae-tree-{}.dot", std::process::id()));
+    let output = run(&["--dump-tree", path.to_str().expect("valid path")], "5");
+    assert!(output.status.success());
+
+    let dot = std::fs::read_to_string(&path).expect("the tree is written");
+    std::fs::remove_file(&path).ok();
+    assert!(dot.starts_with("digraph search {"));
+    assert_eq!(dot.matches("fillcolor=green").count(), 1);
+}
+
+#[test]
+fn events_are_written_as_json_lines() {
+    let path = env::temp_dir().join(format!("reginae-events-{}.jsonl", std::process::id()));
+    let output = run(&["--events", path.to_str().expect("valid path")], "5");
+    assert!(output.status.success());
+
+    let events = std::fs::read_to_string(&path).expect("the events are written");
+    std::fs::remove_file(&path).ok();
+    let lines: Vec<_> = events.lines().collect();
+    assert!(lines.iter().all(|l| l.starts_with(r#"{"event":""#)));
+    assert!(lines[0].starts_with(r#"{"event":"node_entered""#));
+    assert!(lines[lines.len() - 1].starts_with(r#"{"event":"solved""#));
+}
+
+#[test]
+fn builtin_evaluators_are_selected_by_name() {
+    let output = run(&["-e", "overlapping:1.0"], "8");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("true"));
+
+    // the built-ins mix with the evaluators of the libraries
+    let deps = env::current_exe()
+        .expect("the test binary has a path")
+        .parent()
+        .expect("the test binary is in the deps dir")
+        .to_path_buf();
+    let lib = deps.join(format!("{DLL_PREFIX}reginae_evaluators{DLL_SUFFIX}"));
+    let lib = lib.to_str().expect("the target dir is valid utf-8");
+    let mixed = run(
+        &["-e", "overlapping:1.0", "-l", &format!("{lib}:ladder:5")],
+        "8",
+    );
+    assert!(mixed.status.success());
+    assert!(String::from_utf8_lossy(&mixed.stdout).starts_with("true"));
+
+    // the weights are parsed as the weights of the libraries
+    let flag = run(&["-e", "overlapping:heavy"], "8");
+    let library = run(&["-l", &format!("{lib}:overlapping:heavy")], "8");
+    assert!(!flag.status.success() && !library.status.success());
+    for output in [flag, library] {
+        assert!(String::from_utf8_lossy(&output.stderr).contains("failed parsing the weight"));
+    }
+}
+
+#[test]
+fn evaluators_are_read_from_the_environment() {
+    let plain = run(&[], "8");
+    let flags = run(&["-e", "overlapping:1.0"], "8");
+    let vars = run_with_env(&[], &[("REGINAE_EVALUATORS", "overlapping:1.0")], "8");
+    assert!(plain.status.success() && flags.status.success() && vars.status.success());
+    assert_ne!(plain.stdout, flags.stdout);
+    assert_eq!(flags.stdout, vars.stdout);
+
+    // the flags override the environment
+    let both = run_with_env(
+        &["-e", "overlapping:1.0"],
+        &[("REGINAE_EVALUATORS", "unknown:1.0")],
+        "8",
+    );
+    assert!(both.status.success());
+    assert_eq!(flags.stdout, both.stdout);
+
+    let unknown = run_with_env(&[], &[("REGINAE_EVALUATORS", "unknown:1.0")], "8");
+    assert!(!unknown.status.success());
+}
+
+#[test]
+fn render_writes_the_board_image() {
+    let path = env::temp_dir().join(format!("reginae-render-{}.svg", std::process::id()));
+    let output = run(&["--render", path.to_str().expect("valid path")], "5");
+    assert!(output.status.success());
+
+    let svg = std::fs::read_to_string(&path).expect("the image is written");
+    std::fs::remove_file(&path).ok();
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches("class=\"queen\"").count(), 5);
+}
+
+#[test]
+fn board_is_printed() {
+    let output = run(&["--board"], "5");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("valid utf-8");
+    let lines = stdout.lines().skip(1).collect::<Vec<_>>();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[5], "  a b c d e");
+    assert!(lines[..5].iter().all(|l| l.matches('Q').count() == 1));
+}
+
+#[test]
+fn max_jumps_interrupts_the_search() {
+    let output = run(&["--max-jumps", "5"], "20");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("valid utf-8");
+    assert!(
+        stdout.starts_with("false with 5 jumps (interrupted): "),
+        "{stdout}"
+    );
+
+    // the budget of a solvable search isn't reported
+    let output = run(&["--max-jumps", "1000"], "5");
+    let stdout = String::from_utf8(output.stdout).expect("valid utf-8");
+    assert!(stdout.starts_with("true with "), "{stdout}");
+    assert!(!stdout.contains("interrupted"));
+
+    assert!(!run(&["--max-jumps", "many"], "5").status.success());
+}
+
+#[test]
+fn json_output_is_stable() {
+    let output = run(&["--output", "json"], "5");
+    assert!(output.status.success());
+
+    // the field names are the interface of the scripts consuming the output
+    let stdout = String::from_utf8(output.stdout).expect("valid utf-8");
+    let value: serde_json::Value = serde_json::from_str(&stdout).expect("the output is json");
+    let keys = |value: &serde_json::Value| {
+        let mut keys = value
+            .as_object()
+            .expect("the value is an object")
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    };
+    assert_eq!(keys(&value), ["board", "jumps", "outcome", "path", "stats"]);
+    assert_eq!(keys(&value["board"]), ["queens", "width"]);
+    assert_eq!(
+        keys(&value["stats"]),
+        [
+            "book_solutions",
+            "canonical_visits",
+            "evaluator_misbehaviors",
+            "jumps"
+        ]
+    );
+    assert_eq!(value["outcome"], "solved");
+    assert_eq!(value["board"]["width"], 5);
+    assert_eq!(value["board"]["queens"].as_array().map(Vec::len), Some(5));
+    assert_eq!(value["stats"]["jumps"], value["jumps"]);
+
+    let output = run(&["--output", "json", "--max-jumps", "5"], "20");
+    let stdout = String::from_utf8(output.stdout).expect("valid utf-8");
+    let value: serde_json::Value = serde_json::from_str(&stdout).expect("the output is json");
+    assert_eq!(value["outcome"], "interrupted");
+
+    assert!(!run(&["--output", "yaml"], "5").status.success());
+}
@@ -0,0 +1,110 @@
+use serde_json::Value;
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_reginae-dataset"))
+        .args(args)
+        .output()
+        .expect("failed to spawn the dataset generator")
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).expect("the dataset is valid utf-8")
+}
+
+const ARGS: &[&str] = &[
+    "--widths",
+    "5-6",
+    "--per-width",
+    "30",
+    "--seed",
+    "7",
+    "-e",
+    "overlapping:10,ladder:5",
+];
+
+#[test]
+fn jsonl_rows_follow_the_schema() {
+    let rows = stdout(&run(ARGS));
+    let rows = rows
+        .lines()
+        .map(|l| serde_json::from_str::<Value>(l).expect("every line is a json object"))
+        .collect::<Vec<_>>();
+    assert_eq!(rows.len(), 60);
+
+    for row in &rows {
+        let width = row.get("width").and_then(Value::as_u64).expect("width");
+        let candidate = row
+            .get("candidate")
+            .and_then(Value::as_u64)
+            .expect("candidate");
+        let queens = row
+            .get("queens")
+            .and_then(Value::as_array)
+            .expect("queens")
+            .iter()
+            .map(|q| q.as_u64().expect("the queens are indexes"))
+            .collect::<Vec<_>>();
+
+        assert!(width == 5 || width == 6);
+        assert!(candidate < width * width);
+        assert!(!queens.is_empty() && !queens.contains(&candidate));
+        assert!(queens.iter().all(|q| *q < width * width));
+        assert!(row.get("score").and_then(Value::as_u64).is_some());
+        let features = row
+            .get("features")
+            .and_then(Value::as_array)
+            .expect("features");
+        assert_eq!(features.len(), 11);
+        assert!(features
+            .iter()
+            .all(|f| f.as_f64().is_some_and(|f| (0.0..=1.0).contains(&f))));
+        assert!(matches!(
+            row.get("outcome").and_then(Value::as_str),
+            Some("solution" | "dead_end" | "unexplored")
+        ));
+    }
+    assert_eq!(
+        rows.iter()
+            .filter(|r| r.get("width").and_then(Value::as_u64) == Some(5))
+            .count(),
+        30
+    );
+}
+
+#[test]
+fn the_sampling_is_reproducible() {
+    let first = stdout(&run(ARGS));
+    assert_eq!(first, stdout(&run(ARGS)));
+
+    let mut args = ARGS.to_vec();
+    args[5] = "8";
+    assert_ne!(first, stdout(&run(&args)));
+}
+
+#[test]
+fn csv_rows_are_bounded() {
+    let mut args = ARGS.to_vec();
+    args.extend(["--format", "csv", "--max-rows", "45"]);
+    let rows = stdout(&run(&args));
+    let mut lines = rows.lines();
+
+    let header = lines.next().expect("the header is written");
+    assert!(header.starts_with("width,queens,candidate,score,row,column,"));
+    assert!(header.ends_with(",attacked_free,outcome"));
+    let lines = lines.collect::<Vec<_>>();
+    assert_eq!(lines.len(), 45);
+    assert!(lines.iter().all(|l| l.split(',').count() == 16));
+}
+
+#[test]
+fn unknown_formats_are_rejected() {
+    let output = run(&["--format", "xml"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown format"));
+}
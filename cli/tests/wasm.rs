@@ -0,0 +1,61 @@
+#![cfg(feature = "wasm-plugins")]
+
+use std::{
+    env,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+#[test]
+fn wasm_plugin_solves_width_8() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("the cli is a workspace member")
+        .to_path_buf();
+    let target = root.join("target").join("wasm-plugin");
+
+    let status = Command::new(env!("CARGO"))
+        .current_dir(&root)
+        .args([
+            "build",
+            "-p",
+            "reginae-wasm-plugin",
+            "--release",
+            "--target",
+            "wasm32-unknown-unknown",
+            "--target-dir",
+        ])
+        .arg(&target)
+        .status()
+        .expect("failed to spawn cargo");
+    assert!(status.success(), "failed to build the wasm plugin");
+
+    let plugin = target
+        .join("wasm32-unknown-unknown")
+        .join("release")
+        .join("reginae_wasm_plugin.wasm");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_reginae-cli"))
+        .arg("-w")
+        .arg(format!("{}:10", plugin.display()))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn the cli");
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(b"8")
+        .expect("failed to write to stdin");
+
+    let output = child.wait_with_output().expect("failed to wait the cli");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("true"));
+}
@@ -1,32 +1,266 @@
-use crate::{vec, BTreeSet, Cell, Vec};
-use core::mem;
+use crate::{
+    vec, Arc, AttackDelta, BTreeSet, Cell, Compact, Direction, HypotheticalBoard,
+    InvariantViolation, LineIds, Lines, Piece, PlacementError, Position, ResizeError, String,
+    ToString, ToggleResult, Vec,
+};
+use core::{fmt, mem, ops::Index};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// A square board of queens.
+///
+/// With the `serde` feature, the board is serialized as its width and sorted queens, with its
+/// blocked cells, piece and topology flags unless they are the defaults; the attacks are
+/// recomputed on deserialization, and the costs of [`Board::with_costs`] are dropped.
+///
+/// The blocked cells can't hold a queen; on the boards of [`Board::with_cut_rays`], they also
+/// stop the attacks of the queens. The diagonals of the boards of [`Board::new_toroidal`] wrap
+/// around the edges, so they have no end for a block to cut, and the two topologies are
+/// exclusive. The rooks and bishops of [`Board::with_piece`] attack only some of the
+/// lines, and the amazons also attack the knight moves.
+///
+/// With the `bitboard` feature, the board also keeps its safe cells packed as a bit per cell,
+/// updated with the attacks, so [`Board::safe_cells`] scans a word at a time instead of a cell.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "BoardRepr", into = "BoardRepr")
+)]
 pub struct Board {
     cells: Vec<Cell>,
     queens: BTreeSet<usize>,
     width: usize,
+    /// The cost of every cell, or empty if the cells have no cost.
+    costs: Vec<f64>,
+    /// The lines of the cells, shared between the clones of the board.
+    lines: Arc<Lines>,
+    /// The keys of the queens combined by exclusive or; see [`Board::hash64`].
+    hash: u64,
+    /// Whether the blocked cells stop the attacks; see [`Board::with_cut_rays`].
+    cut_rays: bool,
+    /// Whether the lines wrap around the edges; see [`Board::new_toroidal`].
+    toroidal: bool,
+    /// The piece placed by the toggles; see [`Board::with_piece`].
+    piece: Piece,
+    /// The safe cells, with the cell `i` on the bit `i % 64` of the word `i / 64`.
+    #[cfg(feature = "bitboard")]
+    safe: Vec<u64>,
+}
+
+/// The parts of a board, as decomposed by [`Board::into_parts`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardParts {
+    pub width: usize,
+    /// The cells, with their queens, attacks and blocks.
+    pub cells: Vec<Cell>,
+    pub queens: BTreeSet<usize>,
+    /// The cost of every cell, or empty if the cells have no cost.
+    pub costs: Vec<f64>,
+    pub piece: Piece,
+    pub toroidal: bool,
+    pub cut_rays: bool,
+}
+
+impl BoardParts {
+    /// The parts of a plain board of queens, without costs.
+    pub fn new(width: usize, cells: Vec<Cell>, queens: BTreeSet<usize>) -> Self {
+        Self {
+            width,
+            cells,
+            queens,
+            costs: Vec::new(),
+            piece: Piece::Queen,
+            toroidal: false,
+            cut_rays: false,
+        }
+    }
 }
 
 impl Board {
+    /// Creates an empty board of queens.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cells of the width overflow the address space; see the [`core::str::FromStr`]
+    /// of the board for a fallible construction.
     pub fn new(width: usize) -> Self {
-        let cells = width * width;
+        let cells = cell_count(width).expect("the cells of the width fit the address space");
         let cells = vec![Cell::default(); cells];
         let queens = BTreeSet::new();
         Self {
             cells,
             queens,
             width,
+            costs: Vec::new(),
+            lines: Arc::new(Lines::new(width)),
+            hash: 0,
+            cut_rays: false,
+            toroidal: false,
+            piece: Piece::Queen,
+            #[cfg(feature = "bitboard")]
+            safe: safe_words(width * width),
+        }
+    }
+
+    /// Creates an empty board where every cell carries a cost, indexed as the cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of costs isn't the square of the width.
+    pub fn with_costs(width: usize, costs: Vec<f64>) -> Self {
+        assert_eq!(costs.len(), width * width);
+
+        let mut board = Self::new(width);
+        board.costs = costs;
+        board
+    }
+
+    /// Creates an empty board whose blocked cells stop the attacks of the queens, so the queens
+    /// on both sides of a block don't attack each other; see [`Board::block`].
+    pub fn with_cut_rays(width: usize) -> Self {
+        let mut board = Self::new(width);
+        board.cut_rays = true;
+        board
+    }
+
+    /// Whether the blocked cells stop the attacks; see [`Board::with_cut_rays`].
+    pub const fn cuts_rays(&self) -> bool {
+        self.cut_rays
+    }
+
+    /// Creates an empty board on a torus: the lines leaving an edge enter from the opposite one,
+    /// so every diagonal has the width of the board, as the rows and columns do.
+    pub fn new_toroidal(width: usize) -> Self {
+        let mut board = Self::new(width);
+        board.toroidal = true;
+        board
+    }
+
+    /// Whether the lines wrap around the edges; see [`Board::new_toroidal`].
+    pub const fn is_toroidal(&self) -> bool {
+        self.toroidal
+    }
+
+    /// Creates an empty board where the toggles place the given piece, such as the rooks
+    /// attacking only the rows and columns, or the amazons attacking the knight moves as well as
+    /// the lines; see [`Piece`].
+    ///
+    /// The queens of the board are the placed pieces, whatever they are, and a solution holds
+    /// [`Board::target_count`] of them.
+    pub fn with_piece(width: usize, piece: Piece) -> Self {
+        let mut board = Self::new(width);
+        board.piece = piece;
+        board
+    }
+
+    /// The piece placed by the toggles; see [`Board::with_piece`].
+    pub const fn piece(&self) -> Piece {
+        self.piece
+    }
+
+    /// Creates an empty board of the piece and topology, or `None` for a toroidal board cutting
+    /// the rays, as the wrapped lines have no end to cut.
+    fn with_topology(width: usize, piece: Piece, toroidal: bool, cut_rays: bool) -> Option<Self> {
+        if toroidal && cut_rays {
+            return None;
+        }
+        let mut board = Self::with_piece(width, piece);
+        board.toroidal = toroidal;
+        board.cut_rays = cut_rays;
+        Some(board)
+    }
+
+    /// The cells a knight move away from the index, if the piece of the board attacks them; see
+    /// [`Piece::Amazon`].
+    pub fn knight_targets(&self, index: usize) -> impl Iterator<Item = usize> {
+        let amazon = self.piece == Piece::Amazon;
+        knight_moves(self.width, index).filter(move |_| amazon)
+    }
+
+    /// The cost of the cell, or `0.0` if the board has no costs.
+    pub fn cost(&self, index: usize) -> f64 {
+        self.costs.get(index).copied().unwrap_or(0.0)
+    }
+
+    /// Whether the cells carry costs; see [`Board::with_costs`].
+    pub fn has_costs(&self) -> bool {
+        !self.costs.is_empty()
+    }
+
+    /// The total cost of the cells of the queens.
+    pub fn queens_cost(&self) -> f64 {
+        self.queens.iter().map(|q| self.cost(*q)).sum()
+    }
+
+    /// Decomposes the board into its parts; the cells carry the blocks, and the parts carry the
+    /// costs, the piece and the topology, so the board is reassembled as it was.
+    pub fn into_parts(self) -> BoardParts {
+        BoardParts {
+            width: self.width,
+            cells: self.cells,
+            queens: self.queens,
+            costs: self.costs,
+            piece: self.piece,
+            toroidal: self.toroidal,
+            cut_rays: self.cut_rays,
         }
     }
 
+    /// Reassembles a board from its parts, as produced by [`Board::into_parts`], validating the
+    /// cells against the queens, the piece and the topology.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of cells, or of costs if any, isn't the square of the width, or if
+    /// the parts are both toroidal and cutting the rays.
+    pub fn from_parts(parts: BoardParts) -> Result<Self, InvariantViolation> {
+        let BoardParts {
+            width,
+            cells,
+            queens,
+            costs,
+            piece,
+            toroidal,
+            cut_rays,
+        } = parts;
+        assert_eq!(cells.len(), width * width);
+        assert!(costs.is_empty() || costs.len() == width * width);
+        assert!(!(toroidal && cut_rays), "the toroidal boards can't cut the rays");
+
+        let lines = Lines::new(width);
+        let hash = queens.iter().fold(0, |hash, q| hash ^ lines.key(*q));
+        let mut board = Self {
+            cells,
+            queens,
+            width,
+            costs,
+            lines: Arc::new(lines),
+            hash,
+            cut_rays,
+            toroidal,
+            piece,
+            #[cfg(feature = "bitboard")]
+            safe: safe_words(width * width),
+        };
+        (0..board.cells.len()).for_each(|i| board.sync_safe(i));
+        board.validate_invariants()?;
+        Ok(board)
+    }
+
     pub const fn width(&self) -> usize {
         self.width
     }
 
+    /// The attack lines of the cells of the width, shared between the clones of the board.
+    pub fn lines(&self) -> &Lines {
+        &self.lines
+    }
+
     /// Traverses all the cells attacked by the given index, with the following order: horizontal,
     /// vertical, principal diagonal, antidiagonal.
     ///
+    /// The diagonals of a toroidal board are traversed from the index, wrapping around the
+    /// edges, so every cell of a line is traversed once.
+    ///
     /// # Example
     ///
     /// A board of width 8 will produce the following iterations for the index `0`:
@@ -35,28 +269,153 @@ impl Board {
     /// - principal: (0..=63).step_by(9)
     /// - antidiagonal: (0..=0)
     pub fn traverse_boundaries(&self, index: usize) -> impl Iterator<Item = (usize, &Cell)> {
-        let bounds = Boundaries::new(index, self.width);
-        (bounds.horizontal_min..=bounds.horizontal_max)
+        let bounds = self.bounds(index);
+        let along = |direction| self.piece.attacks(direction);
+        let flat = |direction| along(direction) && !self.toroidal;
+        let horizontal =
+            along(Direction::Horizontal).then_some(bounds.horizontal_min..=bounds.horizontal_max);
+        let vertical = along(Direction::Vertical)
+            .then(|| (bounds.vertical_min..=bounds.vertical_max).step_by(self.width));
+        let principal = flat(Direction::Principal)
+            .then(|| (bounds.principal_min..=bounds.principal_max).step_by(self.width + 1));
+        let antidiagonal = flat(Direction::Antidiagonal).then(|| {
+            (bounds.antidiagonal_min..=bounds.antidiagonal_max).step_by((self.width - 1).max(1))
+        });
+        let wrapped = |direction| {
+            (along(direction) && self.toroidal)
+                .then(|| wrapped_diagonal(self.width, index, direction))
+        };
+        horizontal
+            .into_iter()
+            .flatten()
+            .chain(vertical.into_iter().flatten())
+            .chain(principal.into_iter().flatten())
+            .chain(wrapped(Direction::Principal).into_iter().flatten())
+            .chain(antidiagonal.into_iter().flatten())
+            .chain(wrapped(Direction::Antidiagonal).into_iter().flatten())
             .map(|i| (i, &self.cells[i]))
-            .chain(
-                (bounds.vertical_min..=bounds.vertical_max)
-                    .step_by(self.width)
-                    .map(|i| (i, &self.cells[i])),
-            )
-            .chain(
-                (bounds.principal_min..=bounds.principal_max)
-                    .step_by(self.width + 1)
-                    .map(|i| (i, &self.cells[i])),
-            )
-            .chain(
-                (bounds.antidiagonal_min..=bounds.antidiagonal_max)
-                    .step_by(self.width - 1)
-                    .map(|i| (i, &self.cells[i])),
-            )
     }
 
+    /// Number of cells of the attack line with the given direction through the index, including
+    /// the index itself.
+    ///
+    /// Rows and columns always have the width of the board, while diagonals are shorter near the
+    /// edges, unless they wrap around them. The lines cut by the blocks end before them; see
+    /// [`Board::with_cut_rays`].
+    pub fn line_length(&self, index: usize, direction: Direction) -> usize {
+        if self.toroidal {
+            return self.width;
+        }
+        if self.cut_rays {
+            let bounds = self.bounds(index);
+            let width = self.width;
+            return match direction {
+                Direction::Horizontal => bounds.horizontal_max - bounds.horizontal_min,
+                Direction::Vertical => (bounds.vertical_max - bounds.vertical_min) / width,
+                Direction::Principal => (bounds.principal_max - bounds.principal_min) / (width + 1),
+                Direction::Antidiagonal => {
+                    (bounds.antidiagonal_max - bounds.antidiagonal_min) / (width - 1).max(1)
+                }
+            } + 1;
+        }
+
+        let last = self.width - 1;
+        let LineIds { row, column, .. } = self.lines.line_ids(index);
+        match direction {
+            Direction::Horizontal | Direction::Vertical => self.width,
+            Direction::Principal => row.min(column) + (last - row).min(last - column) + 1,
+            Direction::Antidiagonal => row.min(last - column) + column.min(last - row) + 1,
+        }
+    }
+
+    /// Returns `true` if the board holds [`Board::target_count`] queens, one per row unless
+    /// they are bishops, and no queen attacks another; see [`Board::is_valid`].
     pub fn is_solved(&self) -> bool {
-        self.width == self.queens.len()
+        self.target_count() == self.queens.len() && self.is_valid()
+    }
+
+    /// The number of pieces of a solution: the width, or `2 * width - 2` bishops, as many as
+    /// the diagonals of a direction but one. The diagonals of a torus have the width of the
+    /// rows, so the torus holds as many bishops as the width.
+    pub fn target_count(&self) -> usize {
+        match self.piece {
+            Piece::Bishop if !self.toroidal && self.width > 1 => 2 * self.width - 2,
+            _ => self.width,
+        }
+    }
+
+    /// Returns `true` if no queen attacks another.
+    ///
+    /// The placements of the board never attack a queen, but the boards reassembled with
+    /// [`Board::from_parts`] may hold the conflicts of their source; see [`Board::conflicts`]
+    /// and [`Board::knight_conflicts`].
+    pub fn is_valid(&self) -> bool {
+        // a queen attacks its own cell once per direction of its piece, so a second attacker is
+        // a conflict
+        self.queens.iter().all(|q| {
+            Direction::ALL
+                .into_iter()
+                .all(|d| self.cells[*q].attackers(d) == u8::from(self.piece.attacks(d)))
+                && self.cells[*q].knight_attackers() == 0
+        })
+    }
+
+    /// The pairs of amazons attacking each other by a knight move, in ascending order.
+    pub fn knight_conflicts(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.queens.iter().flat_map(move |a| {
+            self.knight_targets(*a)
+                .filter(move |b| b > a && self.queens.contains(b))
+                .map(move |b| (*a, b))
+        })
+    }
+
+    /// The pairs of queens attacking each other, in ascending order, with the line they share.
+    pub fn conflicts(&self) -> impl Iterator<Item = (usize, usize, Direction)> + '_ {
+        self.queens.iter().enumerate().flat_map(move |(i, a)| {
+            self.queens.iter().skip(i + 1).filter_map(move |b| {
+                let direction = Direction::ALL
+                    .into_iter()
+                    .find(|d| self.attacks_along(*a, *b, *d))?;
+                Some((*a, *b, direction))
+            })
+        })
+    }
+
+    /// Whether a queen on the first cell attacks the second one along the line of the
+    /// direction, on the topology of the board and up to its blocks; a queen attacks its own
+    /// cell in every direction of its piece, and no cell in the others.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of the bounds of the board.
+    pub fn attacks_along(&self, queen: usize, index: usize, direction: Direction) -> bool {
+        let [a, b] = [queen, index].map(|i| self.line_keys(i));
+        let line = match direction {
+            Direction::Horizontal => 0,
+            Direction::Vertical => 1,
+            Direction::Principal => 2,
+            Direction::Antidiagonal => 3,
+        };
+        self.piece.attacks(direction)
+            && a[line] == b[line]
+            && (queen == index
+                || !self.cut_rays
+                || !self.cells[index].is_blocked() && !self.is_shielded(queen, index, direction))
+    }
+
+    /// The number of pairs of queens attacking each other; see [`Board::conflicts`] and
+    /// [`Board::knight_conflicts`].
+    pub fn conflict_count(&self) -> usize {
+        self.conflicts().count() + self.knight_conflicts().count()
+    }
+
+    /// The Zobrist hash of the queens: the random keys of their cells, drawn from a seed of the
+    /// width, combined by exclusive or.
+    ///
+    /// The hash is updated as the queens are placed and removed, so it's the same for the boards
+    /// of the same width and queens, however they were reached; the empty board hashes to `0`.
+    pub const fn hash64(&self) -> u64 {
+        self.hash
     }
 
     pub fn is_empty(&self) -> bool {
@@ -67,51 +426,584 @@ impl Board {
         self.cells[index].is_queen()
     }
 
+    /// The number of queens attacking the cell of the index; see [`Cell::attack_count`].
+    ///
+    /// The diagonals of a queen of an even torus cross again opposite the queen, where the queen
+    /// is counted once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of the bounds of the board.
+    pub fn attack_count(&self, index: usize) -> u8 {
+        let count = self.cells[index].attack_count(self.piece);
+        if !self.toroidal {
+            return count;
+        }
+        let crossing = self
+            .queens
+            .iter()
+            .filter(|q| {
+                **q != index
+                    && self.attacks_along(**q, index, Direction::Principal)
+                    && self.attacks_along(**q, index, Direction::Antidiagonal)
+            })
+            .count();
+        count.saturating_sub(u8::try_from(crossing).unwrap_or(u8::MAX))
+    }
+
+    /// The attack counts of every cell, in row-major order.
+    pub fn heatmap(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.cells.len()).map(|i| self.attack_count(i))
+    }
+
     pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
-        self.cells.chunks(self.width)
+        // an empty width has no cells, so no rows
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The cells of every column, from left to right, each from top to bottom.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &Cell>> {
+        // the range is empty for the empty width, so the step is never zero
+        (0..self.width).map(|column| self.cells.iter().skip(column).step_by(self.width))
+    }
+
+    /// The cells of the row, or `None` if it's out of the board.
+    pub fn row(&self, row: usize) -> Option<&[Cell]> {
+        (row < self.width).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// The cells of the column, from top to bottom, or `None` if it's out of the board.
+    pub fn column(&self, column: usize) -> Option<impl Iterator<Item = &Cell>> {
+        (column < self.width).then(|| self.cells.iter().skip(column).step_by(self.width))
     }
 
     pub fn sorted_queens(&self) -> impl Iterator<Item = usize> + '_ {
         self.queens.iter().copied()
     }
 
+    /// The compact text of the board, `width:queen,queen,...`, parsed back by its [`FromStr`].
+    ///
+    /// [`FromStr`]: core::str::FromStr
+    pub const fn compact(&self) -> Compact<'_> {
+        Compact::new(self)
+    }
+
+    /// Draws the board as its [`fmt::Display`], with the given glyphs of the queens, the
+    /// attacked cells, and the free cells.
+    pub fn render_with(&self, queen: char, attacked: char, free: char) -> String {
+        Rendering {
+            board: self,
+            glyphs: [queen, attacked, free],
+        }
+        .to_string()
+    }
+
+    /// Draws the rows with the glyphs of the queens, attacked and free cells, labelled in the
+    /// alternate form.
+    fn draw(&self, f: &mut fmt::Formatter<'_>, [queen, attacked, free]: [char; 3]) -> fmt::Result {
+        // the labels of the alternate form are aligned to the widest one
+        let labels = f.alternate();
+        let rank_width = digits(self.width);
+        let file_width = match self.width {
+            w if w <= 26 => 1,
+            w => digits(w),
+        };
+        let cell_width = if labels { file_width } else { 1 };
+
+        for (row, cells) in self.rows().enumerate() {
+            if labels {
+                write!(f, "{:>rank_width$} ", self.width - row)?;
+            }
+            for (column, cell) in cells.iter().enumerate() {
+                let glyph = match cell {
+                    c if c.is_queen() => queen,
+                    c if c.is_blocked() => '#',
+                    c if c.is_attacked() => attacked,
+                    _ => free,
+                };
+                if column > 0 {
+                    f.write_str(" ")?;
+                }
+                write!(f, "{glyph:>cell_width$}")?;
+            }
+            f.write_str("\n")?;
+        }
+
+        if labels {
+            write!(f, "{:rank_width$} ", "")?;
+            for column in 0..self.width {
+                if column > 0 {
+                    f.write_str(" ")?;
+                }
+                match u8::try_from(column) {
+                    Ok(c) if c < 26 => write!(f, "{:>cell_width$}", char::from(b'a' + c))?,
+                    _ => write!(f, "{:>cell_width$}", column + 1)?,
+                }
+            }
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+
+    /// The queens packed as a bit per cell, with the cell `i` on the bit `i % 64` of the word
+    /// `i / 64`, so the queens of boards of the same width are combined by word-wise operations.
+    pub fn queens_bitset(&self) -> Vec<u64> {
+        let mut bits = vec![0; self.cells.len().div_ceil(64)];
+        for q in &self.queens {
+            bits[q / 64] |= 1 << (q % 64);
+        }
+        bits
+    }
+
+    /// The column of the queen of every row, if every row has exactly one queen.
+    pub fn to_permutation(&self) -> Option<Vec<usize>> {
+        let mut permutation = vec![None; self.width];
+        for q in &self.queens {
+            match &mut permutation[q / self.width] {
+                Some(_) => return None,
+                column => *column = Some(q % self.width),
+            }
+        }
+        permutation.into_iter().collect()
+    }
+
+    /// Creates a board with the width of the permutation, placing a queen on the given column of
+    /// every row; the inverse of [`Board::to_permutation`].
+    pub fn from_permutation(permutation: &[usize]) -> Result<Self, PlacementError> {
+        let width = permutation.len();
+        let mut board = Self::new(width);
+        for (row, column) in permutation.iter().copied().enumerate() {
+            let index = row * width + column;
+            if column >= width {
+                return Err(PlacementError::OutOfBounds(index));
+            }
+            if !board.cells[index].is_free() {
+                return Err(PlacementError::Attacked(index));
+            }
+            board.put_queen(index);
+        }
+        Ok(board)
+    }
+
+    /// Creates a board of the width with a queen on every index, placed in order.
+    ///
+    /// Unlike [`Board::toggle`], an index that is attacked by the previous queens, or that
+    /// repeats one, fails instead of being ignored or removed; the error of an attacked index
+    /// names the first queen attacking it, and the shared line.
+    pub fn from_queens<I>(width: usize, queens: I) -> Result<Self, PlacementError>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        Self::new(width).placing(queens)
+    }
+
+    /// Places a queen on every index of the empty board, in order, as [`Board::from_queens`]
+    /// does.
+    fn placing<I>(mut self, queens: I) -> Result<Self, PlacementError>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        for index in queens {
+            match self.cells.get(index) {
+                Some(c) if c.is_free() => self.put_queen(index),
+                Some(c) if c.is_queen() => return Err(PlacementError::Duplicate(index)),
+                Some(_) => return Err(self.conflict(index)),
+                None => return Err(PlacementError::OutOfBounds(index)),
+            };
+        }
+        Ok(self)
+    }
+
     pub fn toggle_with_pair(&mut self, column: usize, row: usize) -> &mut Self {
         let index = row * self.width + column;
         self.toggle(index)
     }
 
+    /// Extracts the square region with the given width, starting at the top-left `(column, row)`
+    /// coordinate; the region is square, as every board is, so it takes a single width rather
+    /// than a height and a width.
+    ///
+    /// The queens, costs and blocks of the region are preserved, and so are the piece and the
+    /// topology of the board, with the attacks recomputed locally; attacks from queens outside
+    /// the region are dropped. The region of a toroidal board wraps around its own edges, so a
+    /// queen attacked across them is dropped as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region exceeds the bounds of the board.
+    pub fn subboard(&self, column: usize, row: usize, width: usize) -> Board {
+        assert!(column + width <= self.width && row + width <= self.width);
+
+        let region = (row..row + width)
+            .flat_map(|r| (column..column + width).map(move |c| r * self.width + c))
+            .collect::<Vec<_>>();
+        let mut board = Board::with_topology(width, self.piece, self.toroidal, self.cut_rays)
+            .expect("the board has a supported topology");
+        if self.has_costs() {
+            board.costs = region.iter().map(|i| self.costs[*i]).collect();
+        }
+        region
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| self.cells[**i].is_blocked())
+            .for_each(|(index, _)| {
+                board.block(index);
+            });
+        region
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| self.queens.contains(i))
+            .for_each(|(index, _)| {
+                board.toggle(index);
+            });
+        board
+    }
+
+    /// Checks every cell against the queens of the board, recomputing the queen and attack flags
+    /// from scratch.
+    pub fn validate_invariants(&self) -> Result<(), InvariantViolation> {
+        self.cells
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, found)| {
+                let mut expected = Cell::default();
+                if self.queens.contains(&index) {
+                    expected.put_queen();
+                }
+                // the blocks aren't derived from the queens, but a cut ray never reaches them
+                if found.is_blocked() {
+                    expected.block();
+                }
+                for q in self.queens.iter().copied() {
+                    if self.attacks_along(q, index, Direction::Horizontal) {
+                        expected.attack_horizontal();
+                    }
+                    if self.attacks_along(q, index, Direction::Vertical) {
+                        expected.attack_vertical();
+                    }
+                    if self.attacks_along(q, index, Direction::Principal) {
+                        expected.attack_principal();
+                    }
+                    if self.attacks_along(q, index, Direction::Antidiagonal) {
+                        expected.attack_antidiagonal();
+                    }
+                    if self.knight_targets(q).any(|t| t == index) {
+                        expected.attack_knight();
+                    }
+                }
+
+                match expected == *found {
+                    true => Ok(()),
+                    false => Err(InvariantViolation {
+                        index,
+                        expected,
+                        found: *found,
+                    }),
+                }
+            })
+    }
+
     pub fn clear(&mut self) -> &mut Self {
         #[cfg(feature = "tracing")]
         tracing::trace!("clearing board");
 
         self.cells.iter_mut().for_each(|c| {
-            c.clear();
+            c.lift_all();
         });
         self.queens.clear();
+        self.hash = 0;
+        self.sync_all_safe();
+        self
+    }
+
+    /// Clears the board and resizes it to the given width, reusing the allocation of the cells.
+    /// The costs and the blocks are dropped.
+    pub fn reset(&mut self, width: usize) -> &mut Self {
+        self.clear();
+        self.costs.clear();
+        self.cells.iter_mut().for_each(|c| {
+            c.unblock();
+        });
+        self.cells.resize(width * width, Cell::default());
+        #[cfg(feature = "bitboard")]
+        {
+            self.safe = safe_words(width * width);
+        }
+        if self.width != width {
+            self.lines = Arc::new(Lines::new(width));
+        }
+        self.width = width;
         self
     }
 
+    /// Resizes the board to the given width, keeping the queens and blocks whose row and column
+    /// still fit, and returns the dropped queens, as their indices of the former width.
+    ///
+    /// The board is unchanged on error.
+    pub fn resize(&mut self, width: usize) -> Result<Vec<usize>, ResizeError> {
+        if self.has_costs() {
+            return Err(ResizeError::Costs);
+        }
+
+        let former = self.width;
+        let blocked = self.blocked_cells().collect::<Vec<_>>();
+        let queens = self.take_queens();
+        self.reset(width);
+        for block in blocked {
+            if let Some(index) = self.index_of(block % former, block / former) {
+                self.cells[index].block();
+                self.sync_safe(index);
+            }
+        }
+
+        let mut dropped = Vec::new();
+        for queen in queens {
+            let (column, row) = (queen % former, queen / former);
+            match self.index_of(column, row) {
+                Some(index) if self.cells[index].is_free() => {
+                    self.put_queen(index);
+                }
+                _ => dropped.push(queen),
+            }
+        }
+        Ok(dropped)
+    }
+
     pub fn take_queens(&mut self) -> BTreeSet<usize> {
         #[cfg(feature = "tracing")]
         tracing::trace!("clearing board");
 
         self.cells.iter_mut().for_each(|c| {
-            c.clear();
+            c.lift_all();
         });
+        self.hash = 0;
+        self.sync_all_safe();
         mem::take(&mut self.queens)
     }
 
+    /// Rotates the queens of the board by a quarter turn, clockwise; the costs stay on their
+    /// cells.
+    pub fn rotate_clockwise(&mut self) -> &mut Self {
+        // an empty width has no queen to remap
+        let last = self.width.saturating_sub(1);
+        self.remap(|column, row| (last - row, column))
+    }
+
+    /// Rotates the queens of the board by a quarter turn, counterclockwise; the costs stay on
+    /// their cells.
+    pub fn rotate_counterclockwise(&mut self) -> &mut Self {
+        let last = self.width.saturating_sub(1);
+        self.remap(|column, row| (row, last - column))
+    }
+
+    /// Mirrors the queens of the board across the line of the given direction through its
+    /// center.
+    pub fn flip(&mut self, axis: Direction) -> &mut Self {
+        let last = self.width.saturating_sub(1);
+        match axis {
+            Direction::Horizontal => self.remap(|column, row| (column, last - row)),
+            Direction::Vertical => self.remap(|column, row| (last - column, row)),
+            Direction::Principal => self.remap(|column, row| (row, column)),
+            Direction::Antidiagonal => self.remap(|column, row| (last - row, last - column)),
+        }
+    }
+
+    /// Mirrors the queens across the principal diagonal, swapping their rows and columns.
+    pub fn transpose(&mut self) -> &mut Self {
+        self.flip(Direction::Principal)
+    }
+
+    /// Mirrors the queens from left to right, across the vertical axis.
+    pub fn mirror_horizontal(&mut self) -> &mut Self {
+        self.flip(Direction::Vertical)
+    }
+
+    /// Mirrors the queens from top to bottom, across the horizontal axis.
+    pub fn mirror_vertical(&mut self) -> &mut Self {
+        self.flip(Direction::Horizontal)
+    }
+
+    /// A copy of the board rotated clockwise by the given number of quarter turns.
+    pub fn rotated(&self, quarter_turns: usize) -> Board {
+        let mut board = self.clone();
+        for _ in 0..quarter_turns % 4 {
+            board.rotate_clockwise();
+        }
+        board
+    }
+
+    /// A copy of the board mirrored across the given axis; see [`Board::flip`].
+    pub fn flipped(&self, axis: Direction) -> Board {
+        let mut board = self.clone();
+        board.flip(axis);
+        board
+    }
+
+    /// The images of the board under the 8 symmetries of the square: the 4 rotations, followed
+    /// by the 4 rotations of the board mirrored across the vertical axis.
+    pub fn symmetries(&self) -> [Board; 8] {
+        let mirrored = self.flipped(Direction::Vertical);
+        [
+            self.clone(),
+            self.rotated(1),
+            self.rotated(2),
+            self.rotated(3),
+            mirrored.rotated(1),
+            mirrored.rotated(2),
+            mirrored.rotated(3),
+            mirrored,
+        ]
+    }
+
+    /// The smallest sorted queens of the symmetries of the board, shared by all the boards of its
+    /// orbit.
+    pub fn canonical_key(&self) -> Vec<usize> {
+        self.symmetries()
+            .iter()
+            .map(|b| b.sorted_queens().collect::<Vec<_>>())
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Whether the board is one of the symmetries of the other, as [`Board::symmetries`], so both
+    /// share a [`Board::canonical_key`].
+    pub fn eq_up_to_symmetry(&self, other: &Board) -> bool {
+        self.width == other.width
+            && self.queens.len() == other.queens.len()
+            && other.symmetries().iter().any(|b| b.queens == self.queens)
+    }
+
+    /// Whether the board is the canonical representative of its orbit, so exactly one board of
+    /// every set of symmetric boards is fundamental.
+    pub fn is_fundamental(&self) -> bool {
+        self.sorted_queens().eq(self.canonical_key())
+    }
+
+    /// The free cells that a queen placed on the cell would attack, without placing it; empty if
+    /// the cell isn't free.
+    pub fn attack_delta(&self, index: usize) -> AttackDelta {
+        if !self.cells[index].is_free() {
+            return AttackDelta::default();
+        }
+
+        // the lines are traversed in the order of the directions; the diagonals of an even torus
+        // cross again opposite the cell, where the cell is counted by the principal one
+        let mut cells = self.traverse_boundaries(index);
+        let mut free = |direction| {
+            if !self.piece.attacks(direction) {
+                return 0;
+            }
+            cells
+                .by_ref()
+                .take(self.line_length(index, direction))
+                .filter(|(i, c)| *i != index && c.is_free())
+                .filter(|(i, _)| {
+                    direction != Direction::Antidiagonal
+                        || !self.toroidal
+                        || !self.attacks_along(index, *i, Direction::Principal)
+                })
+                .count()
+        };
+        AttackDelta {
+            horizontal: free(Direction::Horizontal),
+            vertical: free(Direction::Vertical),
+            principal: free(Direction::Principal),
+            antidiagonal: free(Direction::Antidiagonal),
+            knight: self
+                .knight_targets(index)
+                .filter(|i| self.cells[*i].is_free())
+                .count(),
+        }
+    }
+
+    /// Answers the queries of `f` as if a queen was placed on the free cell, without mutating
+    /// the board; see [`HypotheticalBoard`].
+    pub fn with_hypothetical<F, R>(&self, index: usize, f: F) -> R
+    where
+        F: FnOnce(&HypotheticalBoard) -> R,
+    {
+        f(&HypotheticalBoard::new(self, index))
+    }
+
+    /// Whether the boards have the same cells on every row, unlike [`PartialEq`] ignoring the
+    /// costs of the cells.
+    pub fn rows_equal(&self, other: &Board) -> bool {
+        self.width == other.width && self.rows().eq(other.rows())
+    }
+
+    /// Moves every queen and block to the `(column, row)` mapped from its coordinate.
+    fn remap<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        let width = self.width;
+        let blocked = self.blocked_cells().collect::<Vec<_>>();
+        let queens = self.take_queens();
+        for block in &blocked {
+            self.cells[*block].unblock();
+            self.sync_safe(*block);
+        }
+        for block in blocked {
+            let (column, row) = f(block % width, block / width);
+            let index = row * width + column;
+            self.cells[index].block();
+            self.sync_safe(index);
+        }
+        queens.into_iter().for_each(|q| {
+            let (column, row) = f(q % width, q / width);
+            self.toggle_with_pair(column, row);
+        });
+        self
+    }
+
+    /// Indexes of the cells where a queen can be placed; equivalent to [`Board::safe_cells`].
     pub fn available(&self) -> impl Iterator<Item = usize> + '_ {
+        self.safe_cells()
+    }
+
+    /// Indexes of the cells without a queen, regardless of whether they are attacked.
+    pub fn free_cells(&self) -> impl Iterator<Item = usize> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| (!c.is_queen()).then_some(i))
+    }
+
+    /// Indexes of the cells without a queen that are not attacked by any queen.
+    #[cfg(not(feature = "bitboard"))]
+    pub fn safe_cells(&self) -> impl Iterator<Item = usize> + '_ {
         self.cells
             .iter()
             .enumerate()
             .filter_map(|(i, c)| c.is_free().then_some(i))
     }
 
+    /// Indexes of the cells without a queen that are not attacked by any queen.
+    #[cfg(feature = "bitboard")]
+    pub fn safe_cells(&self) -> impl Iterator<Item = usize> + '_ {
+        SafeCells::new(&self.safe)
+    }
+
+    /// The boards reachable in one placement, as the safe cell of the queen and the resulting
+    /// board; every board is built only when the iterator reaches it.
+    pub fn successors(&self) -> impl Iterator<Item = (usize, Board)> + '_ {
+        self.safe_cells().map(|index| {
+            let mut board = self.clone();
+            board.put_queen(index);
+            (index, board)
+        })
+    }
+
     pub fn cells(&self) -> impl Iterator<Item = &'_ Cell> {
         self.cells.iter()
     }
 
+    /// The cell of the index, or `None` if it's out of the board; see [`Position::cell`] for the
+    /// panicking query.
+    pub fn cell(&self, index: usize) -> Option<&Cell> {
+        self.cells.get(index)
+    }
+
     pub fn toggle(&mut self, index: usize) -> &mut Self {
         if self.cells[index].is_free() {
             self.put_queen(index)
@@ -122,57 +1014,630 @@ impl Board {
         }
     }
 
-    fn put_queen(&mut self, index: usize) -> &mut Self {
-        #[cfg(feature = "tracing")]
-        tracing::trace!("put queen {index}");
-
-        self.cells[index].put_queen();
-        self.queens.insert(index);
+    /// Toggles the queen of the index as [`Board::toggle`], but places it on an attacked cell
+    /// too, so the queens may attack each other, as the dominating sets do; a blocked cell is
+    /// left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of the bounds of the board.
+    pub fn toggle_attacked(&mut self, index: usize) -> &mut Self {
+        if self.cells[index].is_queen() {
+            self.remove_queen(index)
+        } else if self.cells[index].is_blocked() {
+            self
+        } else {
+            self.put_queen(index)
+        }
+    }
 
-        // update the attacked cells
-        let bounds = Boundaries::new(index, self.width);
-        for i in bounds.horizontal_min..=bounds.horizontal_max {
-            self.cells[i].attack_horizontal();
+    /// Blocks the cell, so no queen can be placed on it, removing its queen if any. If the blocks
+    /// stop the attacks, the attacks cut by the block are lifted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of the bounds of the board.
+    pub fn block(&mut self, index: usize) -> &mut Self {
+        if self.cells[index].is_queen() {
+            self.remove_queen(index);
         }
-        for i in (bounds.vertical_min..=bounds.vertical_max).step_by(self.width) {
-            self.cells[i].attack_vertical();
+        match self.cut_rays {
+            true => {
+                let queens = self.take_queens();
+                self.cells[index].block();
+                self.sync_safe(index);
+                // cutting a ray only lifts attacks, so the queens are placed back as they were
+                queens.into_iter().for_each(|q| {
+                    self.put_queen(q);
+                });
+            }
+            false => {
+                self.cells[index].block();
+                self.sync_safe(index);
+            }
         }
-        for i in (bounds.principal_min..=bounds.principal_max).step_by(self.width + 1) {
-            self.cells[i].attack_principal();
+        self
+    }
+
+    /// Unblocks the cell. If the blocks stop the attacks, the queens the block shielded from
+    /// each other must not attack each other once it's lifted; the board is unchanged on error.
+    pub fn unblock(&mut self, index: usize) -> Result<(), PlacementError> {
+        if index >= self.cells.len() {
+            return Err(PlacementError::OutOfBounds(index));
         }
-        for i in (bounds.antidiagonal_min..=bounds.antidiagonal_max).step_by(self.width - 1) {
-            self.cells[i].attack_antidiagonal();
+        if !self.cut_rays {
+            self.cells[index].unblock();
+            self.sync_safe(index);
+            return Ok(());
         }
 
-        self
+        let queens = self.take_queens();
+        self.cells[index].unblock();
+        self.sync_safe(index);
+        let conflict = queens.iter().copied().find_map(|q| {
+            if self.cells[q].is_free() {
+                self.put_queen(q);
+                return None;
+            }
+            Some(self.conflict(q))
+        });
+        if let Some(conflict) = conflict {
+            self.take_queens();
+            self.cells[index].block();
+            self.sync_safe(index);
+            queens.into_iter().for_each(|q| {
+                self.put_queen(q);
+            });
+            return Err(conflict);
+        }
+        Ok(())
     }
 
-    fn remove_queen(&mut self, index: usize) -> &mut Self {
-        #[cfg(feature = "tracing")]
-        tracing::trace!("remove queen {index}");
+    /// Indexes of the blocked cells.
+    pub fn blocked_cells(&self) -> impl Iterator<Item = usize> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| c.is_blocked().then_some(i))
+    }
 
-        self.cells[index].remove_queen();
+    /// Toggles the queen of the index, returning the performed action.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of the bounds of the board; see [`Board::try_toggle`].
+    pub fn toggle_reporting(&mut self, index: usize) -> ToggleResult {
+        if self.cells[index].is_free() {
+            self.put_queen(index);
+            ToggleResult::Placed
+        } else if self.cells[index].is_queen() {
+            self.remove_queen(index);
+            ToggleResult::Removed
+        } else if self.cells[index].is_blocked() {
+            ToggleResult::Blocked
+        } else {
+            ToggleResult::Ignored
+        }
+    }
+
+    /// Toggles the queen of the index as [`Board::toggle_reporting`], failing instead of
+    /// panicking for an index out of the bounds of the board.
+    pub fn try_toggle(&mut self, index: usize) -> Result<ToggleResult, PlacementError> {
+        match index < self.cells.len() {
+            true => Ok(self.toggle_reporting(index)),
+            false => Err(PlacementError::OutOfBounds(index)),
+        }
+    }
+
+    /// Moves the queen of `from` to `to`, which must be free once the queen is lifted from `from`,
+    /// such as a cell attacked only by the moved queen. The board is unchanged on error.
+    pub fn move_queen(&mut self, from: usize, to: usize) -> Result<(), PlacementError> {
+        let len = self.cells.len();
+        if from >= len {
+            return Err(PlacementError::OutOfBounds(from));
+        }
+        if to >= len {
+            return Err(PlacementError::OutOfBounds(to));
+        }
+        if !self.cells[from].is_queen() {
+            return Err(PlacementError::MissingQueen(from));
+        }
+
+        // every attack of the target must be lifted with the queen, so it's the only attacker of
+        // the lines it shares
+        let cell = self.cells[to];
+        let attacked = |direction| {
+            let shared = self.attacks_along(from, to, direction);
+            cell.attackers(direction) > u8::from(shared)
+        };
+        let knight = self.knight_targets(from).any(|t| t == to);
+        if (cell.is_queen() && to != from)
+            || cell.is_blocked()
+            || Direction::ALL.into_iter().any(attacked)
+            || cell.knight_attackers() > u8::from(knight)
+        {
+            return Err(PlacementError::Attacked(to));
+        }
+
+        self.remove_queen(from).put_queen(to);
+        Ok(())
+    }
+
+    fn put_queen(&mut self, index: usize) -> &mut Self {
+        #[cfg(feature = "tracing")]
+        tracing::trace!("put queen {index}");
+
+        self.cells[index].put_queen();
+        self.queens.insert(index);
+        self.hash ^= self.lines.key(index);
+        self.attack(index)
+    }
+
+    /// The conflict of the attacked index with the first queen attacking it.
+    fn conflict(&self, index: usize) -> PlacementError {
+        self.queens
+            .iter()
+            .find_map(|q| {
+                let direction = Direction::ALL
+                    .into_iter()
+                    .find(|d| self.attacks_along(*q, index, *d))?;
+                Some(PlacementError::Conflict {
+                    index,
+                    queen: *q,
+                    direction,
+                })
+            })
+            .unwrap_or(PlacementError::Attacked(index))
+    }
+
+    /// The ranges of the lines of the index, cut before the blocked cells if the blocks stop the
+    /// attacks.
+    fn bounds(&self, index: usize) -> Boundaries {
+        let bounds = Boundaries::new(index, &self.lines);
+        match self.cut_rays {
+            true => bounds.cut(index, self.width, &self.cells),
+            false => bounds,
+        }
+    }
+
+    /// The row, column, principal diagonal and antidiagonal of the index, with the diagonals
+    /// numbered modulo the width on a toroidal board, so the wrapped parts share a number.
+    fn line_keys(&self, index: usize) -> [usize; 4] {
+        let (row, column) = (index / self.width, index % self.width);
+        let (principal, antidiagonal) = (row + self.width - column, row + column);
+        match self.toroidal {
+            true => [
+                row,
+                column,
+                principal % self.width,
+                antidiagonal % self.width,
+            ],
+            false => [row, column, principal, antidiagonal],
+        }
+    }
+
+    /// Whether a blocked cell between the cells of the line stops the attack between them.
+    fn is_shielded(&self, a: usize, b: usize, direction: Direction) -> bool {
+        if !self.cut_rays {
+            return false;
+        }
+        let step = match direction {
+            Direction::Horizontal => 1,
+            Direction::Vertical => self.width,
+            Direction::Principal => self.width + 1,
+            Direction::Antidiagonal => (self.width - 1).max(1),
+        };
+        (a.min(b)..a.max(b))
+            .step_by(step)
+            .skip(1)
+            .any(|i| self.cells[i].is_blocked())
+    }
+
+    /// Flags the cells of the lines of the index as attacked.
+    fn attack(&mut self, index: usize) -> &mut Self {
+        if self.piece == Piece::Amazon {
+            for i in knight_moves(self.width, index) {
+                self.cells[i].attack_knight();
+                self.sync_safe(i);
+            }
+        }
+
+        let bounds = self.bounds(index);
+        if self.piece.attacks(Direction::Horizontal) {
+            for i in bounds.horizontal_min..=bounds.horizontal_max {
+                self.cells[i].attack_horizontal();
+                self.sync_safe(i);
+            }
+        }
+        if self.piece.attacks(Direction::Vertical) {
+            for i in (bounds.vertical_min..=bounds.vertical_max).step_by(self.width) {
+                self.cells[i].attack_vertical();
+                self.sync_safe(i);
+            }
+        }
+        // the pieces attack both diagonals or neither
+        if !self.piece.attacks(Direction::Principal) {
+            return self;
+        }
+        if self.toroidal {
+            for i in wrapped_diagonal(self.width, index, Direction::Principal) {
+                self.cells[i].attack_principal();
+                self.sync_safe(i);
+            }
+            for i in wrapped_diagonal(self.width, index, Direction::Antidiagonal) {
+                self.cells[i].attack_antidiagonal();
+                self.sync_safe(i);
+            }
+            return self;
+        }
+        for i in (bounds.principal_min..=bounds.principal_max).step_by(self.width + 1) {
+            self.cells[i].attack_principal();
+            self.sync_safe(i);
+        }
+        // a single cell board has no antidiagonal step, but the range has a single index
+        for i in
+            (bounds.antidiagonal_min..=bounds.antidiagonal_max).step_by((self.width - 1).max(1))
+        {
+            self.cells[i].attack_antidiagonal();
+            self.sync_safe(i);
+        }
+
+        self
+    }
+
+    /// Copies the safety of every cell into the bits of the safe cells, if kept.
+    fn sync_all_safe(&mut self) {
+        #[cfg(feature = "bitboard")]
+        {
+            self.safe = safe_words(self.cells.len());
+            (0..self.cells.len()).for_each(|i| self.sync_safe(i));
+        }
+    }
+
+    /// Copies the safety of the cell into the bits of the safe cells, if kept.
+    #[inline]
+    fn sync_safe(&mut self, index: usize) {
+        #[cfg(feature = "bitboard")]
+        {
+            let bit = 1 << (index % 64);
+            match self.cells[index].is_free() {
+                true => self.safe[index / 64] |= bit,
+                false => self.safe[index / 64] &= !bit,
+            }
+        }
+        #[cfg(not(feature = "bitboard"))]
+        let _ = index;
+    }
+
+    fn remove_queen(&mut self, index: usize) -> &mut Self {
+        #[cfg(feature = "tracing")]
+        tracing::trace!("remove queen {index}");
+
+        self.cells[index].remove_queen();
         self.queens.remove(&index);
+        self.hash ^= self.lines.key(index);
 
         // update the attacked cells
-        let bounds = Boundaries::new(index, self.width);
-        for i in bounds.horizontal_min..=bounds.horizontal_max {
-            self.cells[i].lift_horizontal();
+        if self.piece == Piece::Amazon {
+            for i in knight_moves(self.width, index) {
+                self.cells[i].lift_knight();
+                self.sync_safe(i);
+            }
+        }
+        let bounds = self.bounds(index);
+        if self.piece.attacks(Direction::Horizontal) {
+            for i in bounds.horizontal_min..=bounds.horizontal_max {
+                self.cells[i].lift_horizontal();
+                self.sync_safe(i);
+            }
+        }
+        if self.piece.attacks(Direction::Vertical) {
+            for i in (bounds.vertical_min..=bounds.vertical_max).step_by(self.width) {
+                self.cells[i].lift_vertical();
+                self.sync_safe(i);
+            }
+        }
+        // the pieces attack both diagonals or neither
+        if !self.piece.attacks(Direction::Principal) {
+            return self;
         }
-        for i in (bounds.vertical_min..=bounds.vertical_max).step_by(self.width) {
-            self.cells[i].lift_vertical();
+        if self.toroidal {
+            for i in wrapped_diagonal(self.width, index, Direction::Principal) {
+                self.cells[i].lift_principal();
+                self.sync_safe(i);
+            }
+            for i in wrapped_diagonal(self.width, index, Direction::Antidiagonal) {
+                self.cells[i].lift_antidiagonal();
+                self.sync_safe(i);
+            }
+            return self;
         }
         for i in (bounds.principal_min..=bounds.principal_max).step_by(self.width + 1) {
             self.cells[i].lift_principal();
+            self.sync_safe(i);
         }
-        for i in (bounds.antidiagonal_min..=bounds.antidiagonal_max).step_by(self.width - 1) {
+        for i in
+            (bounds.antidiagonal_min..=bounds.antidiagonal_max).step_by((self.width - 1).max(1))
+        {
             self.cells[i].lift_antidiagonal();
+            self.sync_safe(i);
         }
 
         self
     }
 }
 
+impl Position for Board {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn lines(&self) -> &Lines {
+        &self.lines
+    }
+
+    fn cell(&self, index: usize) -> Cell {
+        self.cells[index]
+    }
+
+    fn is_queen(&self, index: usize) -> bool {
+        Board::is_queen(self, index)
+    }
+
+    fn line_length(&self, index: usize, direction: Direction) -> usize {
+        Board::line_length(self, index, direction)
+    }
+
+    fn traverse_boundaries(&self, index: usize) -> impl Iterator<Item = (usize, Cell)> + '_ {
+        Board::traverse_boundaries(self, index).map(|(i, c)| (i, *c))
+    }
+
+    fn knight_targets(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        Board::knight_targets(self, index)
+    }
+
+    fn piece(&self) -> Piece {
+        Board::piece(self)
+    }
+}
+
+/// Draws a row per line, with a glyph per cell separated by spaces: `Q` for the queens, `x` for
+/// the attacked cells, `#` for the blocked cells, and `.` for the free cells.
+///
+/// The alternate form (`{:#}`) labels the rows with their ranks and the columns with their
+/// files, as in chess notation; the files past `z` are numbered.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.draw(f, ['Q', 'x', '.'])
+    }
+}
+
+/// The cells of the diagonal of the direction through the index on a torus of the width, from
+/// the index down the rows, wrapping around the edges.
+fn wrapped_diagonal(
+    width: usize,
+    index: usize,
+    direction: Direction,
+) -> impl Iterator<Item = usize> {
+    let (row, column) = (index / width, index % width);
+    (0..width).map(move |k| {
+        let column = match direction {
+            Direction::Antidiagonal => (column + width - k) % width,
+            _ => (column + k) % width,
+        };
+        (row + k) % width * width + column
+    })
+}
+
+/// The cells a knight move away from the index, on a board of the width.
+fn knight_moves(width: usize, index: usize) -> impl Iterator<Item = usize> {
+    const MOVES: [(isize, isize); 8] = [
+        (1, -2),
+        (2, -1),
+        (2, 1),
+        (1, 2),
+        (-1, 2),
+        (-2, 1),
+        (-2, -1),
+        (-1, -2),
+    ];
+    let (row, column) = (index / width, index % width);
+    MOVES.into_iter().filter_map(move |(dc, dr)| {
+        let column = column.checked_add_signed(dc).filter(|c| *c < width)?;
+        let row = row.checked_add_signed(dr).filter(|r| *r < width)?;
+        Some(row * width + column)
+    })
+}
+
+/// A board drawn with custom glyphs; see [`Board::render_with`].
+struct Rendering<'a> {
+    board: &'a Board,
+    glyphs: [char; 3],
+}
+
+impl fmt::Display for Rendering<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.board.draw(f, self.glyphs)
+    }
+}
+
+/// The bits of the safe cells, from the lowest of the first word.
+#[cfg(feature = "bitboard")]
+struct SafeCells<'a> {
+    words: core::slice::Iter<'a, u64>,
+    /// The index of the lowest bit of the current word.
+    base: usize,
+    word: u64,
+}
+
+#[cfg(feature = "bitboard")]
+impl<'a> SafeCells<'a> {
+    fn new(words: &'a [u64]) -> Self {
+        let mut words = words.iter();
+        let word = words.next().copied().unwrap_or(0);
+        Self {
+            words,
+            base: 0,
+            word,
+        }
+    }
+}
+
+#[cfg(feature = "bitboard")]
+impl Iterator for SafeCells<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            self.word = *self.words.next()?;
+            self.base += 64;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(self.base + bit)
+    }
+
+    fn count(self) -> usize {
+        let rest = self.words.map(|w| w.count_ones() as usize).sum::<usize>();
+        self.word.count_ones() as usize + rest
+    }
+}
+
+/// The number of cells of a board of the width, if they fit the address space along with their
+/// lines.
+pub(crate) fn cell_count(width: usize) -> Option<usize> {
+    let cells = width.checked_mul(width)?;
+    let bytes = cells.checked_mul(mem::size_of::<LineIds>())?;
+    (bytes <= isize::MAX as usize).then_some(cells)
+}
+
+/// The words of the given number of cells, all safe.
+#[cfg(feature = "bitboard")]
+fn safe_words(cells: usize) -> Vec<u64> {
+    let mut words = vec![u64::MAX; cells / 64];
+    let rest = cells % 64;
+    if rest > 0 {
+        words.push((1 << rest) - 1);
+    }
+    words
+}
+
+/// The decimal digits of the number.
+fn digits(mut n: usize) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+/// The cell of the `(column, row)` coordinates.
+///
+/// # Panics
+///
+/// Panics if the coordinates are out of the board, even if their flat index isn't.
+impl Index<(usize, usize)> for Board {
+    type Output = Cell;
+
+    fn index(&self, (column, row): (usize, usize)) -> &Self::Output {
+        match self.index_of(column, row) {
+            Some(index) => &self.cells[index],
+            None => panic!(
+                "the coordinates ({column}, {row}) are out of the board of width {}",
+                self.width
+            ),
+        }
+    }
+}
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.cut_rays == other.cut_rays
+            && self.toroidal == other.toroidal
+            && self.piece == other.piece
+            && self.cells == other.cells
+            && self.queens == other.queens
+            && self.costs.len() == other.costs.len()
+            && self
+                .costs
+                .iter()
+                .zip(&other.costs)
+                .all(|(a, b)| a.to_bits() == b.to_bits())
+    }
+}
+
+// the costs are compared by their bits, so the equality is total
+impl Eq for Board {}
+
+// the attacks are derived from the queens, so a payload carrying its own cells is rejected
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BoardRepr {
+    width: usize,
+    queens: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    blocked: Vec<usize>,
+    #[serde(default, skip_serializing_if = "is_queen")]
+    piece: Piece,
+    #[serde(default, skip_serializing_if = "is_false")]
+    toroidal: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    cut_rays: bool,
+}
+
+#[cfg(feature = "serde")]
+fn is_false(flag: &bool) -> bool {
+    !flag
+}
+
+#[cfg(feature = "serde")]
+fn is_queen(piece: &Piece) -> bool {
+    *piece == Piece::Queen
+}
+
+#[cfg(feature = "serde")]
+impl From<Board> for BoardRepr {
+    fn from(board: Board) -> Self {
+        Self {
+            width: board.width,
+            blocked: board.blocked_cells().collect(),
+            queens: board.queens.into_iter().collect(),
+            piece: board.piece,
+            toroidal: board.toroidal,
+            cut_rays: board.cut_rays,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BoardRepr> for Board {
+    type Error = crate::ParseBoardError;
+
+    fn try_from(repr: BoardRepr) -> Result<Self, Self::Error> {
+        use crate::ParseBoardError;
+
+        // the width is checked before the cells are allocated
+        if cell_count(repr.width).is_none() {
+            return Err(ParseBoardError::WidthTooLarge(repr.width));
+        }
+
+        let mut board = Board::with_topology(repr.width, repr.piece, repr.toroidal, repr.cut_rays)
+            .ok_or(ParseBoardError::UnsupportedTopology)?;
+        for index in repr.blocked {
+            if index >= board.cells.len() {
+                return Err(ParseBoardError::Placement(PlacementError::OutOfBounds(
+                    index,
+                )));
+            }
+            board.block(index);
+        }
+        board.placing(repr.queens).map_err(ParseBoardError::Placement)
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Boundaries {
     pub horizontal_min: usize,
@@ -186,9 +1651,9 @@ struct Boundaries {
 }
 
 impl Boundaries {
-    pub fn new(index: usize, width: usize) -> Self {
-        let row = index / width;
-        let column = index - row * width;
+    pub fn new(index: usize, lines: &Lines) -> Self {
+        let width = lines.width();
+        let LineIds { row, column, .. } = lines.line_ids(index);
         let min_distance_to_zero = row.min(column);
         let min_column_distance_to_right = row.min(width - column - 1);
         let min_row_distance_to_left = column.min(width - row - 1);
@@ -214,6 +1679,41 @@ impl Boundaries {
             antidiagonal_max,
         }
     }
+
+    /// Narrows every range to the cells reached from the index before a blocked cell.
+    pub fn cut(self, index: usize, width: usize, cells: &[Cell]) -> Self {
+        let reach = |min: usize, max: usize, step: usize| {
+            let (mut low, mut high) = (index, index);
+            while low > min && !cells[low - step].is_blocked() {
+                low -= step;
+            }
+            while high < max && !cells[high + step].is_blocked() {
+                high += step;
+            }
+            (low, high)
+        };
+
+        let (horizontal_min, horizontal_max) = reach(self.horizontal_min, self.horizontal_max, 1);
+        let (vertical_min, vertical_max) = reach(self.vertical_min, self.vertical_max, width);
+        let (principal_min, principal_max) =
+            reach(self.principal_min, self.principal_max, width + 1);
+        // a single cell board has no antidiagonal step, but its range has a single index
+        let (antidiagonal_min, antidiagonal_max) = reach(
+            self.antidiagonal_min,
+            self.antidiagonal_max,
+            (width - 1).max(1),
+        );
+        Self {
+            horizontal_min,
+            horizontal_max,
+            vertical_min,
+            vertical_max,
+            principal_min,
+            principal_max,
+            antidiagonal_min,
+            antidiagonal_max,
+        }
+    }
 }
 
 #[test]
@@ -221,10 +1721,617 @@ fn toggle_works() {
     Board::new(8).toggle(0);
 }
 
+#[test]
+fn successors_works() {
+    let mut board = Board::new(6);
+    assert_eq!(board.successors().count(), 36);
+
+    board.toggle(7).toggle(15);
+    assert_eq!(board.successors().count(), board.safe_cells().count());
+    for ((index, successor), safe) in board.successors().zip(board.safe_cells()) {
+        assert_eq!(index, safe);
+        assert!(successor.is_queen(index));
+        assert_eq!(successor.sorted_queens().count(), 3);
+        assert_eq!(successor.clone().toggle(index), &board);
+    }
+
+    // a solved board has no successor
+    assert_eq!(
+        Board::from_permutation(&[1, 3, 0, 2])
+            .unwrap()
+            .successors()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn free_and_safe_cells_differ() {
+    let mut board = Board::new(4);
+    assert_eq!(board.free_cells().count(), 16);
+    assert_eq!(board.safe_cells().count(), 16);
+
+    board.toggle(0);
+    assert_eq!(board.free_cells().count(), 15);
+    assert_eq!(
+        board.safe_cells().collect::<Vec<_>>(),
+        vec![6, 7, 9, 11, 13, 14]
+    );
+    assert!(board.free_cells().any(|i| i == 1));
+    assert!(!board.free_cells().any(|i| i == 0));
+    assert!(board.available().eq(board.safe_cells()));
+}
+
+#[test]
+fn validate_invariants_works() {
+    let mut board = Board::new(8);
+    assert_eq!(board.validate_invariants(), Ok(()));
+
+    board.toggle(0).toggle(10).toggle(20);
+    assert_eq!(board.validate_invariants(), Ok(()));
+
+    // removing a queen keeps the attacks of the others on the shared cells
+    board.toggle(10);
+    assert_eq!(board.validate_invariants(), Ok(()));
+
+    board.cells[1].lift_horizontal();
+    let violation = board.validate_invariants().unwrap_err();
+    assert_eq!(violation.index, 1);
+    assert!(violation.expected.is_attacked_horizontal());
+    assert!(!violation.found.is_attacked_horizontal());
+}
+
+#[test]
+fn parts_round_trip() {
+    let mut board = Board::new(6);
+    board.toggle(1).toggle(9).toggle(17);
+
+    let parts = board.clone().into_parts();
+    assert_eq!(parts.width, 6);
+    assert_eq!(
+        parts.queens.iter().copied().collect::<Vec<_>>(),
+        vec![1, 9, 17]
+    );
+    assert_eq!(Board::from_parts(parts), Ok(board));
+
+    // the costs, the piece, the topology and the blocks are reassembled
+    let mut costly = Board::with_costs(4, (0..16).map(f64::from).collect());
+    costly.toggle(5);
+    let mut rooks = Board::with_piece(4, Piece::Rook);
+    rooks.toggle(0).toggle(5);
+    let mut torus = Board::new_toroidal(5);
+    torus.toggle(0).toggle(7);
+    let mut blocked = Board::with_cut_rays(4);
+    blocked.block(1).toggle(0).toggle(2);
+    for board in [costly, rooks, torus, blocked] {
+        assert_eq!(Board::from_parts(board.clone().into_parts()), Ok(board));
+    }
+
+    // the cells of the rooks don't hold the diagonal attacks of queens
+    let mut parts = Board::with_piece(4, Piece::Rook)
+        .toggle(0)
+        .clone()
+        .into_parts();
+    parts.piece = Piece::Queen;
+    assert!(Board::from_parts(parts).is_err());
+}
+
+#[test]
+fn from_parts_rejects_inconsistent_parts() {
+    let mut board = Board::new(6);
+    board.toggle(1).toggle(9);
+
+    // a queen missing from the set
+    let mut parts = board.clone().into_parts();
+    parts.queens.remove(&9);
+    let violation = Board::from_parts(parts).unwrap_err();
+    assert_eq!(violation.index, 2);
+
+    // a cell without the attack of a queen
+    let mut parts = board.into_parts();
+    parts.cells[0].lift_horizontal();
+    let violation = Board::from_parts(parts).unwrap_err();
+    assert_eq!(violation.index, 0);
+    assert!(violation.expected.is_attacked_horizontal());
+}
+
+#[test]
+#[should_panic(expected = "the toroidal boards can't cut the rays")]
+fn from_parts_rejects_toroidal_cut_rays() {
+    let mut parts = Board::new_toroidal(5).into_parts();
+    parts.cut_rays = true;
+    let _ = Board::from_parts(parts);
+}
+
+#[test]
+fn to_permutation_works() {
+    let mut board = Board::new(4);
+    assert_eq!(board.to_permutation(), None);
+
+    board.toggle(1).toggle(7).toggle(8).toggle(14);
+    assert_eq!(board.to_permutation(), Some(vec![1, 3, 0, 2]));
+
+    board.toggle(8);
+    assert_eq!(board.to_permutation(), None);
+}
+
+#[test]
+fn from_queens_works() {
+    let queens = [0, 12, 23, 29, 34, 46, 49, 59];
+    let board = Board::from_queens(8, queens).unwrap();
+    assert!(board.is_solved());
+    assert!(board.sorted_queens().eq(queens));
+    assert_eq!(
+        Some(board),
+        Board::from_permutation(&[0, 4, 7, 5, 2, 6, 1, 3]).ok()
+    );
+
+    // the queens are placed in order, so the first failure is reported
+    assert_eq!(
+        Board::from_queens(4, [1, 16, 5]),
+        Err(PlacementError::OutOfBounds(16))
+    );
+    assert_eq!(
+        Board::from_queens(4, [1, 4, 16]),
+        Err(PlacementError::Conflict {
+            index: 4,
+            queen: 1,
+            direction: Direction::Antidiagonal
+        })
+    );
+    assert_eq!(
+        Board::from_queens(4, [1, 1]),
+        Err(PlacementError::Duplicate(1))
+    );
+
+    // the first queen attacking the index is named, with the line they share
+    let error = Board::from_queens(8, [3, 13, 27]).unwrap_err();
+    assert_eq!(
+        error,
+        PlacementError::Conflict {
+            index: 27,
+            queen: 3,
+            direction: Direction::Vertical
+        }
+    );
+    assert_eq!(
+        alloc::format!("{error}"),
+        "queen 27 conflicts with queen 3 on the column"
+    );
+    let error = Board::from_queens(8, [0, 36]).unwrap_err();
+    assert_eq!(
+        alloc::format!("{error}"),
+        "queen 36 conflicts with queen 0 on the principal diagonal"
+    );
+    assert_eq!(Board::from_queens(0, []), Ok(Board::new(0)));
+}
+
+#[test]
+fn from_permutation_works() {
+    let board = Board::from_permutation(&[1, 3, 0, 2]).unwrap();
+    assert!(board.is_solved());
+    assert_eq!(board.sorted_queens().collect::<Vec<_>>(), vec![1, 7, 8, 14]);
+    assert_eq!(board.to_permutation(), Some(vec![1, 3, 0, 2]));
+
+    assert_eq!(
+        Board::from_permutation(&[0, 4, 1]),
+        Err(PlacementError::OutOfBounds(7))
+    );
+    assert_eq!(
+        Board::from_permutation(&[0, 1, 3, 2]),
+        Err(PlacementError::Attacked(5))
+    );
+    assert_eq!(Board::from_permutation(&[]), Ok(Board::new(0)));
+}
+
+#[test]
+fn move_queen_works() {
+    use crate::test_utils::assert_board_consistent;
+
+    let mut board = Board::new(5);
+    board.toggle(0).toggle(7);
+
+    // the target is attacked only by the moved queen, on its row
+    let mut expected = Board::new(5);
+    expected.toggle(4).toggle(7);
+    assert_eq!(board.move_queen(0, 4), Ok(()));
+    assert_eq!(board, expected);
+    assert_board_consistent(&board);
+    assert!(board.is_queen(4) && !board.is_queen(0));
+    assert!(board.cells[0].is_attacked_horizontal());
+    assert!(!board.cells[0].is_attacked_vertical() && !board.cells[0].is_attacked_principal());
+
+    // moving it back restores the attacks of both cells
+    assert_eq!(board.move_queen(4, 0), Ok(()));
+    assert!(board.is_queen(0) && !board.cells[4].is_queen());
+    assert!(board.cells[4].is_attacked_horizontal() && !board.cells[4].is_attacked_antidiagonal());
+    assert_board_consistent(&board);
+
+    let unchanged = board.clone();
+    assert_eq!(board.move_queen(1, 4), Err(PlacementError::MissingQueen(1)));
+    assert_eq!(
+        board.move_queen(0, 25),
+        Err(PlacementError::OutOfBounds(25))
+    );
+    assert_eq!(board.move_queen(0, 7), Err(PlacementError::Attacked(7)));
+    assert_eq!(board.move_queen(0, 12), Err(PlacementError::Attacked(12)));
+    assert_eq!(board.move_queen(0, 9), Err(PlacementError::Attacked(9)));
+    assert_eq!(board, unchanged);
+    assert_eq!(board.move_queen(0, 0), Ok(()));
+    assert_eq!(board, unchanged);
+}
+
+#[test]
+fn is_fundamental_works() {
+    use alloc::collections::BTreeMap;
+
+    // places a queen on every row, collecting the solutions
+    fn solutions(board: &mut Board, row: usize, found: &mut Vec<Board>) {
+        if row == board.width() {
+            found.push(board.clone());
+            return;
+        }
+        for index in row * board.width()..(row + 1) * board.width() {
+            if board.cells[index].is_free() {
+                board.toggle(index);
+                solutions(board, row + 1, found);
+                board.toggle(index);
+            }
+        }
+    }
+
+    for (width, total, fundamental) in [(1, 1, 1), (4, 2, 1), (6, 4, 1), (8, 92, 12)] {
+        let mut found = Vec::new();
+        solutions(&mut Board::new(width), 0, &mut found);
+        assert_eq!(found.len(), total);
+
+        let mut orbits = BTreeMap::<_, Vec<_>>::new();
+        for board in found {
+            orbits.entry(board.canonical_key()).or_default().push(board);
+        }
+        assert_eq!(orbits.len(), fundamental);
+        for (key, boards) in orbits {
+            let fundamental = boards
+                .iter()
+                .filter(|b| b.is_fundamental())
+                .collect::<Vec<_>>();
+            assert_eq!(fundamental.len(), 1);
+            assert_eq!(fundamental[0].sorted_queens().collect::<Vec<_>>(), key);
+            assert!(boards
+                .iter()
+                .all(|b| b.symmetries().contains(fundamental[0])));
+        }
+    }
+
+    // partial boards have orbits too
+    let mut board = Board::new(5);
+    board.toggle(1);
+    assert!(board.is_fundamental());
+    assert!(!board.rotated(1).is_fundamental());
+    assert!(!board.flipped(Direction::Vertical).is_fundamental());
+    assert!(Board::new(3).is_fundamental());
+}
+
+#[test]
+fn rows_equal_works() {
+    let mut board = Board::new(4);
+    board.toggle(1);
+    let mut costly = Board::with_costs(4, vec![1.0; 16]);
+    costly.toggle(1);
+    assert_ne!(board, costly);
+    assert!(board.rows_equal(&costly));
+
+    costly.toggle(1).toggle(2);
+    assert!(!board.rows_equal(&costly));
+    assert!(!Board::new(4).rows_equal(&Board::new(2)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trips() {
+    let solution = Board::from_permutation(&[0, 4, 7, 5, 2, 6, 1, 3]).unwrap();
+    let json = serde_json::to_string(&solution).unwrap();
+    assert_eq!(json, r#"{"width":8,"queens":[0,12,23,29,34,46,49,59]}"#);
+
+    // the attacks are recomputed as by the placement of the queens
+    let board: Board = serde_json::from_str(&json).unwrap();
+    assert_eq!(board, solution);
+    assert!(board.validate_invariants().is_ok());
+
+    let board = serde_json::from_str::<Board>(r#"{"width":4,"queens":[0,5]}"#);
+    assert!(board.is_err());
+    let board = serde_json::from_str::<Board>(r#"{"width":4,"queens":[16]}"#);
+    assert!(board.is_err());
+
+    // the widths overflowing the cells are rejected before the cells are allocated
+    for json in [
+        r#"{"width":4294967296,"queens":[]}"#,
+        r#"{"width":18446744073709551615,"queens":[]}"#,
+    ] {
+        let error = serde_json::from_str::<Board>(json).unwrap_err();
+        assert!(error.to_string().contains("too large"), "{error}");
+    }
+
+    // a partially filled board
+    let mut partial = Board::new(8);
+    partial.toggle(3).toggle(13);
+    let json = serde_json::to_string(&partial).unwrap();
+    assert_eq!(json, r#"{"width":8,"queens":[3,13]}"#);
+    assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), partial);
+
+    // the tampered payloads: a repeated queen, a missing width, and cells out of sync with the
+    // queens
+    for json in [
+        r#"{"width":8,"queens":[3,13,3]}"#,
+        r#"{"queens":[3,13]}"#,
+        r#"{"width":8,"queens":[3,13],"cells":[0]}"#,
+    ] {
+        assert!(serde_json::from_str::<Board>(json).is_err(), "{json}");
+    }
+
+    // the cells round trip their content, and reject the unknown bits
+    for cell in solution.cells() {
+        let json = serde_json::to_string(cell).unwrap();
+        assert_eq!(serde_json::from_str::<Cell>(&json).unwrap(), *cell);
+    }
+    assert!(serde_json::from_str::<Cell>("1073741824").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trips_the_piece() {
+    // the rooks of the solution share the diagonals, so they conflict as queens
+    let mut rooks = Board::with_piece(4, Piece::Rook);
+    [0, 5, 10, 15].into_iter().for_each(|q| {
+        rooks.toggle(q);
+    });
+    assert!(rooks.is_solved());
+
+    let json = serde_json::to_string(&rooks).unwrap();
+    assert_eq!(json, r#"{"width":4,"queens":[0,5,10,15],"piece":"rook"}"#);
+    let board: Board = serde_json::from_str(&json).unwrap();
+    assert_eq!(board, rooks);
+    assert!(board.validate_invariants().is_ok());
+
+    let json = r#"{"width":4,"queens":[0,5,10,15],"piece":"queen"}"#;
+    assert!(serde_json::from_str::<Board>(json).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trips_the_topology() {
+    // the solution of a torus, which a plain board would accept with other attacks
+    let mut torus = Board::new_toroidal(5);
+    [0, 7, 14, 16, 23].into_iter().for_each(|q| {
+        torus.toggle(q);
+    });
+    assert!(torus.is_solved());
+
+    let json = serde_json::to_string(&torus).unwrap();
+    assert_eq!(
+        json,
+        r#"{"width":5,"queens":[0,7,14,16,23],"toroidal":true}"#
+    );
+    let board: Board = serde_json::from_str(&json).unwrap();
+    assert_eq!(board, torus);
+    assert!(board.validate_invariants().is_ok());
+
+    let cut = Board::with_cut_rays(4);
+    let json = serde_json::to_string(&cut).unwrap();
+    assert_eq!(json, r#"{"width":4,"queens":[],"cut_rays":true}"#);
+    assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), cut);
+
+    // the wrapped lines have no end to cut
+    let json = r#"{"width":5,"queens":[0],"blocked":[1,5,6],"toroidal":true,"cut_rays":true}"#;
+    let error = serde_json::from_str::<Board>(json).unwrap_err();
+    assert!(error.to_string().contains("can't cut the rays"), "{error}");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trips_the_blocks() {
+    // the block shields the queens of the row from each other
+    let mut blocked = Board::with_cut_rays(4);
+    blocked.block(1).toggle(0).toggle(2);
+    assert_eq!(blocked.queens.len(), 2);
+
+    let json = serde_json::to_string(&blocked).unwrap();
+    assert_eq!(
+        json,
+        r#"{"width":4,"queens":[0,2],"blocked":[1],"cut_rays":true}"#
+    );
+    let board: Board = serde_json::from_str(&json).unwrap();
+    assert_eq!(board, blocked);
+    assert!(board.validate_invariants().is_ok());
+
+    // a queen on a block, and a block out of bounds
+    for json in [
+        r#"{"width":4,"queens":[1],"blocked":[1]}"#,
+        r#"{"width":4,"queens":[],"blocked":[16]}"#,
+    ] {
+        assert!(serde_json::from_str::<Board>(json).is_err(), "{json}");
+    }
+}
+
+#[test]
+fn removal_keeps_the_attacks_of_shared_lines() {
+    // the pairs of queens sharing a line, and a cell of the line between them
+    let pairs = [
+        (0, 4, 2, Direction::Horizontal),
+        (0, 20, 10, Direction::Vertical),
+        (0, 24, 12, Direction::Principal),
+        (4, 20, 12, Direction::Antidiagonal),
+    ];
+    for (a, b, between, direction) in pairs {
+        let mut board = Board::new(5);
+        board.put_queen(a).put_queen(b);
+        assert_eq!(board.cells[between].attackers(direction), 2);
+        assert!(board.validate_invariants().is_ok());
+
+        board.remove_queen(a);
+        assert!(board.cells[between].is_attacked());
+        assert_eq!(board.cells[between].attackers(direction), 1);
+
+        // the board is the one of the remaining queen alone
+        let mut expected = Board::new(5);
+        expected.toggle(b);
+        assert_eq!(board, expected);
+        assert!(board.validate_invariants().is_ok());
+    }
+
+    // a queen can't move along a line still attacked by another one
+    let mut board = Board::new(5);
+    board.put_queen(0).put_queen(4);
+    assert_eq!(board.move_queen(0, 2), Err(PlacementError::Attacked(2)));
+    board.remove_queen(4);
+    assert_eq!(board.move_queen(0, 2), Ok(()));
+}
+
+#[test]
+fn degenerate_widths_work() {
+    // the empty board has no cells, and is solved without queens
+    let mut board = Board::new(0);
+    assert!(board.is_solved() && board.is_empty());
+    assert_eq!(board.rows().count(), 0);
+    assert_eq!(board.available().count(), 0);
+    assert_eq!(board.symmetries(), [(); 8].map(|_| Board::new(0)));
+    assert!(board
+        .rotate_clockwise()
+        .flip(Direction::Principal)
+        .is_empty());
+    assert!(board.validate_invariants().is_ok());
+
+    // the single cell board is solved by its single queen
+    let mut board = Board::new(1);
+    assert!(board
+        .traverse_boundaries(0)
+        .map(|(i, _)| i)
+        .eq([0, 0, 0, 0]));
+    for direction in Direction::ALL {
+        assert_eq!(board.line_length(0, direction), 1);
+    }
+    assert_eq!(board.toggle_reporting(0), ToggleResult::Placed);
+    assert!(board.is_solved());
+    assert_eq!(board.rows().count(), 1);
+    assert_eq!(board.toggle_reporting(0), ToggleResult::Removed);
+    assert_eq!(board, Board::new(1));
+
+    // the width 2 has no solution, as every queen attacks the whole board
+    let mut board = Board::new(2);
+    assert!(board
+        .traverse_boundaries(1)
+        .map(|(i, _)| i)
+        .eq([0, 1, 1, 3, 1, 1, 2]));
+    board.toggle(1);
+    assert_eq!(board.available().count(), 0);
+    assert!(!board.is_solved());
+    board.toggle(1);
+    assert_eq!(board, Board::new(2));
+}
+
+#[test]
+fn display_works() {
+    use alloc::format;
+
+    let board = Board::from_permutation(&[1, 3, 0, 2]).unwrap();
+    assert_eq!(format!("{board}"), "x Q x x\nx x x Q\nQ x x x\nx x Q x\n");
+    assert_eq!(
+        format!("{board:#}"),
+        "4 x Q x x\n3 x x x Q\n2 Q x x x\n1 x x Q x\n  a b c d\n"
+    );
+
+    let mut board = Board::new(3);
+    board.toggle(0);
+    assert_eq!(format!("{board}"), "Q x x\nx x .\nx . x\n");
+    assert_eq!(format!("{}", Board::new(0)), "");
+    assert_eq!(board.render_with('Q', '*', '.'), "Q * *\n* * .\n* . *\n");
+    assert_eq!(board.render_with('#', '+', ' '), "# + +\n+ +  \n+   +\n");
+
+    // the ranks and files wider than a digit are aligned
+    let mut board = Board::new(10);
+    board.toggle(99);
+    let drawn = format!("{board:#}");
+    let lines = drawn.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "10 x . . . . . . . . x");
+    assert_eq!(lines[9], " 1 x x x x x x x x x Q");
+    assert_eq!(lines[10], "   a b c d e f g h i j");
+    let drawn = format!("{:#}", Board::new(27));
+    assert!(drawn.ends_with(" y  z 27\n"));
+    assert!(drawn.starts_with("27  .  ."));
+}
+
+#[test]
+fn line_length_works() {
+    let board = Board::new(8);
+    for index in [0, 7, 27, 63] {
+        assert_eq!(board.line_length(index, Direction::Horizontal), 8);
+        assert_eq!(board.line_length(index, Direction::Vertical), 8);
+    }
+
+    // corners
+    assert_eq!(board.line_length(0, Direction::Principal), 8);
+    assert_eq!(board.line_length(0, Direction::Antidiagonal), 1);
+    assert_eq!(board.line_length(7, Direction::Principal), 1);
+    assert_eq!(board.line_length(7, Direction::Antidiagonal), 8);
+    assert_eq!(board.line_length(56, Direction::Antidiagonal), 8);
+    assert_eq!(board.line_length(63, Direction::Principal), 8);
+
+    // center and near the edges
+    assert_eq!(board.line_length(27, Direction::Principal), 8);
+    assert_eq!(board.line_length(27, Direction::Antidiagonal), 7);
+    assert_eq!(board.line_length(1, Direction::Principal), 7);
+    assert_eq!(board.line_length(1, Direction::Antidiagonal), 2);
+
+    // the lengths match the traversal of the boundaries
+    for index in 0..64 {
+        assert_eq!(
+            Direction::ALL
+                .iter()
+                .map(|d| board.line_length(index, *d))
+                .sum::<usize>(),
+            board.traverse_boundaries(index).count()
+        );
+    }
+}
+
+#[test]
+fn subboard_works() {
+    let mut board = Board::new(8);
+    board.toggle(0).toggle(10).toggle(20).toggle(63);
+
+    let subboard = board.subboard(1, 1, 4);
+    assert_eq!(subboard.width(), 4);
+    assert_eq!(subboard.sorted_queens().collect::<Vec<_>>(), vec![1, 7]);
+
+    // the attacks of the queens outside the region are dropped
+    assert!(subboard.cells().next().unwrap().is_attacked());
+    assert!(subboard.cells().nth(12).unwrap().is_free());
+    assert!(!subboard.cells().nth(15).unwrap().is_attacked_principal());
+
+    let subboard = board.subboard(0, 0, 8);
+    assert_eq!(subboard, board);
+
+    // the rooks attack only their rows and columns, and the block of the region shields the
+    // queens of its row from each other
+    let mut rooks = Board::with_piece(6, Piece::Rook);
+    rooks.toggle(7).toggle(14);
+    let subboard = rooks.subboard(1, 1, 3);
+    assert_eq!(subboard.piece(), Piece::Rook);
+    assert_eq!(subboard.sorted_queens().collect::<Vec<_>>(), vec![0, 4]);
+    assert!(subboard.validate_invariants().is_ok());
+
+    let mut blocked = Board::with_cut_rays(6);
+    blocked.block(8).toggle(7).toggle(9);
+    let subboard = blocked.subboard(1, 1, 3);
+    assert!(subboard.cuts_rays());
+    assert_eq!(subboard.blocked_cells().collect::<Vec<_>>(), vec![1]);
+    assert_eq!(subboard.sorted_queens().collect::<Vec<_>>(), vec![0, 2]);
+}
+
 #[test]
 fn boundary_cases() {
     fn case(index: usize, width: usize, boundaries: [usize; 8]) {
-        let computed = Boundaries::new(index, width);
+        let computed = Boundaries::new(index, &Lines::new(width));
         assert_eq!(boundaries[0], computed.horizontal_min);
         assert_eq!(boundaries[1], computed.horizontal_max);
         assert_eq!(boundaries[2], computed.vertical_min);
@@ -259,6 +2366,38 @@ fn boundary_cases() {
     case(52, 9, [45, 53, 7, 79, 2, 62, 44, 76]);
 }
 
+#[test]
+fn line_ids_match_the_boundaries() {
+    for width in 1..=12 {
+        let lines = Lines::new(width);
+        for index in 0..width * width {
+            let ids = lines.line_ids(index);
+            let bounds = Boundaries::new(index, &lines);
+            assert!(lines
+                .cells_of_row(ids.row)
+                .eq(bounds.horizontal_min..=bounds.horizontal_max));
+            assert!(lines
+                .cells_of_column(ids.column)
+                .eq((bounds.vertical_min..=bounds.vertical_max).step_by(width)));
+            assert!(lines
+                .cells_of_principal(ids.principal)
+                .eq((bounds.principal_min..=bounds.principal_max).step_by(width + 1)));
+            assert!(lines
+                .cells_of_antidiagonal(ids.antidiagonal)
+                .eq(
+                    (bounds.antidiagonal_min..=bounds.antidiagonal_max).step_by((width - 1).max(1))
+                ));
+            assert_eq!((ids.row, ids.column), (index / width, index % width));
+        }
+    }
+
+    // the lines are shared between the clones, and follow the resizes
+    let mut board = Board::new(8);
+    assert!(Arc::ptr_eq(&board.lines, &board.clone().lines));
+    board.reset(5);
+    assert_eq!(board.lines().width(), 5);
+}
+
 #[test]
 fn traverse_boundaries_works() {
     fn case<Q>(index: usize, width: usize, values: Q)
@@ -277,8 +2416,816 @@ fn traverse_boundaries_works() {
         0,
         8,
         (0..8)
-            .chain([0, 8, 16, 24, 32, 40, 48, 56].into_iter())
-            .chain([0, 9, 18, 27, 36, 45, 54, 63].into_iter())
-            .chain([0].into_iter()),
+            .chain([0, 8, 16, 24, 32, 40, 48, 56])
+            .chain([0, 9, 18, 27, 36, 45, 54, 63])
+            .chain([0]),
+    );
+}
+
+#[test]
+fn columns_work() {
+    let board = Board::from_queens(5, [1, 8, 10, 17]).unwrap();
+
+    // the columns are the transposed rows
+    let columns = board
+        .columns()
+        .map(|c| c.copied().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let transposed = (0..5)
+        .map(|c| board.rows().map(|r| r[c]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(columns, transposed);
+    assert_eq!(
+        columns
+            .iter()
+            .map(|c| c.iter().filter(|c| c.is_queen()).count())
+            .collect::<Vec<_>>(),
+        vec![1, 1, 1, 1, 0]
     );
+
+    // the single accessors match the iterators, and are bounds checked
+    assert_eq!(board.row(1), board.rows().nth(1));
+    assert!(board.column(3).unwrap().eq(board.columns().nth(3).unwrap()));
+    assert!(board.row(5).is_none());
+    assert!(board.column(5).is_none());
+
+    // the single cell and the empty widths
+    let single = Board::from_queens(1, [0]).unwrap();
+    assert_eq!(single.columns().count(), 1);
+    assert!(single.column(0).unwrap().all(Cell::is_queen));
+    assert_eq!(single.row(0).map(<[Cell]>::len), Some(1));
+    assert_eq!(Board::new(0).columns().count(), 0);
+    assert!(Board::new(0).row(0).is_none() && Board::new(0).column(0).is_none());
+}
+
+#[test]
+fn coordinates_work() {
+    let board = Board::from_queens(5, [7]).unwrap();
+    assert_eq!(board.coords(7), (2, 1));
+    assert_eq!(board.index_of(2, 1), Some(7));
+    assert!(board[(2, 1)].is_queen());
+    assert!(board[(4, 1)].is_attacked_horizontal());
+    assert_eq!(board.cell(7), Some(&board[(2, 1)]));
+
+    // the coordinates past the edges don't wrap to the next row
+    assert_eq!(board.index_of(5, 0), None);
+    assert_eq!(board.index_of(0, 5), None);
+    assert_eq!(board.cell(25), None);
+    for index in 0..25 {
+        let (column, row) = board.coords(index);
+        assert_eq!(board.index_of(column, row), Some(index));
+    }
+}
+
+#[test]
+#[should_panic(expected = "the coordinates (5, 0) are out of the board of width 5")]
+fn index_past_the_edge_panics() {
+    let _ = Board::new(5)[(5, 0)];
+}
+
+#[test]
+fn toggle_reporting_works() {
+    let mut board = Board::new(4);
+
+    assert_eq!(board.toggle_reporting(1), ToggleResult::Placed);
+    assert!(board.is_queen(1));
+
+    // attacked by the queen of the index 1
+    assert_eq!(board.toggle_reporting(2), ToggleResult::Ignored);
+    assert!(!board.is_queen(2));
+    assert_eq!(board.sorted_queens().collect::<Vec<_>>(), vec![1]);
+
+    assert_eq!(board.toggle_reporting(1), ToggleResult::Removed);
+    assert!(board.is_empty());
+    assert!(board.cells().all(Cell::is_free));
+
+    // the fallible toggle reports the same actions, and the indexes past the board
+    assert_eq!(board.try_toggle(5), Ok(ToggleResult::Placed));
+    assert_eq!(board.try_toggle(6), Ok(ToggleResult::Ignored));
+    assert_eq!(board.try_toggle(16), Err(PlacementError::OutOfBounds(16)));
+    assert_eq!(board.sorted_queens().collect::<Vec<_>>(), vec![5]);
+    assert_eq!(board.try_toggle(5), Ok(ToggleResult::Removed));
+    assert!(board.is_empty());
+
+    // the blocks are told apart from the attacks, even on an attacked cell
+    board.toggle(0).block(1).block(6);
+    assert_eq!(board.toggle_reporting(1), ToggleResult::Blocked);
+    assert_eq!(board.try_toggle(6), Ok(ToggleResult::Blocked));
+    assert_eq!(board.sorted_queens().collect::<Vec<_>>(), vec![0]);
+}
+
+#[test]
+fn toggle_attacked_works() {
+    let mut board = Board::new(4);
+    board.toggle_attacked(1).toggle_attacked(2);
+    assert_eq!(board.sorted_queens().collect::<Vec<_>>(), vec![1, 2]);
+    assert!(!board.is_valid());
+    assert!(board.validate_invariants().is_ok());
+
+    // lifting the queens restores the cells
+    board.toggle_attacked(1).toggle_attacked(2);
+    assert!(board.is_empty());
+    assert!(board.cells().all(Cell::is_free));
+
+    board.block(3).toggle_attacked(3);
+    assert!(!board.is_queen(3));
+}
+
+#[test]
+fn single_cell_board_works() {
+    let mut board = Board::new(1);
+    assert_eq!(board.traverse_boundaries(0).count(), 4);
+    assert_eq!(board.toggle_reporting(0), ToggleResult::Placed);
+    assert!(board.is_solved());
+    assert_eq!(board.toggle_reporting(0), ToggleResult::Removed);
+    crate::test_utils::assert_board_consistent(&board);
+}
+
+#[test]
+fn reset_works() {
+    let mut board = Board::new(8);
+    board.toggle(0).toggle(10);
+
+    board.reset(4);
+    assert_eq!(board, Board::new(4));
+    board.toggle(1).reset(6);
+    assert_eq!(board, Board::new(6));
+}
+
+#[test]
+fn rotate_cases() {
+    fn case<Q>(width: usize, queens: Q, output: Q)
+    where
+        Q: IntoIterator<Item = usize>,
+    {
+        let board = Board::from_queens(width, queens).unwrap();
+        let output = output.into_iter().collect::<Vec<_>>();
+        let mut rotated = board.clone();
+        rotated.rotate_clockwise();
+        assert_eq!(
+            rotated.sorted_queens().collect::<Vec<_>>(),
+            output,
+            "failed for width {width}"
+        );
+        rotated.rotate_counterclockwise();
+        assert_eq!(rotated, board, "failed for width {width}");
+    }
+
+    case(
+        8,
+        [3, 14, 18, 31, 33, 44, 48, 61],
+        [1, 11, 21, 31, 34, 40, 54, 60],
+    );
+    case(8, [27], [28]);
+    case(8, [28], [36]);
+    case(8, [36], [35]);
+    case(8, [35], [27]);
+    case(9, [40], [40]);
+    case(9, [31], [41]);
+    case(9, [41], [49]);
+    case(9, [49], [39]);
+    case(9, [39], [31]);
+    case(0, [], []);
+    case(1, [0], [0]);
+}
+
+#[test]
+fn four_rotations_are_the_identity() {
+    for width in [1, 2, 3, 4, 5, 7, 8, 9, 12] {
+        let mut board = Board::new(width);
+        // a queen on the first row and another off every axis of symmetry, where there is room
+        board.toggle(width / 3);
+        if let Some(index) = board
+            .index_of(width - 1, 2)
+            .filter(|i| !board.cell(*i).unwrap().is_attacked())
+        {
+            board.toggle(index);
+        }
+        let original = board.clone();
+
+        for _ in 0..4 {
+            board.rotate_clockwise();
+            assert_eq!(board.validate_invariants(), Ok(()));
+        }
+        assert_eq!(board, original, "failed for width {width}");
+        for _ in 0..4 {
+            board.rotate_counterclockwise();
+        }
+        assert_eq!(board, original, "failed for width {width}");
+    }
+}
+
+#[test]
+fn rotated_works() {
+    let mut board = Board::new(5);
+    board.toggle(1).toggle(8).toggle(10);
+
+    let mut expected = board.clone();
+    expected.rotate_clockwise();
+    assert_eq!(board.rotated(1), expected);
+    assert_eq!(expected.sorted_queens().collect::<Vec<_>>(), vec![2, 9, 18]);
+
+    // the original is kept intact
+    assert_eq!(board.sorted_queens().collect::<Vec<_>>(), vec![1, 8, 10]);
+    assert_eq!(board.rotated(4), board);
+    assert_eq!(board.rotated(2), board.rotated(1).rotated(1));
+    assert_eq!(board.rotated(3).rotated(1), board);
+}
+
+#[test]
+fn flipped_works() {
+    let mut board = Board::new(5);
+    board.toggle(1).toggle(8);
+
+    let queens = |b: Board| b.sorted_queens().collect::<Vec<_>>();
+    assert_eq!(queens(board.flipped(Direction::Horizontal)), vec![18, 21]);
+    assert_eq!(queens(board.flipped(Direction::Vertical)), vec![3, 6]);
+    assert_eq!(queens(board.flipped(Direction::Principal)), vec![5, 16]);
+    assert_eq!(queens(board.flipped(Direction::Antidiagonal)), vec![8, 19]);
+
+    for axis in Direction::ALL {
+        assert_eq!(board.flipped(axis).flipped(axis), board);
+        board.flipped(axis).validate_invariants().unwrap();
+    }
+}
+
+#[test]
+fn queens_bitset_works() {
+    let a = Board::from_permutation(&[0, 4, 7, 5, 2, 6, 1, 3]).unwrap();
+    let b = Board::from_permutation(&[0, 5, 7, 2, 6, 3, 1, 4]).unwrap();
+    let (bits_a, bits_b) = (a.queens_bitset(), b.queens_bitset());
+    assert_eq!(bits_a.len(), 1);
+
+    // the intersection of the bits is the set of the shared queens
+    let shared = bits_a
+        .iter()
+        .zip(&bits_b)
+        .map(|(a, b)| a & b)
+        .collect::<Vec<_>>();
+    let expected = a
+        .sorted_queens()
+        .filter(|q| b.is_queen(*q))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, vec![0, 23, 49]);
+    let mut board = Board::new(8);
+    expected.iter().for_each(|q| {
+        board.toggle(*q);
+    });
+    assert_eq!(shared, board.queens_bitset());
+
+    // the words cover the cells past the first 64
+    let mut board = Board::new(9);
+    board.toggle(80);
+    assert_eq!(board.queens_bitset(), vec![0, 1 << 16]);
+    assert_eq!(Board::new(0).queens_bitset(), Vec::<u64>::new());
+}
+
+#[test]
+fn costs_work() {
+    let board = Board::new(2);
+    assert!(!board.has_costs());
+    assert_eq!(board.cost(3), 0.0);
+
+    let mut board = Board::with_costs(3, (0..9).map(|c| c as f64).collect());
+    assert!(board.has_costs());
+    board.toggle(1).toggle(6);
+    assert_eq!(board.queens_cost(), 7.0);
+
+    // the costs are part of the equality
+    let mut plain = Board::new(3);
+    plain.toggle(1).toggle(6);
+    assert_ne!(board, plain);
+    board.reset(3).toggle(1).toggle(6);
+    assert_eq!(board, plain);
+}
+
+#[test]
+fn conflicts_work() {
+    // assembles the cells of the queens, attacking each other or not
+    let assemble = |width: usize, queens: &[usize]| {
+        let lines = Lines::new(width);
+        let mut cells = vec![Cell::default(); width * width];
+        for (index, cell) in cells.iter_mut().enumerate() {
+            let ids = lines.line_ids(index);
+            for q in queens.iter().map(|q| lines.line_ids(*q)) {
+                if q.row == ids.row {
+                    cell.attack_horizontal();
+                }
+                if q.column == ids.column {
+                    cell.attack_vertical();
+                }
+                if q.principal == ids.principal {
+                    cell.attack_principal();
+                }
+                if q.antidiagonal == ids.antidiagonal {
+                    cell.attack_antidiagonal();
+                }
+            }
+            if queens.contains(&index) {
+                cell.put_queen();
+            }
+        }
+        Board::from_parts(BoardParts::new(
+            width,
+            cells,
+            queens.iter().copied().collect(),
+        ))
+        .unwrap()
+    };
+
+    assert_eq!(Board::new(4).conflict_count(), 0);
+
+    // two queens on the same row
+    let board = assemble(4, &[1, 3]);
+    assert_eq!(
+        board.conflicts().collect::<Vec<_>>(),
+        [(1, 3, Direction::Horizontal)]
+    );
+    assert!(!board.is_valid());
+
+    // two queens on the same diagonal, and a third on the column of the first
+    let board = assemble(4, &[0, 5, 12]);
+    assert_eq!(
+        board.conflicts().collect::<Vec<_>>(),
+        [(0, 5, Direction::Principal), (0, 12, Direction::Vertical)]
+    );
+    assert!(!board.is_valid());
+
+    // a full board of attacking queens isn't solved
+    let board = assemble(4, &[0, 1, 2, 3]);
+    assert_eq!(board.conflict_count(), 6);
+    assert!(!board.is_solved());
+
+    // a genuine solution
+    let board = Board::from_permutation(&[0, 4, 7, 5, 2, 6, 1, 3]).unwrap();
+    assert_eq!(board.conflicts().next(), None);
+    assert_eq!(board.conflict_count(), 0);
+    assert!(board.is_valid());
+    assert!(board.is_solved());
+    assert!(Board::new(8).is_valid());
+}
+
+#[test]
+fn heatmap_works() {
+    let mut board = Board::new(6);
+    assert!(board.heatmap().all(|c| c == 0));
+
+    // the column of the queen of (0, 0) crosses the row of the queen of (3, 1)
+    board.toggle(0).toggle(9);
+    assert_eq!(board.attack_count(6), 2);
+    assert_eq!(board.attack_count(27), 1);
+    assert_eq!(board.attack_count(0), 0);
+
+    // the principal diagonal of the queen of (1, 2) runs through the same cell
+    board.toggle(13);
+    assert_eq!(board.attack_count(6), 3);
+    assert_eq!(board.attack_count(34), 1);
+
+    // every count matches the queens sharing a line with the cell
+    let lines = board.lines().clone();
+    let heatmap = board.heatmap().collect::<Vec<_>>();
+    assert_eq!(heatmap.len(), 36);
+    for (index, count) in heatmap.into_iter().enumerate() {
+        let ids = lines.line_ids(index);
+        let expected = board
+            .sorted_queens()
+            .filter(|q| *q != index && lines.line_ids(*q).shared(&ids).is_some())
+            .count();
+        assert_eq!(usize::from(count), expected);
+    }
+
+    // the own cell of a piece is attacked only along the lines of the piece
+    for piece in [Piece::Rook, Piece::Bishop, Piece::Amazon] {
+        let mut board = Board::with_piece(6, piece);
+        board.toggle(0).toggle(9).toggle(26);
+        assert_eq!(board.sorted_queens().count(), 3, "{piece:?}");
+        assert_eq!(board.attack_count(0), 0, "{piece:?}");
+        for (index, count) in board.heatmap().enumerate() {
+            let expected = board
+                .sorted_queens()
+                .filter(|q| {
+                    *q != index
+                        && (Direction::ALL
+                            .into_iter()
+                            .any(|d| board.attacks_along(*q, index, d))
+                            || board.knight_targets(*q).any(|t| t == index))
+                })
+                .count();
+            assert_eq!(usize::from(count), expected, "{piece:?} {index}");
+        }
+    }
+}
+
+#[test]
+fn toroidal_heatmap_counts_the_queens_once() {
+    // the diagonals of the corner cross again on (2, 2) of the even torus
+    let mut board = Board::new_toroidal(4);
+    board.toggle(0);
+    assert_eq!(
+        board.heatmap().collect::<Vec<_>>(),
+        [0, 1, 1, 1, 1, 1, 0, 1, 1, 0, 1, 0, 1, 1, 0, 1]
+    );
+
+    // every count matches the distinct queens attacking the cell
+    for width in [5, 6] {
+        let mut board = Board::new_toroidal(width);
+        board.toggle(0).toggle(width + 2);
+        assert_eq!(board.sorted_queens().count(), 2);
+        for (index, count) in board.heatmap().enumerate() {
+            let expected = board
+                .sorted_queens()
+                .filter(|q| {
+                    *q != index
+                        && Direction::ALL
+                            .into_iter()
+                            .any(|d| board.attacks_along(*q, index, d))
+                })
+                .count();
+            assert_eq!(usize::from(count), expected, "{width} {index}");
+        }
+    }
+}
+
+#[test]
+fn resize_works() {
+    // the queens of the last rows and columns don't fit the smaller board
+    let mut board = Board::from_permutation(&[0, 4, 7, 5, 2, 6, 1, 3]).unwrap();
+    assert_eq!(board.resize(6), Ok(vec![23, 46, 49, 59]));
+    assert_eq!(board.sorted_queens().collect::<Vec<_>>(), [0, 10, 23, 26]);
+    assert_eq!(board.validate_invariants(), Ok(()));
+
+    // the queens keep their coordinates on a larger board
+    let mut board = Board::from_permutation(&[1, 3, 0, 2]).unwrap();
+    assert_eq!(board.resize(6), Ok(vec![]));
+    assert_eq!(board.sorted_queens().collect::<Vec<_>>(), [1, 9, 12, 20]);
+    assert_eq!(board.validate_invariants(), Ok(()));
+    assert_eq!(board.resize(0), Ok(vec![1, 9, 12, 20]));
+    assert!(board.is_empty());
+
+    // the costs of the new cells are unknown
+    let mut board = Board::with_costs(2, vec![1.0; 4]);
+    board.toggle(0);
+    assert_eq!(board.resize(3), Err(ResizeError::Costs));
+    assert_eq!(board.width(), 2);
+    assert!(board.is_queen(0));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn reflections_round_trip(
+        width in 1_usize..=9,
+        indices in proptest::collection::vec(0_usize..81, 0..9),
+    ) {
+        // the attacked indices are ignored by the toggles
+        let mut board = Board::new(width);
+        for index in indices.into_iter().filter(|i| *i < width * width) {
+            board.toggle(index);
+        }
+        let queens = board.sorted_queens().collect::<Vec<_>>();
+
+        let mut transposed = board.clone();
+        transposed.transpose();
+        proptest::prop_assert_eq!(transposed.validate_invariants(), Ok(()));
+        transposed.transpose();
+        proptest::prop_assert_eq!(transposed.sorted_queens().collect::<Vec<_>>(), queens.clone());
+
+        let mut mirrored = board.clone();
+        mirrored.mirror_horizontal();
+        proptest::prop_assert_eq!(mirrored.validate_invariants(), Ok(()));
+        mirrored.mirror_horizontal().mirror_vertical();
+        proptest::prop_assert_eq!(mirrored.validate_invariants(), Ok(()));
+        mirrored.mirror_vertical();
+        proptest::prop_assert_eq!(mirrored.sorted_queens().collect::<Vec<_>>(), queens);
+
+        // both mirrors make a half turn
+        let mut turned = board.clone();
+        turned.mirror_horizontal().mirror_vertical();
+        proptest::prop_assert_eq!(turned, board.rotated(2));
+    }
+}
+
+#[test]
+fn eq_up_to_symmetry_works() {
+    let solution = Board::from_permutation(&[0, 4, 7, 5, 2, 6, 1, 3]).unwrap();
+
+    // a reflection which isn't a rotation
+    let mirrored = solution.flipped(Direction::Vertical);
+    assert!((0..4).all(|turns| solution.rotated(turns) != mirrored));
+    assert!(solution.eq_up_to_symmetry(&mirrored));
+    assert!(mirrored.eq_up_to_symmetry(&solution));
+    assert!(solution.eq_up_to_symmetry(&solution.flipped(Direction::Principal).rotated(1)));
+    assert_eq!(solution.canonical_key(), mirrored.canonical_key());
+
+    // another solution from the same corner, of another orbit
+    let other = Board::from_permutation(&[0, 5, 7, 2, 6, 3, 1, 4]).unwrap();
+    assert!(!solution.eq_up_to_symmetry(&other));
+    assert_ne!(solution.canonical_key(), other.canonical_key());
+
+    // the boards of other widths or queens
+    assert!(!Board::new(8).eq_up_to_symmetry(&Board::new(9)));
+    let mut partial = solution.clone();
+    partial.toggle(0);
+    assert!(!solution.eq_up_to_symmetry(&partial));
+    assert!(Board::new(5).eq_up_to_symmetry(&Board::new(5)));
+}
+
+#[test]
+fn hash64_works() {
+    let mut board = Board::new(8);
+    assert_eq!(board.hash64(), 0);
+
+    // a toggle is undone by another
+    board.toggle(3).toggle(14);
+    let hash = board.hash64();
+    board.toggle(29);
+    assert_ne!(board.hash64(), hash);
+    board.toggle(29);
+    assert_eq!(board.hash64(), hash);
+
+    // the hash depends on the queens only, however they were placed
+    let placed = Board::from_queens(8, [14, 3]).unwrap();
+    assert_eq!(placed.hash64(), hash);
+    assert_eq!(
+        Board::from_parts(placed.into_parts()).unwrap().hash64(),
+        hash
+    );
+    let mut rotated = board.rotated(1);
+    let expected = Board::from_queens(8, rotated.sorted_queens()).unwrap();
+    assert_eq!(rotated.hash64(), expected.hash64());
+    rotated.rotate_counterclockwise();
+    assert_eq!(rotated.hash64(), hash);
+
+    board.take_queens();
+    assert_eq!(board.hash64(), 0);
+    board.toggle(3).clear();
+    assert_eq!(board.hash64(), 0);
+}
+
+#[test]
+fn hash64_has_no_collisions_on_a_sample() {
+    use alloc::collections::BTreeMap;
+
+    // seeded, so the sample is reproducible
+    let mut rng = crate::test_utils::XorShift::new(0x2545_f491_4f6c_dd1d);
+    let mut random = |max: usize| rng.below(max);
+
+    // the keys are drawn per width, so only the boards of a width are told apart
+    let mut hashes = BTreeMap::new();
+    for _ in 0..5000 {
+        let width = 6 + random(7);
+        let mut board = Board::new(width);
+        for _ in 0..2 + random(width - 1) {
+            board.toggle(random(width * width));
+        }
+        let queens = board.sorted_queens().collect::<Vec<_>>();
+        let previous = hashes.insert((width, board.hash64()), queens.clone());
+        assert!(previous.is_none_or(|p| p == queens));
+    }
+    assert!(hashes.len() > 4000);
+}
+
+#[test]
+fn safe_cells_match_the_cells() {
+    // seeded, so the sequences are reproducible
+    let mut rng = crate::test_utils::XorShift::new(0x9e37_79b9_7f4a_7c15);
+    let mut random = |max: usize| rng.below(max);
+
+    // the safe cells of either backend are the free cells of a scan
+    for _ in 0..2000 {
+        let width = 1 + random(12);
+        let mut board = Board::new(width);
+        for _ in 0..random(3 * width) {
+            board.toggle(random(width * width));
+            let expected = board
+                .cells()
+                .enumerate()
+                .filter_map(|(i, c)| c.is_free().then_some(i))
+                .collect::<Vec<_>>();
+            assert_eq!(board.available().collect::<Vec<_>>(), expected);
+            assert!(board.sorted_queens().all(|q| board.is_queen(q)));
+        }
+        board.rotate_clockwise();
+        assert_eq!(
+            board.safe_cells().count(),
+            board.cells().filter(|c| c.is_free()).count()
+        );
+        board.reset(width + 1);
+        assert_eq!(board.safe_cells().count(), (width + 1) * (width + 1));
+    }
+}
+
+#[test]
+fn blocked_cells_are_never_available() {
+    let mut rng = crate::test_utils::XorShift::new(0x2545_f491_4f6c_dd1d);
+    let mut random = |max: usize| rng.below(max);
+
+    for cut_rays in [false, true] {
+        for _ in 0..500 {
+            let width = 1 + random(10);
+            let mut board = match cut_rays {
+                true => Board::with_cut_rays(width),
+                false => Board::new(width),
+            };
+            for _ in 0..random(3 * width) {
+                match random(3) {
+                    0 => {
+                        board.block(random(width * width));
+                    }
+                    1 => {
+                        let _ = board.unblock(random(width * width));
+                    }
+                    _ => {
+                        board.toggle(random(width * width));
+                    }
+                }
+                assert!(board.available().all(|i| !board.cells[i].is_blocked()));
+                assert_eq!(board.validate_invariants(), Ok(()));
+                assert!(board.is_valid());
+            }
+            board.rotate_clockwise().mirror_horizontal();
+            assert!(board.available().all(|i| !board.cells[i].is_blocked()));
+            assert_eq!(board.validate_invariants(), Ok(()));
+        }
+    }
+
+    // a blocked cell is skipped by the toggles, and the block lifts its queen
+    let mut board = Board::new(4);
+    board.toggle(5).block(5).block(10).toggle(10);
+    assert!(board.is_empty());
+    assert_eq!(board.blocked_cells().collect::<Vec<_>>(), [5, 10]);
+    assert_eq!(board.available().count(), 14);
+    assert_eq!(board.move_queen(0, 5), Err(PlacementError::MissingQueen(0)));
+    board.toggle(0);
+    assert_eq!(board.move_queen(0, 10), Err(PlacementError::Attacked(10)));
+    assert_eq!(board.to_string(), "Q x x x\nx # . .\nx . # .\nx . . x\n");
+    board.clear();
+    assert_eq!(board.blocked_cells().count(), 2);
+    board.reset(4);
+    assert_eq!(board.blocked_cells().count(), 0);
+}
+
+#[test]
+fn cut_rays_shield_the_queens() {
+    let mut board = Board::with_cut_rays(5);
+    board.block(2).toggle(0);
+
+    // the queen behind the block isn't attacked, and takes the same row
+    assert!(board.cells[1].is_attacked());
+    assert!(!board.cells[3].is_attacked() && !board.cells[4].is_attacked());
+    board.toggle(4);
+    assert!(board.is_queen(4) && board.is_valid());
+    assert_eq!(board.conflicts().count(), 0);
+    assert_eq!(
+        board.to_string(),
+        "Q x # x Q\nx x . x x\nx . x . x\nx x . x x\nx . . . x\n"
+    );
+
+    // the shielded queens would attack each other, so the block stays
+    assert_eq!(
+        board.unblock(2),
+        Err(PlacementError::Conflict {
+            index: 4,
+            queen: 0,
+            direction: Direction::Horizontal
+        })
+    );
+    assert!(board.cells[2].is_blocked() && board.is_queen(4));
+    assert_eq!(board.validate_invariants(), Ok(()));
+
+    // a queen between the block and the former attacker is shielded once blocked
+    board.toggle(4).block(1);
+    assert!(!board.cells[2].is_attacked() && board.cells[4].is_free());
+    assert_eq!(board.unblock(1), Ok(()));
+    assert!(board.cells[4].is_free() && !board.cells[3].is_attacked());
+    assert_eq!(board.validate_invariants(), Ok(()));
+
+    // the plain blocks don't stop the attacks
+    let mut board = Board::new(5);
+    board.block(2).toggle(0);
+    assert!(board.cells[4].is_attacked() && board.cells[2].is_attacked());
+    assert_eq!(board.unblock(2), Ok(()));
+    assert_ne!(Board::new(5), Board::with_cut_rays(5));
+}
+
+#[test]
+fn toroidal_attacks_wrap() {
+    let mut board = Board::new_toroidal(5);
+    board.toggle(0);
+
+    // the antidiagonal of the corner wraps to the last column, the principal one is whole
+    for i in [9, 13, 17, 21] {
+        assert!(board.cells[i].is_attacked_antidiagonal(), "{i}");
+    }
+    for i in [6, 12, 18, 24] {
+        assert!(board.cells[i].is_attacked_principal(), "{i}");
+    }
+    assert_eq!(
+        board.available().collect::<Vec<_>>(),
+        [7, 8, 11, 14, 16, 19, 22, 23]
+    );
+
+    // every line is traversed once, with the width of the board
+    let traversed = board
+        .traverse_boundaries(0)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    assert_eq!(traversed.len(), 20);
+    assert_eq!(traversed[15..], [0, 9, 13, 17, 21]);
+    assert!(Direction::ALL
+        .into_iter()
+        .all(|d| board.line_length(0, d) == 5));
+    assert_eq!(board.attack_delta(7).total(), 4);
+
+    // a queen attacking across the edge is a conflict, unlike on the plain board
+    assert_eq!(
+        board.toggle(14).sorted_queens().collect::<Vec<_>>(),
+        [0, 14]
+    );
+    assert!(board.is_valid());
+    let mut plain = Board::new(5);
+    plain.toggle(0).toggle(9);
+    assert_eq!(Board::new_toroidal(5).toggle(0).toggle(9).queens.len(), 1);
+    assert_eq!(plain.queens.len(), 2);
+
+    // the toggles keep the attacks of the wrapped lines
+    for _ in 0..2 {
+        board.toggle(14).toggle(7).toggle(0);
+        assert_eq!(board.validate_invariants(), Ok(()));
+    }
+    board.clear();
+    assert!(board.is_toroidal() && board.cells.iter().all(Cell::is_free));
+    assert_ne!(board, Board::new(5));
+}
+
+#[test]
+fn amazons_attack_the_knight_moves() {
+    let mut board = Board::with_piece(6, Piece::Amazon);
+    assert_eq!(board.knight_targets(0).collect::<Vec<_>>(), [8, 13]);
+    assert_eq!(board.knight_targets(6).collect::<Vec<_>>(), [2, 14, 19]);
+    assert_eq!(board.knight_targets(14).count(), 8);
+    assert_eq!(Board::new(6).knight_targets(14).count(), 0);
+
+    // the knight cells of the corner are attacked, and lifted with the amazon
+    board.toggle(0);
+    assert!(board.cells[8].is_attacked_knight() && board.cells[13].is_attacked_knight());
+    assert!(!board.available().any(|i| i == 8 || i == 13));
+    assert_eq!(
+        board.available().count(),
+        Board::new(6).toggle(0).available().count() - 2
+    );
+    board.toggle(0);
+    assert!(board.cells.iter().all(Cell::is_free));
+
+    // the knight cells of the edges stay on the board, and a move keeps them consistent
+    board.toggle(35).toggle(6);
+    assert!(board.cells[2].is_attacked_knight() && board.cells[22].is_attacked_knight());
+    assert_eq!(board.move_queen(6, 22), Err(PlacementError::Attacked(22)));
+    assert_eq!(board.move_queen(6, 8), Ok(()));
+    assert!(!board.cells[2].is_attacked_knight() && board.cells[4].is_attacked_knight());
+    assert_eq!(board.validate_invariants(), Ok(()));
+    assert!(board.is_valid() && board.knight_conflicts().next().is_none());
+
+    // the toggles agree with the invariants from scratch
+    let mut rng = crate::test_utils::XorShift::new(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..300 {
+        board.toggle(rng.below(36));
+        assert_eq!(board.validate_invariants(), Ok(()));
+        assert!(board.is_valid());
+    }
+}
+
+#[test]
+fn rooks_and_bishops_attack_their_lines() {
+    let mut rooks = Board::with_piece(5, Piece::Rook);
+    rooks.toggle(12);
+    assert_eq!(rooks.available().count(), 16);
+    assert!(rooks.is_free(0) && !rooks.is_free(2) && !rooks.is_free(10));
+    assert_eq!(rooks.traverse_boundaries(12).count(), 10);
+    assert_eq!(rooks.target_count(), 5);
+
+    let mut bishops = Board::with_piece(5, Piece::Bishop);
+    bishops.toggle(12);
+    assert_eq!(bishops.available().count(), 16);
+    assert!(!bishops.is_free(0) && bishops.is_free(2) && bishops.is_free(10));
+    assert_eq!(bishops.traverse_boundaries(12).count(), 10);
+    assert_eq!(bishops.target_count(), 8);
+    assert_eq!(Board::with_piece(1, Piece::Bishop).target_count(), 1);
+    assert_eq!(Board::with_piece(5, Piece::Bishop).target_count(), 8);
+
+    // the bishops share the rows, so a solution holds more of them than the width
+    bishops.toggle(12);
+    for i in [0, 1, 2, 3, 4, 21, 22, 23] {
+        bishops.toggle(i);
+    }
+    assert_eq!(bishops.sorted_queens().count(), 8);
+    assert!(bishops.is_solved() && bishops.validate_invariants().is_ok());
+
+    // the rooks of a diagonal don't attack each other, and a move keeps their lines consistent
+    rooks.toggle(18);
+    assert!(rooks.is_valid() && rooks.conflicts().next().is_none());
+    assert_eq!(rooks.move_queen(18, 14), Err(PlacementError::Attacked(14)));
+    assert_eq!(rooks.move_queen(18, 24), Ok(()));
+    assert_eq!(rooks.validate_invariants(), Ok(()));
+    assert!(!rooks.is_solved());
 }
@@ -0,0 +1,114 @@
+use crate::{Board, Vec};
+use core::hash::{Hash, Hasher};
+
+/// A board compared and hashed by its orbit under the symmetries of the square, so the symmetric
+/// boards are the same key of a map or set.
+///
+/// The canonical key of [`Board::canonical_key`] is computed once, on construction.
+#[derive(Debug, Clone)]
+pub struct CanonicalBoard {
+    board: Board,
+    key: Vec<usize>,
+}
+
+impl CanonicalBoard {
+    pub fn new(board: Board) -> Self {
+        let key = board.canonical_key();
+        Self { board, key }
+    }
+
+    /// The wrapped board, as it was constructed.
+    pub const fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The canonical key of the board, shared by all the boards of its orbit.
+    pub fn key(&self) -> &[usize] {
+        &self.key
+    }
+
+    pub fn into_inner(self) -> Board {
+        self.board
+    }
+}
+
+impl From<Board> for CanonicalBoard {
+    fn from(board: Board) -> Self {
+        Self::new(board)
+    }
+}
+
+impl PartialEq for CanonicalBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.board.width() == other.board.width() && self.key == other.key
+    }
+}
+
+impl Eq for CanonicalBoard {}
+
+impl Hash for CanonicalBoard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.board.width().hash(state);
+        self.key.hash(state);
+    }
+}
+
+#[test]
+fn canonical_board_works() {
+    fn solutions(board: &mut Board, row: usize, found: &mut Vec<Board>) {
+        if row == board.width() {
+            found.push(board.clone());
+            return;
+        }
+        for column in 0..board.width() {
+            let index = row * board.width() + column;
+            if board.cells().nth(index).is_some_and(|c| c.is_free()) {
+                board.toggle(index);
+                solutions(board, row + 1, found);
+                board.toggle(index);
+            }
+        }
+    }
+
+    for (width, fundamental) in [(1, 1), (4, 1), (6, 1), (8, 12)] {
+        let mut found = Vec::new();
+        solutions(&mut Board::new(width), 0, &mut found);
+
+        // every symmetry of every solution collapses into its orbit
+        let canonical = found
+            .iter()
+            .flat_map(|b| b.symmetries())
+            .map(CanonicalBoard::from)
+            .collect::<Vec<_>>();
+        let mut orbits = Vec::new();
+        for board in &canonical {
+            if !orbits.contains(&board) {
+                orbits.push(board);
+            }
+        }
+        assert_eq!(orbits.len(), fundamental);
+
+        // the boards of an orbit also hash alike, checked where `std` provides a hash set
+        #[cfg(feature = "std")]
+        assert_eq!(
+            canonical
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            fundamental
+        );
+    }
+
+    // the empty boards of different widths share the empty key
+    let empty = [Board::new(4), Board::new(5)].map(CanonicalBoard::new);
+    assert_eq!(empty[0].key(), empty[1].key());
+    assert_ne!(empty[0], empty[1]);
+
+    let mut board = Board::new(5);
+    board.toggle(1);
+    let canonical = CanonicalBoard::new(board.rotated(1));
+    assert_eq!(canonical, CanonicalBoard::new(board.clone()));
+    assert_eq!(canonical.board(), &board.rotated(1));
+    assert_eq!(canonical.key(), [1]);
+    assert_eq!(canonical.into_inner(), board.rotated(1));
+}
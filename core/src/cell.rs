@@ -1,16 +1,56 @@
+use crate::{Direction, InvalidCell, Piece};
+
+/// The content of a cell: a queen flag, the number of queens attacking the cell per direction
+/// and by a knight move, and a block flag, packed into a word.
+///
+/// The counters are saturated at [`Cell::MAX_ATTACKERS`], and at [`Cell::MAX_KNIGHTS`] for the
+/// knight moves of the amazons; see [`Piece`]. A queen attacks its own cell in every direction
+/// of its piece. A blocked cell can't hold a queen; see [`crate::Board::block`].
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "u32", into = "u32")
+)]
 pub struct Cell {
-    content: u8,
+    content: u32,
 }
 
 impl Cell {
-    const QUEEN: u8 = 1;
-    const HORIZONTAL: u8 = 1 << 1;
-    const VERTICAL: u8 = 1 << 2;
-    const PRINCIPAL: u8 = 1 << 3;
-    const ANTIDIAGONAL: u8 = 1 << 4;
+    /// The attackers counted per direction.
+    pub const MAX_ATTACKERS: u8 = (1 << Cell::COUNTER_BITS) - 1;
+    /// The attackers counted by a knight move, more than the 8 cells a knight move away.
+    pub const MAX_KNIGHTS: u8 = (1 << Cell::KNIGHT_BITS) - 1;
+
+    const QUEEN: u32 = 1;
+    const COUNTER_BITS: u32 = 6;
+    const KNIGHT_BITS: u32 = 4;
+    const HORIZONTAL: u32 = 1;
+    const VERTICAL: u32 = Cell::HORIZONTAL + Cell::COUNTER_BITS;
+    const PRINCIPAL: u32 = Cell::VERTICAL + Cell::COUNTER_BITS;
+    const ANTIDIAGONAL: u32 = Cell::PRINCIPAL + Cell::COUNTER_BITS;
+    const KNIGHT: u32 = Cell::ANTIDIAGONAL + Cell::COUNTER_BITS;
+    const BLOCKED: u32 = 1 << (Cell::KNIGHT + Cell::KNIGHT_BITS);
+    const MASK: u32 = (Cell::BLOCKED << 1) - 1;
+
+    /// Creates a cell from the flags of the former byte layout: the queen on the bit 0, and an
+    /// attack on the horizontal, vertical, principal and antidiagonal lines on the bits 1 to 4.
+    /// Every attack flag is converted into a single attacker; the other bits are dropped.
+    #[deprecated(note = "the attackers are counted in a word; use `Cell::from_bits`")]
+    pub const fn new(flags: u8) -> Self {
+        let flags = flags as u32;
+        Self {
+            content: (flags & Cell::QUEEN)
+                | ((flags >> 1) & 1) << Cell::HORIZONTAL
+                | ((flags >> 2) & 1) << Cell::VERTICAL
+                | ((flags >> 3) & 1) << Cell::PRINCIPAL
+                | ((flags >> 4) & 1) << Cell::ANTIDIAGONAL,
+        }
+    }
 
-    pub const fn new(content: u8) -> Self {
+    /// Creates a cell from its content word, as converted into a `u32`; see [`Cell::try_from`]
+    /// for the validated conversion.
+    pub const fn from_bits(content: u32) -> Self {
         Self { content }
     }
 
@@ -19,23 +59,70 @@ impl Cell {
     }
 
     pub const fn is_attacked(&self) -> bool {
-        self.content != 0
+        self.content & !Cell::BLOCKED != 0
+    }
+
+    pub const fn is_blocked(&self) -> bool {
+        (self.content & Cell::BLOCKED) == Cell::BLOCKED
     }
 
     pub const fn is_attacked_horizontal(&self) -> bool {
-        (self.content & Cell::HORIZONTAL) == Cell::HORIZONTAL
+        self.counter(Cell::HORIZONTAL) > 0
     }
 
     pub const fn is_attacked_vertical(&self) -> bool {
-        (self.content & Cell::VERTICAL) == Cell::VERTICAL
+        self.counter(Cell::VERTICAL) > 0
     }
 
     pub const fn is_attacked_principal(&self) -> bool {
-        (self.content & Cell::PRINCIPAL) == Cell::PRINCIPAL
+        self.counter(Cell::PRINCIPAL) > 0
     }
 
     pub const fn is_attacked_antidiagonal(&self) -> bool {
-        (self.content & Cell::ANTIDIAGONAL) == Cell::ANTIDIAGONAL
+        self.counter(Cell::ANTIDIAGONAL) > 0
+    }
+
+    pub const fn is_attacked_knight(&self) -> bool {
+        self.counter(Cell::KNIGHT) > 0
+    }
+
+    /// The number of amazons attacking the cell by a knight move.
+    pub const fn knight_attackers(&self) -> u8 {
+        self.counter(Cell::KNIGHT)
+    }
+
+    /// The number of queens attacking the cell in the direction.
+    pub const fn attackers(&self, direction: Direction) -> u8 {
+        let shift = match direction {
+            Direction::Horizontal => Cell::HORIZONTAL,
+            Direction::Vertical => Cell::VERTICAL,
+            Direction::Principal => Cell::PRINCIPAL,
+            Direction::Antidiagonal => Cell::ANTIDIAGONAL,
+        };
+        self.counter(shift)
+    }
+
+    /// The number of queens of the piece attacking the cell, other than its own queen, which
+    /// attacks the cell once per direction of the piece.
+    ///
+    /// Two cells of a flat board share at most one line, and a knight move leaves the lines, so
+    /// every attacker is counted in a single direction or by its knight move. The diagonals of
+    /// an even torus cross twice, so the cell can't tell its attackers apart there; see
+    /// [`crate::Board::attack_count`].
+    pub fn attack_count(&self, piece: Piece) -> u8 {
+        let total = Direction::ALL
+            .into_iter()
+            .map(|d| u16::from(self.attackers(d)))
+            .sum::<u16>()
+            + u16::from(self.knight_attackers());
+        let own = match self.is_queen() {
+            true => Direction::ALL
+                .into_iter()
+                .filter(|d| piece.attacks(*d))
+                .count() as u16,
+            false => 0,
+        };
+        u8::try_from(total - own).unwrap_or(u8::MAX)
     }
 
     pub const fn is_free(&self) -> bool {
@@ -47,6 +134,22 @@ impl Cell {
         self
     }
 
+    /// Removes the queen and the attacks of the cell, keeping its block.
+    pub fn lift_all(&mut self) -> &mut Self {
+        self.content &= Cell::BLOCKED;
+        self
+    }
+
+    pub fn block(&mut self) -> &mut Self {
+        self.content |= Cell::BLOCKED;
+        self
+    }
+
+    pub fn unblock(&mut self) -> &mut Self {
+        self.content &= !Cell::BLOCKED;
+        self
+    }
+
     pub fn put_queen(&mut self) -> &mut Self {
         self.content |= Cell::QUEEN;
         self
@@ -58,42 +161,152 @@ impl Cell {
     }
 
     pub fn attack_horizontal(&mut self) -> &mut Self {
-        self.content |= Cell::HORIZONTAL;
-        self
+        self.increment(Cell::HORIZONTAL)
     }
 
     pub fn attack_vertical(&mut self) -> &mut Self {
-        self.content |= Cell::VERTICAL;
-        self
+        self.increment(Cell::VERTICAL)
     }
 
     pub fn attack_principal(&mut self) -> &mut Self {
-        self.content |= Cell::PRINCIPAL;
-        self
+        self.increment(Cell::PRINCIPAL)
     }
 
     pub fn attack_antidiagonal(&mut self) -> &mut Self {
-        self.content |= Cell::ANTIDIAGONAL;
-        self
+        self.increment(Cell::ANTIDIAGONAL)
+    }
+
+    pub fn attack_knight(&mut self) -> &mut Self {
+        self.increment(Cell::KNIGHT)
     }
 
     pub fn lift_horizontal(&mut self) -> &mut Self {
-        self.content &= !Cell::HORIZONTAL;
-        self
+        self.decrement(Cell::HORIZONTAL)
     }
 
     pub fn lift_vertical(&mut self) -> &mut Self {
-        self.content &= !Cell::VERTICAL;
-        self
+        self.decrement(Cell::VERTICAL)
     }
 
     pub fn lift_principal(&mut self) -> &mut Self {
-        self.content &= !Cell::PRINCIPAL;
-        self
+        self.decrement(Cell::PRINCIPAL)
     }
 
     pub fn lift_antidiagonal(&mut self) -> &mut Self {
-        self.content &= !Cell::ANTIDIAGONAL;
+        self.decrement(Cell::ANTIDIAGONAL)
+    }
+
+    pub fn lift_knight(&mut self) -> &mut Self {
+        self.decrement(Cell::KNIGHT)
+    }
+
+    /// The saturation of the counter at the shift.
+    const fn max_counter(shift: u32) -> u8 {
+        match shift {
+            Cell::KNIGHT => Cell::MAX_KNIGHTS,
+            _ => Cell::MAX_ATTACKERS,
+        }
+    }
+
+    const fn counter(&self, shift: u32) -> u8 {
+        ((self.content >> shift) & Cell::max_counter(shift) as u32) as u8
+    }
+
+    fn increment(&mut self, shift: u32) -> &mut Self {
+        if self.counter(shift) < Cell::max_counter(shift) {
+            self.content += 1 << shift;
+        }
+        self
+    }
+
+    fn decrement(&mut self, shift: u32) -> &mut Self {
+        if self.counter(shift) > 0 {
+            self.content -= 1 << shift;
+        }
         self
     }
 }
+
+impl From<Cell> for u32 {
+    fn from(cell: Cell) -> Self {
+        cell.content
+    }
+}
+
+impl TryFrom<u32> for Cell {
+    type Error = InvalidCell;
+
+    fn try_from(content: u32) -> Result<Self, Self::Error> {
+        if content & !Cell::MASK == 0 {
+            Ok(Self::from_bits(content))
+        } else {
+            Err(InvalidCell(content))
+        }
+    }
+}
+
+#[test]
+fn attackers_are_counted() {
+    let mut cell = Cell::default();
+    cell.attack_horizontal()
+        .attack_horizontal()
+        .attack_principal();
+    assert_eq!(cell.attackers(Direction::Horizontal), 2);
+    assert_eq!(cell.attackers(Direction::Principal), 1);
+    assert_eq!(cell.attackers(Direction::Vertical), 0);
+
+    // a lift keeps the attack of the other queen
+    cell.lift_horizontal();
+    assert!(cell.is_attacked_horizontal());
+    cell.lift_horizontal().lift_horizontal().lift_principal();
+    assert!(cell.is_free());
+
+    // the counters saturate instead of overflowing into the next direction
+    for _ in 0..=Cell::MAX_ATTACKERS {
+        cell.attack_vertical();
+    }
+    assert_eq!(cell.attackers(Direction::Vertical), Cell::MAX_ATTACKERS);
+    assert!(!cell.is_attacked_principal());
+    for _ in 0..=Cell::MAX_KNIGHTS {
+        cell.attack_antidiagonal().attack_knight();
+    }
+    assert_eq!(cell.knight_attackers(), Cell::MAX_KNIGHTS);
+    assert!(!cell.is_blocked());
+    assert_eq!(Cell::try_from(u32::from(cell)), Ok(cell));
+    assert!(Cell::try_from(u32::MAX).is_err());
+
+    // the knight moves are counted apart from the lines
+    let mut cell = Cell::default();
+    cell.attack_knight().attack_knight().attack_horizontal();
+    assert!(cell.is_attacked_knight() && cell.is_attacked());
+    assert_eq!(
+        (cell.knight_attackers(), cell.attack_count(Piece::Amazon)),
+        (2, 3)
+    );
+    cell.lift_knight().lift_knight().lift_horizontal();
+    assert!(cell.is_free());
+
+    // the block is neither an attack nor a free cell, and survives the lifts
+    let mut cell = Cell::default();
+    cell.block();
+    assert!(cell.is_blocked() && !cell.is_attacked() && !cell.is_free());
+    cell.attack_principal().attack_knight().lift_all();
+    assert!(cell.is_blocked() && !cell.is_attacked());
+    assert_eq!(Cell::try_from(u32::from(cell)), Ok(cell));
+    cell.unblock();
+    assert!(cell.is_free());
+}
+
+#[test]
+#[allow(deprecated)]
+fn byte_flags_are_converted() {
+    let queen = Cell::new(0b1_1111);
+    assert!(queen.is_queen());
+    assert!(Direction::ALL.into_iter().all(|d| queen.attackers(d) == 1));
+    assert_eq!(queen.attack_count(Piece::Queen), 0);
+
+    let mut expected = Cell::default();
+    expected.attack_vertical().attack_antidiagonal();
+    assert_eq!(Cell::new(0b1_0100), expected);
+    assert_eq!(Cell::new(0b1110_0000), Cell::default());
+}
@@ -0,0 +1,126 @@
+use crate::{Board, ParseBoardError, Vec};
+use core::{fmt, str::FromStr};
+
+/// The compact text of a board, as its width and sorted queens: `8:0,12,23,29`; see
+/// [`Board::compact`].
+///
+/// The text is parsed back by the [`FromStr`] of [`Board`].
+#[derive(Debug, Clone, Copy)]
+pub struct Compact<'a> {
+    board: &'a Board,
+}
+
+impl<'a> Compact<'a> {
+    pub const fn new(board: &'a Board) -> Self {
+        Self { board }
+    }
+}
+
+impl fmt::Display for Compact<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.board.width())?;
+        for (i, queen) in self.board.sorted_queens().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{queen}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the compact text of a board, `width:queen,queen,...`.
+///
+/// The whitespace around the numbers is ignored, as is a trailing comma; a width without the
+/// colon is an empty board. The queens are placed as by [`Board::from_queens`], so the repeated
+/// or attacked queens are rejected.
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (width, queens) = s.split_once(':').unwrap_or((s, ""));
+        let width = width
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| ParseBoardError::InvalidWidth)?;
+        if crate::board::cell_count(width).is_none() {
+            return Err(ParseBoardError::WidthTooLarge(width));
+        }
+
+        let queens = queens.trim();
+        let queens = queens.strip_suffix(',').unwrap_or(queens);
+        let queens = match queens.is_empty() {
+            true => Vec::new(),
+            false => queens
+                .split(',')
+                .enumerate()
+                .map(|(i, q)| {
+                    q.trim()
+                        .parse::<usize>()
+                        .map_err(|_| ParseBoardError::InvalidQueen(i))
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        Board::from_queens(width, queens).map_err(ParseBoardError::Placement)
+    }
+}
+
+#[test]
+fn compact_round_trips() {
+    use crate::{PlacementError, ToString};
+
+    // the empty boards
+    assert_eq!("8".parse::<Board>(), Ok(Board::new(8)));
+    assert_eq!("8:".parse::<Board>(), Ok(Board::new(8)));
+    assert_eq!("0:".parse::<Board>(), Ok(Board::new(0)));
+    assert_eq!(Board::new(8).compact().to_string(), "8:");
+
+    // a full solution, with the whitespace and a trailing separator
+    let solution = Board::from_permutation(&[1, 3, 0, 2]).unwrap();
+    assert_eq!(solution.compact().to_string(), "4:1,7,8,14");
+    assert_eq!("4:1,7,8,14".parse::<Board>(), Ok(solution.clone()));
+    assert_eq!(" 4 : 14, 8,7 ,1,\n".parse::<Board>(), Ok(solution));
+
+    // the malformed input
+    assert_eq!("".parse::<Board>(), Err(ParseBoardError::InvalidWidth));
+    assert_eq!("x:1".parse::<Board>(), Err(ParseBoardError::InvalidWidth));
+    assert_eq!(
+        "4294967296:".parse::<Board>(),
+        Err(ParseBoardError::WidthTooLarge(4294967296))
+    );
+    assert_eq!(
+        usize::MAX.to_string().parse::<Board>(),
+        Err(ParseBoardError::WidthTooLarge(usize::MAX))
+    );
+    assert_eq!(
+        "4:1,,7".parse::<Board>(),
+        Err(ParseBoardError::InvalidQueen(1))
+    );
+    assert_eq!(
+        "4:1,7,,".parse::<Board>(),
+        Err(ParseBoardError::InvalidQueen(2))
+    );
+    assert_eq!(
+        "4:-1".parse::<Board>(),
+        Err(ParseBoardError::InvalidQueen(0))
+    );
+
+    // the indices past the board, and the conflicting queens
+    assert_eq!(
+        "4:1,16".parse::<Board>(),
+        Err(ParseBoardError::Placement(PlacementError::OutOfBounds(16)))
+    );
+    assert_eq!(
+        "4:1,1".parse::<Board>(),
+        Err(ParseBoardError::Placement(PlacementError::Duplicate(1)))
+    );
+    assert_eq!(
+        "4:0,5".parse::<Board>(),
+        Err(ParseBoardError::Placement(PlacementError::Conflict {
+            index: 5,
+            queen: 0,
+            direction: crate::Direction::Principal
+        }))
+    );
+}
@@ -0,0 +1,38 @@
+/// An attack line through a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+    /// The diagonal from the top-left to the bottom-right.
+    Principal,
+    /// The diagonal from the top-right to the bottom-left.
+    Antidiagonal,
+}
+
+impl Direction {
+    /// All the directions, in the order of [`crate::Board::traverse_boundaries`].
+    pub const ALL: [Direction; 4] = [
+        Direction::Horizontal,
+        Direction::Vertical,
+        Direction::Principal,
+        Direction::Antidiagonal,
+    ];
+}
+
+#[test]
+fn all_works() {
+    // the match breaks the build when a variant is added, so it must be added to `ALL` as well
+    let position = |direction: Direction| match direction {
+        Direction::Horizontal => 0,
+        Direction::Vertical => 1,
+        Direction::Principal => 2,
+        Direction::Antidiagonal => 3,
+    };
+    for (i, direction) in Direction::ALL.into_iter().enumerate() {
+        assert_eq!(position(direction), i);
+        assert_eq!(
+            Direction::ALL.iter().filter(|d| **d == direction).count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{Cell, Direction};
+use core::fmt;
+
+/// A queen couldn't be placed on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PlacementError {
+    /// The index is out of the bounds of the board.
+    OutOfBounds(usize),
+    /// The cell of the index is attacked by another queen.
+    Attacked(usize),
+    /// The cell of the index has no queen to be moved.
+    MissingQueen(usize),
+    /// The index repeats a queen placed before it.
+    Duplicate(usize),
+    /// The cell of the index is attacked by a queen placed before it, on the line of the
+    /// direction.
+    Conflict {
+        index: usize,
+        queen: usize,
+        direction: Direction,
+    },
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds(index) => write!(f, "the index {index} is out of bounds"),
+            Self::Attacked(index) => write!(f, "the cell {index} is attacked"),
+            Self::MissingQueen(index) => write!(f, "the cell {index} has no queen"),
+            Self::Duplicate(index) => write!(f, "the queen {index} is repeated"),
+            Self::Conflict {
+                index,
+                queen,
+                direction,
+            } => {
+                let line = match direction {
+                    Direction::Horizontal => "row",
+                    Direction::Vertical => "column",
+                    Direction::Principal => "principal diagonal",
+                    Direction::Antidiagonal => "antidiagonal",
+                };
+                write!(
+                    f,
+                    "queen {index} conflicts with queen {queen} on the {line}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PlacementError {}
+
+/// A board couldn't be resized; see [`crate::Board::resize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResizeError {
+    /// The board carries costs, which have no value for the cells of the new width.
+    Costs,
+}
+
+impl fmt::Display for ResizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Costs => write!(f, "a board with costs can't be resized"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ResizeError {}
+
+/// The compact text or the grid of a board couldn't be parsed; see [`crate::Compact`] and
+/// [`crate::Board::from_ascii`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParseBoardError {
+    /// The width isn't a number.
+    InvalidWidth,
+    /// The cells of the width overflow the address space.
+    WidthTooLarge(usize),
+    /// The board is both toroidal and cutting the rays, which aren't supported together.
+    UnsupportedTopology,
+    /// The queen of the position in the list isn't a number.
+    InvalidQueen(usize),
+    /// A queen couldn't be placed on the board.
+    Placement(PlacementError),
+    /// The row of the line, counted from 1, doesn't have the length of the first row.
+    RowLength {
+        line: usize,
+        length: usize,
+        width: usize,
+    },
+    /// The number of rows of the grid isn't the length of its rows.
+    RowCount { rows: usize, width: usize },
+    /// The glyph of the line, counted from 1, isn't a queen or an empty cell.
+    UnknownGlyph { line: usize, glyph: char },
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidWidth => write!(f, "the width isn't a number"),
+            Self::WidthTooLarge(width) => write!(f, "the width {width} is too large"),
+            Self::UnsupportedTopology => write!(f, "a toroidal board can't cut the rays"),
+            Self::InvalidQueen(position) => write!(f, "the queen {position} isn't a number"),
+            Self::Placement(e) => write!(f, "{e}"),
+            Self::RowLength {
+                line,
+                length,
+                width,
+            } => write!(
+                f,
+                "the row of line {line} has {length} cells instead of {width}"
+            ),
+            Self::RowCount { rows, width } => {
+                write!(f, "the grid has {rows} rows of {width} cells")
+            }
+            Self::UnknownGlyph { line, glyph } => {
+                write!(f, "unknown glyph {glyph:?} on line {line}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseBoardError {}
+
+/// The content of a cell has bits that don't map to the queen flag or an attack counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InvalidCell(pub u32);
+
+impl fmt::Display for InvalidCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid cell content {:#010x}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidCell {}
+
+/// The bookkeeping of a board is inconsistent with its queens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InvariantViolation {
+    pub index: usize,
+    pub expected: Cell,
+    pub found: Cell,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the cell {} is {:#010x}, expected {:#010x}",
+            self.index,
+            u32::from(self.found),
+            u32::from(self.expected)
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantViolation {}
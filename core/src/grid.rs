@@ -0,0 +1,117 @@
+use crate::{Board, ParseBoardError, Vec};
+
+impl Board {
+    /// Parses a grid of glyphs, a row per line: `Q` for the queens, `#` for the blocked cells,
+    /// and `.`, `x`, `*` or a space for the empty cells.
+    ///
+    /// The width is the length of the first row, and the grid must be square; the trailing empty
+    /// lines are ignored. The rows drawn by the [`core::fmt::Display`] of the board, with their
+    /// glyphs separated by single spaces, are read without the separators.
+    ///
+    /// The queens are placed as by [`Board::from_queens`], so the attacked queens are rejected.
+    pub fn from_ascii(s: &str) -> Result<Self, ParseBoardError> {
+        let mut rows = s.lines().collect::<Vec<_>>();
+        while rows.last().is_some_and(|r| r.is_empty()) {
+            rows.pop();
+        }
+
+        // the separated rows have a glyph on every other character
+        let count = rows.len();
+        let separated = count > 1
+            && rows.iter().all(|r| {
+                r.chars().count() == 2 * count - 1 && r.chars().skip(1).step_by(2).all(|c| c == ' ')
+            });
+        let step = if separated { 2 } else { 1 };
+
+        let width = rows.first().map_or(0, |r| r.chars().step_by(step).count());
+        let mut queens = Vec::new();
+        let mut blocked = Vec::new();
+        for (row, glyphs) in rows.iter().enumerate() {
+            let line = row + 1;
+            let length = glyphs.chars().step_by(step).count();
+            if length != width {
+                return Err(ParseBoardError::RowLength {
+                    line,
+                    length,
+                    width,
+                });
+            }
+            for (column, glyph) in glyphs.chars().step_by(step).enumerate() {
+                match glyph {
+                    'Q' => queens.push(row * width + column),
+                    '#' => blocked.push(row * width + column),
+                    '.' | 'x' | '*' | ' ' => (),
+                    glyph => return Err(ParseBoardError::UnknownGlyph { line, glyph }),
+                }
+            }
+        }
+
+        if count != width {
+            return Err(ParseBoardError::RowCount { rows: count, width });
+        }
+        let mut board = Board::from_queens(width, queens).map_err(ParseBoardError::Placement)?;
+        blocked.into_iter().for_each(|b| {
+            board.block(b);
+        });
+        Ok(board)
+    }
+}
+
+#[test]
+fn from_ascii_works() {
+    use crate::{PlacementError, ToString};
+
+    // the glyphs of the empty cells are interchangeable
+    let solution = Board::from_permutation(&[1, 3, 0, 2]).unwrap();
+    assert_eq!(
+        Board::from_ascii(".Q..\n***Q\nQ   \nxxQx\n"),
+        Ok(solution.clone())
+    );
+    assert_eq!(
+        Board::from_ascii(".Q..\r\n...Q\r\nQ...\r\n..Q.\r\n\n"),
+        Ok(solution.clone())
+    );
+
+    // the drawing of the board is read back
+    assert_eq!(Board::from_ascii(&solution.to_string()), Ok(solution));
+    let mut board = Board::new(5);
+    board.toggle(7);
+    assert_eq!(Board::from_ascii(&board.to_string()), Ok(board.clone()));
+    assert_eq!(Board::from_ascii(""), Ok(Board::new(0)));
+    assert_eq!(Board::from_ascii("."), Ok(Board::new(1)));
+    board.block(0);
+    assert_eq!(Board::from_ascii(&board.to_string()), Ok(board));
+
+    // a ragged grid
+    assert_eq!(
+        Board::from_ascii(".Q..\n...Q\nQ..\n..Q.\n"),
+        Err(ParseBoardError::RowLength {
+            line: 3,
+            length: 3,
+            width: 4
+        })
+    );
+    assert_eq!(
+        Board::from_ascii("....\n....\n"),
+        Err(ParseBoardError::RowCount { rows: 2, width: 4 })
+    );
+
+    // an unknown glyph
+    assert_eq!(
+        Board::from_ascii(".Q..\n...Q\nq...\n..Q.\n"),
+        Err(ParseBoardError::UnknownGlyph {
+            line: 3,
+            glyph: 'q'
+        })
+    );
+
+    // the queens attacking each other
+    assert_eq!(
+        Board::from_ascii("Q.\n.Q"),
+        Err(ParseBoardError::Placement(PlacementError::Conflict {
+            index: 3,
+            queen: 0,
+            direction: crate::Direction::Principal
+        }))
+    );
+}
@@ -0,0 +1,172 @@
+use crate::{Board, Cell, Direction, Lines, Piece, Position};
+
+/// The cells that a queen would attack if placed on a cell, that are free before it, counted per
+/// direction; see [`Board::attack_delta`].
+///
+/// The lines of a cell only cross on the cell itself, except for the diagonals of an even
+/// torus, crossing again opposite the cell, where the cell is counted by the principal one; so
+/// every newly attacked cell is counted once.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AttackDelta {
+    pub horizontal: usize,
+    pub vertical: usize,
+    pub principal: usize,
+    pub antidiagonal: usize,
+    /// The cells a knight move away, attacked by the amazons; see [`crate::Piece`].
+    pub knight: usize,
+}
+
+impl AttackDelta {
+    /// The newly attacked cells of all the directions.
+    pub const fn total(&self) -> usize {
+        self.horizontal + self.vertical + self.principal + self.antidiagonal + self.knight
+    }
+}
+
+/// A board with a queen hypothetically placed on a free cell, answering the queries of
+/// [`Position`] as the toggled board would, without mutating it; see
+/// [`Board::with_hypothetical`].
+///
+/// The attacks of the queen are an overlay over the cells of the board, computed per query from
+/// the lines of the cell, on the topology of the board and up to its blocks; see
+/// [`Board::attacks_along`].
+#[derive(Debug, Clone, Copy)]
+pub struct HypotheticalBoard<'a> {
+    board: &'a Board,
+    /// The cell of the queen, or `None` if the cell isn't free and the board is unchanged.
+    index: Option<usize>,
+}
+
+impl<'a> HypotheticalBoard<'a> {
+    /// Places the queen on the cell; a cell that isn't free is ignored, as by
+    /// [`Board::toggle_reporting`] for an attacked cell.
+    pub fn new(board: &'a Board, index: usize) -> Self {
+        let index = Some(index).filter(|i| board.is_free(*i));
+        Self { board, index }
+    }
+
+    /// The board without the hypothetical queen.
+    pub const fn board(&self) -> &'a Board {
+        self.board
+    }
+
+    /// The cell of the hypothetical queen, if it was free.
+    pub const fn index(&self) -> Option<usize> {
+        self.index
+    }
+}
+
+impl Position for HypotheticalBoard<'_> {
+    fn width(&self) -> usize {
+        self.board.width()
+    }
+
+    fn lines(&self) -> &Lines {
+        self.board.lines()
+    }
+
+    fn cell(&self, index: usize) -> Cell {
+        let mut cell = Position::cell(self.board, index);
+        let Some(queen) = self.index else {
+            return cell;
+        };
+
+        let attacks = |direction| self.board.attacks_along(queen, index, direction);
+        if attacks(Direction::Horizontal) {
+            cell.attack_horizontal();
+        }
+        if attacks(Direction::Vertical) {
+            cell.attack_vertical();
+        }
+        if attacks(Direction::Principal) {
+            cell.attack_principal();
+        }
+        if attacks(Direction::Antidiagonal) {
+            cell.attack_antidiagonal();
+        }
+        if self.board.knight_targets(queen).any(|t| t == index) {
+            cell.attack_knight();
+        }
+        if queen == index {
+            cell.put_queen();
+        }
+        cell
+    }
+
+    fn line_length(&self, index: usize, direction: Direction) -> usize {
+        self.board.line_length(index, direction)
+    }
+
+    fn knight_targets(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.board.knight_targets(index)
+    }
+
+    fn piece(&self) -> Piece {
+        self.board.piece()
+    }
+
+    // the lines of the board, rather than of the width, so they wrap as the board does
+    fn traverse_boundaries(&self, index: usize) -> impl Iterator<Item = (usize, Cell)> + '_ {
+        self.board
+            .traverse_boundaries(index)
+            .map(move |(i, _)| (i, self.cell(i)))
+    }
+}
+
+#[test]
+fn hypothetical_board_matches_the_toggle() {
+    for (width, kind) in (1..=7).flat_map(|w| (0..5).map(move |k| (w, k))) {
+        let mut board = match kind {
+            0 => Board::new(width),
+            1 => Board::new_toroidal(width),
+            2 => Board::with_piece(width, Piece::Amazon),
+            3 => Board::with_piece(width, Piece::Rook),
+            _ => Board::with_piece(width, Piece::Bishop),
+        };
+        if width > 3 {
+            board.toggle(1).toggle(width + 3);
+        }
+
+        for index in 0..width * width {
+            let view = HypotheticalBoard::new(&board, index);
+            let mut toggled = board.clone();
+            let free = toggled.cells().nth(index).is_some_and(|c| c.is_free());
+            if free {
+                toggled.toggle(index);
+            }
+            assert_eq!(view.index(), free.then_some(index));
+
+            // every cell matches, as do the traversals of the lines
+            for i in 0..width * width {
+                assert_eq!(view.cell(i), *toggled.cells().nth(i).unwrap());
+            }
+            assert!(view
+                .traverse_boundaries(index)
+                .eq(toggled.traverse_boundaries(index).map(|(i, c)| (i, *c))));
+
+            // the delta counts the free cells that became attacked
+            let attacked = (0..width * width)
+                .filter(|i| *i != index)
+                .filter(|i| board.is_free(*i) && !toggled.is_free(*i))
+                .count();
+            let delta = board.attack_delta(index);
+            assert_eq!(delta.total(), if free { attacked } else { 0 });
+        }
+    }
+
+    // the board is borrowed, not changed
+    let board = Board::new(4);
+    let free = board.with_hypothetical(5, |view| (0..16).filter(|i| view.is_free(*i)).count());
+    assert_eq!(free, 4);
+    assert!(board.is_empty());
+    assert_eq!(
+        board.attack_delta(5),
+        AttackDelta {
+            horizontal: 3,
+            vertical: 3,
+            principal: 3,
+            antidiagonal: 2,
+            knight: 0,
+        }
+    );
+}
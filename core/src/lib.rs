@@ -1,16 +1,62 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(not(feature = "std"))]
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::{collections::BTreeSet, vec, vec::Vec};
+use alloc::{
+    collections::BTreeSet,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
 
 #[cfg(feature = "std")]
-use std::{collections::BTreeSet, vec, vec::Vec};
+use std::{
+    collections::BTreeSet,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
 
 mod board;
-pub use board::Board;
+pub use board::{Board, BoardParts};
+
+mod canonical;
+pub use canonical::CanonicalBoard;
 
 mod cell;
 pub use cell::Cell;
+
+mod compact;
+pub use compact::Compact;
+
+mod direction;
+pub use direction::Direction;
+
+mod grid;
+
+mod hypothetical;
+pub use hypothetical::{AttackDelta, HypotheticalBoard};
+
+mod lines;
+pub use lines::{LineIds, Lines};
+
+mod piece;
+pub use piece::Piece;
+
+mod position;
+pub use position::Position;
+
+mod rng;
+pub use rng::XorShift;
+
+mod toggle;
+pub use toggle::ToggleResult;
+
+mod error;
+pub use error::{InvalidCell, InvariantViolation, ParseBoardError, PlacementError, ResizeError};
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
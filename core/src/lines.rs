@@ -0,0 +1,199 @@
+use crate::{Direction, Vec};
+use core::fmt;
+
+/// The attack lines of a cell, as the ids of [`Lines`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineIds {
+    pub row: usize,
+    pub column: usize,
+    /// The diagonal from the top-left to the bottom-right, as `column + width - 1 - row`.
+    pub principal: usize,
+    /// The diagonal from the top-right to the bottom-left, as `row + column`.
+    pub antidiagonal: usize,
+}
+
+impl LineIds {
+    /// The first direction, in the order of [`Direction::ALL`], of the lines shared with the
+    /// other cell.
+    pub fn shared(&self, other: &LineIds) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|direction| match direction {
+                Direction::Horizontal => self.row == other.row,
+                Direction::Vertical => self.column == other.column,
+                Direction::Principal => self.principal == other.principal,
+                Direction::Antidiagonal => self.antidiagonal == other.antidiagonal,
+            })
+    }
+}
+
+/// The attack lines of every cell of a width, computed once and shared between the boards of
+/// the width; see [`crate::Board::lines`].
+///
+/// The diagonals are numbered from `0` to `2 * width - 2`, so the lines of every direction can
+/// index a flat table of counters.
+///
+/// The lines also carry the random key of every cell for the hashes of the boards, seeded from
+/// the width; see [`crate::Board::hash64`].
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Lines {
+    width: usize,
+    ids: Vec<LineIds>,
+    keys: Vec<u64>,
+}
+
+impl Lines {
+    pub fn new(width: usize) -> Self {
+        let ids = (0..width * width)
+            .map(|index| {
+                let (row, column) = (index / width, index % width);
+                LineIds {
+                    row,
+                    column,
+                    principal: column + width - 1 - row,
+                    antidiagonal: row + column,
+                }
+            })
+            .collect();
+
+        // splitmix64, so the keys of a width are the same for every board
+        let mut state = (width as u64).wrapping_mul(0xd1b5_4a32_d192_ed03);
+        let keys = (0..width * width)
+            .map(|_| {
+                state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                z ^ (z >> 31)
+            })
+            .collect();
+        Self { width, ids, keys }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of diagonals of every direction.
+    pub const fn diagonals(&self) -> usize {
+        (2 * self.width).saturating_sub(1)
+    }
+
+    /// The lines of the cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of the bounds of the width.
+    pub fn line_ids(&self, index: usize) -> LineIds {
+        self.ids[index]
+    }
+
+    /// The random key of the cell, combined by exclusive or into the hash of the queens.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of the bounds of the width.
+    pub fn key(&self, index: usize) -> u64 {
+        self.keys[index]
+    }
+
+    /// The cells of the row, from left to right.
+    pub fn cells_of_row(&self, row: usize) -> impl Iterator<Item = usize> {
+        (row * self.width..).take(self.width * usize::from(row < self.width))
+    }
+
+    /// The cells of the column, from top to bottom.
+    pub fn cells_of_column(&self, column: usize) -> impl Iterator<Item = usize> {
+        (column..)
+            .step_by(self.width.max(1))
+            .take(self.width * usize::from(column < self.width))
+    }
+
+    /// The cells of the principal diagonal, from top to bottom.
+    pub fn cells_of_principal(&self, principal: usize) -> impl Iterator<Item = usize> {
+        let last = self.width.saturating_sub(1);
+        let (row, column) = match principal.checked_sub(last) {
+            Some(column) => (0, column),
+            None => (last - principal, 0),
+        };
+        (row * self.width + column..)
+            .step_by(self.width + 1)
+            .take(self.diagonal_length(principal))
+    }
+
+    /// The cells of the antidiagonal, from top to bottom.
+    pub fn cells_of_antidiagonal(&self, antidiagonal: usize) -> impl Iterator<Item = usize> {
+        let last = self.width.saturating_sub(1);
+        let (row, column) = match antidiagonal.checked_sub(last) {
+            Some(row) => (row, last),
+            None => (0, antidiagonal),
+        };
+        (row * self.width + column..)
+            .step_by(last.max(1))
+            .take(self.diagonal_length(antidiagonal))
+    }
+
+    /// The cells of the diagonal of either direction, or `0` if it doesn't exist.
+    fn diagonal_length(&self, diagonal: usize) -> usize {
+        let last = self.width.saturating_sub(1);
+        match diagonal {
+            d if d >= self.diagonals() => 0,
+            d if d <= last => d + 1,
+            d => self.diagonals() - d,
+        }
+    }
+}
+
+// the ids are derived from the width, so they aren't worth printing with every board
+impl fmt::Debug for Lines {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lines")
+            .field("width", &self.width)
+            .finish_non_exhaustive()
+    }
+}
+
+#[test]
+fn lines_work() {
+    let lines = Lines::new(4);
+    assert_eq!(
+        lines.line_ids(6),
+        LineIds {
+            row: 1,
+            column: 2,
+            principal: 4,
+            antidiagonal: 3,
+        }
+    );
+    assert_eq!(lines.diagonals(), 7);
+    assert!(lines.cells_of_row(1).eq([4, 5, 6, 7]));
+    assert!(lines.cells_of_column(2).eq([2, 6, 10, 14]));
+    assert!(lines.cells_of_principal(4).eq([1, 6, 11]));
+    assert!(lines.cells_of_antidiagonal(3).eq([3, 6, 9, 12]));
+    assert_eq!(
+        lines.line_ids(6).shared(&lines.line_ids(3)),
+        Some(Direction::Antidiagonal)
+    );
+    assert_eq!(
+        lines.line_ids(6).shared(&lines.line_ids(4)),
+        Some(Direction::Horizontal)
+    );
+    assert_eq!(lines.line_ids(6).shared(&lines.line_ids(0)), None);
+    assert!(lines.cells_of_principal(0).eq([12]));
+    assert!(lines.cells_of_antidiagonal(6).eq([15]));
+
+    // the lines out of the width have no cells
+    assert_eq!(lines.cells_of_row(4).count(), 0);
+    assert_eq!(lines.cells_of_column(4).count(), 0);
+    assert_eq!(lines.cells_of_principal(7).count(), 0);
+    assert_eq!(lines.cells_of_antidiagonal(7).count(), 0);
+
+    // the empty and single cell widths
+    assert_eq!(Lines::new(0).diagonals(), 0);
+    assert_eq!(Lines::new(0).cells_of_principal(0).count(), 0);
+    let lines = Lines::new(1);
+    assert!(lines.cells_of_row(0).eq([0]));
+    assert!(lines.cells_of_column(0).eq([0]));
+    assert!(lines.cells_of_principal(0).eq([0]));
+    assert!(lines.cells_of_antidiagonal(0).eq([0]));
+}
@@ -0,0 +1,43 @@
+use crate::Direction;
+
+/// The piece placed on the cells of a board, setting the cells it attacks; see
+/// [`crate::Board::with_piece`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Piece {
+    /// Attacks the cells of its row, column and diagonals.
+    #[default]
+    Queen,
+    /// Attacks the cells of its row and column; every width has a solution.
+    Rook,
+    /// Attacks the cells of its diagonals; a solution holds `2 * width - 2` bishops, as they
+    /// don't need a row of their own.
+    Bishop,
+    /// Attacks as a queen, and also the cells a knight move away; the smallest width with a
+    /// solution is 10.
+    Amazon,
+}
+
+impl Piece {
+    /// Whether the piece attacks the cells of the line of the direction.
+    pub const fn attacks(self, direction: Direction) -> bool {
+        match direction {
+            Direction::Horizontal | Direction::Vertical => !matches!(self, Self::Bishop),
+            Direction::Principal | Direction::Antidiagonal => !matches!(self, Self::Rook),
+        }
+    }
+}
+
+#[test]
+fn attacks_works() {
+    assert!(Direction::ALL.into_iter().all(|d| Piece::Queen.attacks(d)));
+    assert!(Direction::ALL.into_iter().all(|d| Piece::Amazon.attacks(d)));
+    assert!(Piece::Rook.attacks(Direction::Horizontal));
+    assert!(!Piece::Rook.attacks(Direction::Principal));
+    assert!(Piece::Bishop.attacks(Direction::Antidiagonal));
+    assert!(!Piece::Bishop.attacks(Direction::Vertical));
+}
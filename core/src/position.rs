@@ -0,0 +1,93 @@
+use crate::{Cell, Direction, Lines, Piece};
+
+/// The read-only queries of a board, answered by a [`crate::Board`] or by the overlay of a
+/// hypothetical queen of [`crate::HypotheticalBoard`].
+///
+/// An evaluator generic over the position scores a candidate without placing it on the board.
+pub trait Position {
+    fn width(&self) -> usize;
+
+    /// The attack lines of the cells of the width.
+    fn lines(&self) -> &Lines;
+
+    /// The content of the cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of the bounds of the board.
+    fn cell(&self, index: usize) -> Cell;
+
+    /// The `(column, row)` coordinates of the index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of the bounds of the board.
+    fn coords(&self, index: usize) -> (usize, usize) {
+        let ids = self.lines().line_ids(index);
+        (ids.column, ids.row)
+    }
+
+    /// The index of the `(column, row)` coordinates, or `None` if they are out of the board.
+    fn index_of(&self, column: usize, row: usize) -> Option<usize> {
+        let width = self.width();
+        (column < width && row < width).then(|| row * width + column)
+    }
+
+    fn is_queen(&self, index: usize) -> bool {
+        self.cell(index).is_queen()
+    }
+
+    fn is_attacked(&self, index: usize) -> bool {
+        self.cell(index).is_attacked()
+    }
+
+    fn is_free(&self, index: usize) -> bool {
+        self.cell(index).is_free()
+    }
+
+    /// Number of cells of the attack line with the given direction through the index; see
+    /// [`crate::Board::line_length`].
+    fn line_length(&self, index: usize, direction: Direction) -> usize {
+        let last = self.width() - 1;
+        let ids = self.lines().line_ids(index);
+        let (row, column) = (ids.row, ids.column);
+        match direction {
+            Direction::Horizontal | Direction::Vertical => self.width(),
+            Direction::Principal => row.min(column) + (last - row).min(last - column) + 1,
+            Direction::Antidiagonal => row.min(last - column) + column.min(last - row) + 1,
+        }
+    }
+
+    /// The cells a knight move away from the index, if the pieces of the position attack them;
+    /// see [`crate::Board::knight_targets`]. None by default.
+    fn knight_targets(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let _ = index;
+        core::iter::empty()
+    }
+
+    /// The piece of the queens, attacking the lines of [`Position::traverse_boundaries`]; see
+    /// [`crate::Board::piece`]. A queen by default.
+    fn piece(&self) -> Piece {
+        Piece::Queen
+    }
+
+    /// Traverses the cells of [`Position::traverse_boundaries`], followed by the cells of
+    /// [`Position::knight_targets`], for the evaluators aware of the amazons.
+    fn traverse_with_knights(&self, index: usize) -> impl Iterator<Item = (usize, Cell)> + '_ {
+        self.traverse_boundaries(index)
+            .chain(self.knight_targets(index).map(|i| (i, self.cell(i))))
+    }
+
+    /// Traverses the cells of the attack lines through the index, in the order of
+    /// [`crate::Board::traverse_boundaries`].
+    fn traverse_boundaries(&self, index: usize) -> impl Iterator<Item = (usize, Cell)> + '_ {
+        let lines = self.lines();
+        let ids = lines.line_ids(index);
+        lines
+            .cells_of_row(ids.row)
+            .chain(lines.cells_of_column(ids.column))
+            .chain(lines.cells_of_principal(ids.principal))
+            .chain(lines.cells_of_antidiagonal(ids.antidiagonal))
+            .map(|i| (i, self.cell(i)))
+    }
+}
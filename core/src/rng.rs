@@ -0,0 +1,45 @@
+/// A xorshift generator of 64 bits, so the searches and the tests drawing from it are
+/// reproducible from the seed; the sequence isn't suitable for anything but sampling.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    /// Creates a generator from the seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the seed is zero, as the sequence of a zero state is constant.
+    pub const fn new(seed: u64) -> Self {
+        assert!(seed != 0, "the seed of a xorshift can't be zero");
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A number below `max`, slightly biased towards the small numbers unless `max` is a power
+    /// of two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn below(&mut self, max: usize) -> usize {
+        (self.next_u64() % max as u64) as usize
+    }
+}
+
+#[test]
+fn xorshift_is_reproducible() {
+    let mut a = XorShift::new(0x9e37_79b9_7f4a_7c15);
+    let mut b = a.clone();
+    let drawn = (0..100).map(|_| a.below(10)).collect::<crate::Vec<_>>();
+    assert_eq!(drawn, (0..100).map(|_| b.below(10)).collect::<crate::Vec<_>>());
+    assert!((0..10).all(|n| drawn.contains(&n)));
+    assert_ne!(XorShift::new(1).next_u64(), XorShift::new(2).next_u64());
+}
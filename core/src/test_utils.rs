@@ -0,0 +1,30 @@
+//! Invariant checks and seeded sampling shared by the unit tests, the benchmarks and the fuzz
+//! targets.
+
+use crate::Board;
+
+/// The generator of the sampled boards, so every sample is reproducible from its seed.
+pub use crate::XorShift;
+
+/// Asserts the cells of the board are consistent with its queens, recomputing the queen and
+/// attack flags from scratch.
+///
+/// # Panics
+///
+/// Panics with the violation and the queens of the board if the invariants don't hold.
+pub fn assert_board_consistent(board: &Board) {
+    let width = board.width();
+    assert_eq!(board.cells().count(), width * width);
+    assert!(
+        board.sorted_queens().all(|q| q < width * width),
+        "queens out of bounds on {:?}",
+        board.sorted_queens().collect::<crate::Vec<_>>()
+    );
+
+    if let Err(violation) = board.validate_invariants() {
+        panic!(
+            "{violation} on {:?}",
+            board.sorted_queens().collect::<crate::Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,12 @@
+/// The action performed by [`crate::Board::toggle_reporting`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ToggleResult {
+    /// A queen was placed on the free cell.
+    Placed,
+    /// The queen of the cell was removed.
+    Removed,
+    /// The cell is attacked, so nothing changed.
+    Ignored,
+    /// The cell is blocked, so nothing changed; see [`crate::Board::block`].
+    Blocked,
+}
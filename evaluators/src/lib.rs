@@ -1,11 +1,88 @@
 #![no_std]
 
-use reginae_core::Board;
+use reginae_core::{Board, Direction, HypotheticalBoard, LineIds, Lines, Position};
+
+/// a built-in evaluator, with its name in the manifest of the crate.
+#[derive(Debug, Clone, Copy)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub evaluator: fn(&Board, usize) -> f64,
+    /// the same evaluator, for the hypothetical boards.
+    ///
+    /// the generic evaluators can't be taken as pointers for every lifetime of the boards, so
+    /// this is a closure instantiating them.
+    pub hypothetical: fn(&HypotheticalBoard, usize) -> f64,
+}
+
+/// declares the built-in evaluators once, exporting them in the manifest of the plugin and
+/// listing them in [`BUILTINS`].
+macro_rules! builtins {
+    ($($f:ident, description = $description:literal, weight = $weight:expr;)*) => {
+        reginae_plugin_abi::export_manifest!($(
+            reginae_plugin_abi::export_evaluator!(
+                Board,
+                $f,
+                name = stringify!($f),
+                description = $description,
+                weight = $weight,
+            ),
+        )*);
+
+        /// the built-in evaluators, in the order of the manifest of the crate.
+        pub const BUILTINS: &[Builtin] = &[$(
+            Builtin {
+                name: stringify!($f),
+                evaluator: $f,
+                hypothetical: |view, last_move| $f(view, last_move),
+            },
+        )*];
+    };
+}
+
+builtins! {
+    overlapping,
+    description = "prefers moves whose attacks overlap the attacks of other queens",
+    weight = 10.0;
+    ladder,
+    description = "prefers moves a knight move away from other queens",
+    weight = 5.0;
+    wrapping_ladder,
+    description = "prefers moves a knight move away from other queens on a toroidal board",
+    weight = -5.0;
+    symmetry_bias,
+    description = "prefers moves completing a rotational symmetry of the board",
+    weight = 1.0;
+    fewest_new_attacks,
+    description = "prefers moves attacking the fewest previously free cells",
+    weight = 10.0;
+    center_control,
+    description = "prefers moves close to the center of the board",
+    weight = 1.0;
+    edge_affinity,
+    description = "prefers moves close to the edges of the board",
+    weight = 1.0;
+    column_spread,
+    description = "prefers moves far from the columns of other queens",
+    weight = 1.0;
+    remaining_freedom,
+    description = "prefers moves leaving the most free cells on the board",
+    weight = 10.0;
+}
+
+/// the built-in evaluator with the given name, as named in the manifest of the crate.
+pub fn by_name(name: &str) -> Option<fn(&Board, usize) -> f64> {
+    BUILTINS
+        .iter()
+        .find(|builtin| builtin.name == name)
+        .map(|builtin| builtin.evaluator)
+}
 
 /// score hight as the attacked cells from the last move produces more overlapped attacks on
 /// the same cell (naturally, from other queens)
-#[no_mangle]
-pub fn overlapping(board: &Board, last_move: usize) -> f64 {
+pub fn overlapping<P>(board: &P, last_move: usize) -> f64
+where
+    P: Position,
+{
     let width = board.width();
     let mut count = 0_u64;
     let mut boundaries = board.traverse_boundaries(last_move);
@@ -62,70 +139,33 @@ pub fn overlapping(board: &Board, last_move: usize) -> f64 {
 /// score higher as more queens are ladder to last move (i.e. knight move).
 ///
 /// ladder seems to perform well for odd width, but will cause harm to even width search.
-#[no_mangle]
-pub fn ladder(board: &Board, last_move: usize) -> f64 {
-    let width = board.width();
-    let row = last_move / width;
-    let column = last_move - row * width;
-    let mut count = 0;
+pub fn ladder<P>(board: &P, last_move: usize) -> f64
+where
+    P: Position,
+{
+    // the knight moves around the last move
+    const JUMPS: [(isize, isize); 8] = [
+        (-2, -1),
+        (-1, -2),
+        (1, -2),
+        (2, -1),
+        (2, 1),
+        (1, 2),
+        (-1, 2),
+        (-2, 1),
+    ];
 
-    if let Some((column, row)) = column.checked_sub(2).zip(row.checked_sub(1)) {
-        let index = row * width + column;
-        count += board.is_queen(index) as u64;
-    }
-
-    if let Some((column, row)) = column.checked_sub(1).zip(row.checked_sub(2)) {
-        let index = row * width + column;
-        count += board.is_queen(index) as u64;
-    }
-
-    if let Some((column, row)) = Some(column + 1)
-        .filter(|c| c < &width)
-        .zip(row.checked_sub(2))
-    {
-        let index = row * width + column;
-        count += board.is_queen(index) as u64;
-    }
-
-    if let Some((column, row)) = Some(column + 2)
-        .filter(|c| c < &width)
-        .zip(row.checked_sub(1))
-    {
-        let index = row * width + column;
-        count += board.is_queen(index) as u64;
-    }
-
-    if let Some((column, row)) = Some(column + 2)
-        .filter(|c| c < &width)
-        .zip(Some(row + 1).filter(|c| c < &width))
-    {
-        let index = row * width + column;
-        count += board.is_queen(index) as u64;
-    }
-
-    if let Some((column, row)) = Some(column + 1)
-        .filter(|c| c < &width)
-        .zip(Some(row + 2).filter(|c| c < &width))
-    {
-        let index = row * width + column;
-        count += board.is_queen(index) as u64;
-    }
-
-    if let Some((column, row)) = column
-        .checked_sub(1)
-        .zip(Some(row + 2).filter(|c| c < &width))
-    {
-        let index = row * width + column;
-        count += board.is_queen(index) as u64;
-    }
-
-    if let Some((column, row)) = column
-        .checked_sub(2)
-        .zip(Some(row + 1).filter(|c| c < &width))
-    {
-        let index = row * width + column;
-        count += board.is_queen(index) as u64;
-    }
+    let (column, row) = board.coords(last_move);
+    let count = JUMPS
+        .iter()
+        .filter_map(|(c, r)| {
+            let (column, row) = column
+                .checked_add_signed(*c)
+                .zip(row.checked_add_signed(*r))?;
+            board.index_of(column, row)
+        })
+        .filter(|i| board.is_queen(*i))
+        .count();
 
     count as f64 / 8.0
 }
@@ -134,8 +174,10 @@ pub fn ladder(board: &Board, last_move: usize) -> f64 {
 /// board to produce a toroidal surface.
 ///
 /// can be used in combination with the regular ladder for even width with a negative weight.
-#[no_mangle]
-pub fn wrapping_ladder(board: &Board, last_move: usize) -> f64 {
+pub fn wrapping_ladder<P>(board: &P, last_move: usize) -> f64
+where
+    P: Position,
+{
     let width = board.width();
     let cells = board.width() * board.width();
     let mut count = 0;
@@ -174,3 +216,321 @@ pub fn wrapping_ladder(board: &Board, last_move: usize) -> f64 {
 
     count as f64 / 8.0
 }
+
+/// score higher as the last move completes a rotational symmetry of the board, i.e. its images
+/// under the quarter, half, and three-quarter turns are queens.
+///
+/// centrally symmetric partial boards tend to lead to solutions faster for certain widths.
+pub fn symmetry_bias<P>(board: &P, last_move: usize) -> f64
+where
+    P: Position,
+{
+    let mut image = last_move;
+    let mut count = 0;
+
+    for _ in 0..3 {
+        image = rotated(image, board.lines());
+        count += board.is_queen(image) as u32;
+    }
+
+    count as f64 / 3.0
+}
+
+/// score higher as the last move attacks fewer cells that were free before it, keeping the board
+/// open.
+///
+/// a cell of a line of the last move was free before it if the line is its only attack, as the
+/// queens of a board never share a line.
+pub fn fewest_new_attacks<P>(board: &P, last_move: usize) -> f64
+where
+    P: Position,
+{
+    let mut boundaries = board.traverse_boundaries(last_move);
+    let mut count = 0_u64;
+    let mut attacked = 0_u64;
+
+    // the traversal skips the lines the piece doesn't attack
+    let piece = board.piece();
+    for direction in Direction::ALL.into_iter().filter(|d| piece.attacks(*d)) {
+        let length = board.line_length(last_move, direction);
+        for (i, c) in boundaries.by_ref().take(length) {
+            if i == last_move {
+                continue;
+            }
+            let only = match direction {
+                Direction::Horizontal => {
+                    !c.is_attacked_vertical()
+                        && !c.is_attacked_principal()
+                        && !c.is_attacked_antidiagonal()
+                }
+                Direction::Vertical => {
+                    !c.is_attacked_horizontal()
+                        && !c.is_attacked_principal()
+                        && !c.is_attacked_antidiagonal()
+                }
+                Direction::Principal => {
+                    !c.is_attacked_horizontal()
+                        && !c.is_attacked_vertical()
+                        && !c.is_attacked_antidiagonal()
+                }
+                Direction::Antidiagonal => {
+                    !c.is_attacked_horizontal()
+                        && !c.is_attacked_vertical()
+                        && !c.is_attacked_principal()
+                }
+            };
+            count += 1;
+            attacked += (only && !c.is_queen()) as u64;
+        }
+    }
+
+    // a single cell board has no other cell to attack
+    if count == 0 {
+        return 1.0;
+    }
+
+    1.0 - attacked as f64 / count as f64
+}
+
+/// score higher as the last move is closer to the center of the board, from `1.0` at the center
+/// to `0.0` at the corners, by the chebyshev distance.
+///
+/// the distances are doubled so the center of an even width, between four cells, is exact.
+pub fn center_control<P>(board: &P, last_move: usize) -> f64
+where
+    P: Position,
+{
+    let last = board.width().saturating_sub(1);
+    // a single cell board is its own center
+    if last == 0 {
+        return 1.0;
+    }
+
+    let (column, row) = board.coords(last_move);
+    let distance = (2 * column).abs_diff(last).max((2 * row).abs_diff(last));
+
+    1.0 - distance as f64 / last as f64
+}
+
+/// score higher as the last move is closer to an edge of the board, from `1.0` on the edges to
+/// `0.0` on the innermost ring, by the distance to the nearest edge.
+///
+/// pushing the queens to the rim reduces the diagonal collisions for some widths.
+pub fn edge_affinity<P>(board: &P, last_move: usize) -> f64
+where
+    P: Position,
+{
+    let last = board.width().saturating_sub(1);
+    // every cell of the boards up to width 2 is on an edge
+    let innermost = last / 2;
+    if innermost == 0 {
+        return 1.0;
+    }
+
+    let (column, row) = board.coords(last_move);
+    let distance = column.min(row).min(last - column).min(last - row);
+
+    1.0 - distance as f64 / innermost as f64
+}
+
+/// score higher as the column of the last move is farther from the nearest column of another
+/// queen, relative to the spacing of the queens spread evenly over the columns, up to `1.0`.
+///
+/// the columns of the queens are read from the vertical attacks of the first row, as the queens
+/// of a board never share a column.
+pub fn column_spread<P>(board: &P, last_move: usize) -> f64
+where
+    P: Position,
+{
+    let width = board.width();
+    let (column, _) = board.coords(last_move);
+    let mut queens = 0_usize;
+    let mut nearest = None;
+
+    for c in (0..width).filter(|c| board.cell(*c).is_attacked_vertical()) {
+        queens += 1;
+        if c != column {
+            let distance = c.abs_diff(column);
+            nearest = Some(nearest.map_or(distance, |n: usize| n.min(distance)));
+        }
+    }
+
+    // a lone queen has no cluster to avoid
+    let Some(nearest) = nearest else {
+        return 1.0;
+    };
+    let spacing = width as f64 / queens as f64;
+
+    (nearest as f64 / spacing).min(1.0)
+}
+
+/// score higher as more cells of the board are left free after the last move, as the share of
+/// the free cells among all the cells.
+///
+/// unlike [`fewest_new_attacks`], the whole board is counted, so the score also falls as the
+/// board fills up.
+pub fn remaining_freedom<P>(board: &P, _last_move: usize) -> f64
+where
+    P: Position,
+{
+    let cells = board.width() * board.width();
+    let free = (0..cells).filter(|i| board.is_free(*i)).count();
+
+    free as f64 / cells as f64
+}
+
+/// the image of the index under a clockwise quarter turn of the board.
+fn rotated(index: usize, lines: &Lines) -> usize {
+    let width = lines.width();
+    let LineIds { row, column, .. } = lines.line_ids(index);
+    column * width + (width - 1 - row)
+}
+
+#[test]
+fn rotated_works() {
+    let lines = Lines::new(8);
+    assert_eq!(rotated(0, &lines), 7);
+    assert_eq!(rotated(7, &lines), 63);
+    assert_eq!(rotated(63, &lines), 56);
+    assert_eq!(rotated(56, &lines), 0);
+    assert_eq!(rotated(12, &Lines::new(5)), 12);
+}
+
+#[test]
+fn symmetry_bias_rewards_symmetric_moves() {
+    let mut board = Board::new(8);
+    board.toggle(1);
+
+    // the half turn of 1 is 62
+    let mut symmetric = board.clone();
+    symmetric.toggle(62);
+    let mut asymmetric = board.clone();
+    asymmetric.toggle(61);
+    assert!(symmetry_bias(&symmetric, 62) > symmetry_bias(&asymmetric, 61));
+    assert_eq!(symmetry_bias(&asymmetric, 61), 0.0);
+
+    // the center of an odd board is its own image
+    let mut board = Board::new(5);
+    board.toggle(12);
+    assert_eq!(symmetry_bias(&board, 12), 1.0);
+}
+
+#[test]
+fn fewest_new_attacks_rewards_overlapping_moves() {
+    let mut board = Board::new(8);
+    board.toggle(0);
+
+    // a move on the attacked lines of the corner queen attacks fewer free cells than a move far
+    // from them
+    let mut near = board.clone();
+    near.toggle(10);
+    let mut far = board.clone();
+    far.toggle(29);
+    assert!(fewest_new_attacks(&near, 10) > fewest_new_attacks(&far, 29));
+
+    // the first queen only attacks free cells
+    assert_eq!(fewest_new_attacks(&board, 0), 0.0);
+
+    // the cells 10 and 15 are the only free cells of the 9 cells of the lines of the second queen
+    let mut board = Board::new(4);
+    board.toggle(1).toggle(7);
+    assert_eq!(fewest_new_attacks(&board, 7), 1.0 - 2.0 / 9.0);
+}
+
+#[test]
+fn center_control_rewards_central_moves() {
+    let board = Board::new(9);
+    assert_eq!(center_control(&board, 40), 1.0);
+    assert_eq!(center_control(&board, 0), 0.0);
+    assert_eq!(center_control(&board, 80), 0.0);
+    assert_eq!(center_control(&board, 31), 0.75);
+    assert!(center_control(&board, 30) > center_control(&board, 1));
+
+    // the four central cells of an even width share the best score
+    let board = Board::new(8);
+    let central = [27, 28, 35, 36].map(|i| center_control(&board, i));
+    assert!(central.iter().all(|s| *s == 1.0 - 1.0 / 7.0));
+    assert_eq!(center_control(&board, 63), 0.0);
+    assert_eq!(center_control(&Board::new(1), 0), 1.0);
+}
+
+#[test]
+fn edge_affinity_rewards_moves_on_the_rim() {
+    // the odd width has a single innermost cell
+    let board = Board::new(7);
+    assert_eq!(edge_affinity(&board, 0), 1.0);
+    assert_eq!(edge_affinity(&board, 3), 1.0);
+    assert_eq!(edge_affinity(&board, 24), 0.0);
+    assert_eq!(edge_affinity(&board, 16), 1.0 - 2.0 / 3.0);
+
+    // the even width has four
+    let board = Board::new(8);
+    assert_eq!(edge_affinity(&board, 63), 1.0);
+    assert_eq!(edge_affinity(&board, 32), 1.0);
+    assert!([27, 28, 35, 36]
+        .iter()
+        .all(|i| edge_affinity(&board, *i) == 0.0));
+    assert_eq!(edge_affinity(&board, 18), 1.0 - 2.0 / 3.0);
+    assert_eq!(edge_affinity(&Board::new(2), 3), 1.0);
+}
+
+#[test]
+fn column_spread_rewards_distant_columns() {
+    // the queens of the columns 0 to 3, and of the even columns
+    let mut clustered = Board::new(8);
+    clustered.toggle(0).toggle(17).toggle(34).toggle(51);
+    let mut spread = Board::new(8);
+    spread.toggle(0).toggle(34).toggle(12).toggle(46);
+    assert_eq!(clustered.sorted_queens().count(), 4);
+    assert_eq!(spread.sorted_queens().count(), 4);
+
+    assert_eq!(column_spread(&clustered, 51), 0.5);
+    assert_eq!(column_spread(&clustered, 17), 0.5);
+    assert_eq!(column_spread(&spread, 46), 1.0);
+    assert_eq!(column_spread(&spread, 12), 1.0);
+
+    // the first queen scores the most
+    let mut board = Board::new(8);
+    board.toggle(27);
+    assert_eq!(column_spread(&board, 27), 1.0);
+}
+
+#[test]
+fn remaining_freedom_counts_the_free_cells() {
+    // the corner queen takes 22 cells of its row, column and diagonal
+    let mut board = Board::new(8);
+    board.toggle(0);
+    assert_eq!(remaining_freedom(&board, 0), 42.0 / 64.0);
+
+    // the central queen takes 28 cells
+    let mut board = Board::new(8);
+    board.toggle(27);
+    assert_eq!(remaining_freedom(&board, 27), 36.0 / 64.0);
+    assert!(remaining_freedom(&board, 27) == board.available().count() as f64 / 64.0);
+
+    board.toggle(8);
+    assert!(remaining_freedom(&board, 8) < 36.0 / 64.0);
+}
+
+#[test]
+fn by_name_resolves_the_builtins() {
+    let board = Board::new(8);
+    let names = [
+        ("overlapping", overlapping as fn(&Board, usize) -> f64),
+        ("ladder", ladder),
+        ("wrapping_ladder", wrapping_ladder),
+        ("symmetry_bias", symmetry_bias),
+        ("fewest_new_attacks", fewest_new_attacks),
+        ("center_control", center_control),
+        ("edge_affinity", edge_affinity),
+        ("column_spread", column_spread),
+        ("remaining_freedom", remaining_freedom),
+    ];
+    for (name, f) in names {
+        let resolved = by_name(name).expect("the built-ins resolve");
+        assert_eq!(resolved(&board, 27), f(&board, 27), "{name}");
+    }
+    assert!(by_name("unknown").is_none());
+    assert!(by_name("").is_none());
+    assert!(by_name("Overlapping").is_none());
+}
@@ -0,0 +1,31 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use reginae_core::test_utils::assert_board_consistent;
+use reginae_solver::{Board, NormalizedBoard};
+
+// the first byte picks the width, and every following byte an operation
+fuzz_target!(|data: &[u8]| {
+    let Some((width, operations)) = data.split_first() else {
+        return;
+    };
+
+    let width = 1 + *width as usize % 16;
+    let mut board = NormalizedBoard::from(Board::new(width));
+    let mut operations = operations.iter();
+    while let Some(operation) = operations.next() {
+        match operation % 8 {
+            0 => {
+                board.rotate_clockwise();
+            }
+            1 => {
+                board.clear();
+            }
+            _ => {
+                let index = operations.next().copied().unwrap_or(*operation) as usize;
+                board.toggle(index % (width * width));
+            }
+        }
+        assert_board_consistent(&board);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use reginae_core::test_utils::assert_board_consistent;
+
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+    if let Ok(board) = reginae_cli::parse_input(&input) {
+        assert_board_consistent(&board);
+    }
+});
@@ -0,0 +1,29 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use reginae_core::test_utils::assert_board_consistent;
+use reginae_solver::{Board, NormalizedBoard};
+
+// the first byte picks the width, and every following byte a queen to toggle
+fuzz_target!(|data: &[u8]| {
+    let Some((width, queens)) = data.split_first() else {
+        return;
+    };
+
+    let width = 1 + *width as usize % 16;
+    let mut board = Board::new(width);
+    queens.iter().for_each(|q| {
+        board.toggle(*q as usize % (width * width));
+    });
+
+    let normalized = NormalizedBoard::from(board.clone());
+    assert_board_consistent(&normalized);
+    assert_eq!(
+        normalized.sorted_queens().count(),
+        board.sorted_queens().count()
+    );
+
+    let restored = Board::from(normalized);
+    assert_board_consistent(&restored);
+    assert_eq!(restored, board);
+});
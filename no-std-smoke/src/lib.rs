@@ -0,0 +1,38 @@
+//! Smoke test of the solver without `std`; build it alone so the features of the other crates of
+//! the workspace aren't unified into it.
+//!
+//! ```shell
+//! cargo test -p reginae-no-std-smoke
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use reginae_solver::{Board, Solver};
+
+/// Solves the board with the given preset queens, returning its sorted queens.
+pub fn solve(width: usize, queens: &[usize]) -> Option<Vec<usize>> {
+    let mut board = Board::new(width);
+    queens.iter().for_each(|q| {
+        board.toggle(*q);
+    });
+
+    let solution = Solver::default().solve(board);
+    solution
+        .success
+        .then(|| solution.board.sorted_queens().collect())
+}
+
+#[test]
+fn solves_width_6() {
+    // the search of an empty board starts at the corner, which has no solution for width 6, so
+    // it falls back to the other cells of the root
+    let queens = solve(6, &[]).expect("width 6 is solvable");
+    assert_eq!(queens, [1, 9, 17, 18, 26, 34]);
+
+    let queens = solve(6, &[1]).expect("width 6 is solvable");
+    assert_eq!(queens, [1, 9, 17, 18, 26, 34]);
+    assert_eq!(Solver::default().solve_all(Board::new(6)).count(), 4);
+}
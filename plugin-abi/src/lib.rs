@@ -0,0 +1,358 @@
+//! The contract between the hosts and the dynamically loaded evaluator plugins.
+//!
+//! A plugin exports two `extern "C"` symbols, generated by [`export_manifest!`]:
+//! - [`VERSION_SYMBOL`], returning the [`ABI_VERSION`] the plugin was built against.
+//! - [`MANIFEST_SYMBOL`], returning a pointer to the static [`Manifest`] of its evaluators.
+//!
+//! The host must check the version before touching the manifest, as its layout is only
+//! guaranteed for matching versions.
+//!
+//! Every evaluator is declared with [`export_evaluator!`], which generates its `extern "C"` shim,
+//! with a panic guard when the `std` feature is enabled, and its entry of the manifest.
+//!
+//! Plugins compiled to WebAssembly follow the protocol of the [`wasm`] module instead.
+
+#![no_std]
+
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+use core::{ffi::CStr, mem, slice};
+
+pub mod wasm;
+
+/// Version of the plugin ABI; bumped on every change of the layouts or signatures of this crate.
+pub const ABI_VERSION: u32 = 2;
+
+/// Name of the exported symbol of type [`VersionFn`].
+pub const VERSION_SYMBOL: &[u8] = b"reginae_plugin_abi_version\0";
+
+/// Name of the exported symbol of type [`ManifestFn`].
+pub const MANIFEST_SYMBOL: &[u8] = b"reginae_plugin_manifest\0";
+
+/// Signature of the exported ABI version symbol.
+pub type VersionFn = unsafe extern "C" fn() -> u32;
+
+/// Signature of the exported manifest symbol.
+pub type ManifestFn = unsafe extern "C" fn() -> *const Manifest;
+
+/// Signature of an evaluator, taking the board and the last move, and returning a score between
+/// `0.0` and `1.0`.
+pub type EvaluatorFn = unsafe extern "C" fn(board: *const BoardView, last_move: usize) -> f64;
+
+/// Borrowed view of a board, valid only for the duration of an evaluator call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BoardView {
+    pub width: usize,
+    /// Sorted indexes of the queens.
+    pub queens: *const usize,
+    pub queens_len: usize,
+}
+
+impl BoardView {
+    /// Creates a view of the given width and sorted queens.
+    pub const fn new(width: usize, queens: &[usize]) -> Self {
+        Self {
+            width,
+            queens: queens.as_ptr(),
+            queens_len: queens.len(),
+        }
+    }
+
+    /// Sorted indexes of the queens.
+    ///
+    /// # Safety
+    ///
+    /// The view must have been created from a slice that is still alive.
+    pub unsafe fn queens(&self) -> &[usize] {
+        match self.queens_len {
+            0 => &[],
+            len => slice::from_raw_parts(self.queens, len),
+        }
+    }
+}
+
+/// A named evaluator of a plugin.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct EvaluatorEntry {
+    /// Nul-terminated name of the evaluator.
+    pub name: *const u8,
+    /// Nul-terminated description of the evaluator.
+    pub description: *const u8,
+    /// The weight used when the host isn't given one.
+    pub default_weight: f64,
+    pub evaluator: EvaluatorFn,
+}
+
+impl EvaluatorEntry {
+    /// Name of the evaluator.
+    ///
+    /// # Safety
+    ///
+    /// The entry must point to a valid nul-terminated name, as produced by [`export_evaluator!`].
+    pub unsafe fn name(&self) -> &CStr {
+        CStr::from_ptr(self.name.cast())
+    }
+
+    /// Description of the evaluator.
+    ///
+    /// # Safety
+    ///
+    /// The entry must point to a valid nul-terminated description, as produced by
+    /// [`export_evaluator!`].
+    pub unsafe fn description(&self) -> &CStr {
+        CStr::from_ptr(self.description.cast())
+    }
+}
+
+// safety: the name and description point to static, immutable data
+unsafe impl Sync for EvaluatorEntry {}
+
+/// The evaluators exported by a plugin.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Manifest {
+    pub abi_version: u32,
+    pub evaluators: *const EvaluatorEntry,
+    pub evaluators_len: usize,
+}
+
+// safety: the manifest points only to static, immutable data
+unsafe impl Sync for Manifest {}
+
+impl Manifest {
+    /// The evaluators of the plugin.
+    ///
+    /// # Safety
+    ///
+    /// The manifest must be valid for [`ABI_VERSION`], as produced by [`export_manifest!`].
+    pub unsafe fn evaluators(&self) -> &[EvaluatorEntry] {
+        match self.evaluators_len {
+            0 => &[],
+            len => slice::from_raw_parts(self.evaluators, len),
+        }
+    }
+
+    /// Finds an evaluator by its name.
+    ///
+    /// # Safety
+    ///
+    /// The manifest must be valid for [`ABI_VERSION`], as produced by [`export_manifest!`].
+    pub unsafe fn find(&self, name: &str) -> Option<EvaluatorFn> {
+        self.entry(name).map(|e| e.evaluator)
+    }
+
+    /// Finds the entry of an evaluator by its name.
+    ///
+    /// # Safety
+    ///
+    /// The manifest must be valid for [`ABI_VERSION`], as produced by [`export_manifest!`].
+    pub unsafe fn entry(&self, name: &str) -> Option<&EvaluatorEntry> {
+        self.evaluators()
+            .iter()
+            .find(|e| e.name().to_bytes() == name.as_bytes())
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    /// Runs the evaluator, scoring `0.0` if it panics.
+    #[cfg(any(test, feature = "std"))]
+    pub fn guard<F: FnOnce() -> f64>(f: F) -> f64 {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_or(0.0)
+    }
+
+    /// Runs the evaluator; without `std`, a panic aborts at the `extern "C"` boundary.
+    #[cfg(not(any(test, feature = "std")))]
+    pub fn guard<F: FnOnce() -> f64>(f: F) -> f64 {
+        f()
+    }
+}
+
+/// Declares an evaluator of a plugin, adapting a safe `fn(&Board, usize) -> f64` into the
+/// [`EvaluatorEntry`] of its manifest.
+///
+/// The board is rebuilt from the view via `Board::new` and `Board::toggle`, so the plugin and the
+/// host don't need to agree on the layout of the board. With the `std` feature, a panic of the
+/// evaluator is caught and scores `0.0`.
+///
+/// ```ignore
+/// reginae_plugin_abi::export_evaluator!(
+///     Board,
+///     overlapping,
+///     name = "overlapping",
+///     description = "prefers moves that overlap the attacks of other queens",
+///     weight = 10.0,
+/// )
+/// ```
+#[macro_export]
+macro_rules! export_evaluator {
+    (
+        $board:ty,
+        $f:path,
+        name = $name:expr,
+        description = $description:expr,
+        weight = $weight:expr $(,)?
+    ) => {{
+        unsafe extern "C" fn evaluator(view: *const $crate::BoardView, last_move: usize) -> f64 {
+            let view = &*view;
+            $crate::__private::guard(|| {
+                let mut board = <$board>::new(view.width);
+                view.queens().iter().for_each(|q| {
+                    board.toggle(*q);
+                });
+                $f(&board, last_move)
+            })
+        }
+
+        $crate::EvaluatorEntry {
+            name: concat!($name, "\0").as_ptr(),
+            description: concat!($description, "\0").as_ptr(),
+            default_weight: $weight,
+            evaluator,
+        }
+    }};
+}
+
+/// Exports the version and manifest symbols of a plugin with the given evaluators.
+///
+/// The evaluators are either declared with [`export_evaluator!`], or listed after the board
+/// type, named after their functions, without a description, and with a default weight of
+/// `1.0`.
+///
+/// ```ignore
+/// reginae_plugin_abi::export_manifest!(
+///     reginae_plugin_abi::export_evaluator!(Board, overlapping, name = "overlapping", ...),
+/// );
+/// reginae_plugin_abi::export_manifest!(Board; overlapping, ladder);
+/// ```
+#[macro_export]
+macro_rules! export_manifest {
+    ($board:ty; $($f:ident),* $(,)?) => {
+        $crate::export_manifest!($(
+            $crate::export_evaluator!(
+                $board,
+                $f,
+                name = stringify!($f),
+                description = "",
+                weight = 1.0,
+            ),
+        )*);
+    };
+    ($($entry:expr),* $(,)?) => {
+        const _: () = {
+            const EVALUATORS: &[$crate::EvaluatorEntry] = &[$($entry),*];
+
+            static MANIFEST: $crate::Manifest = $crate::Manifest {
+                abi_version: $crate::ABI_VERSION,
+                evaluators: EVALUATORS.as_ptr(),
+                evaluators_len: EVALUATORS.len(),
+            };
+
+            #[export_name = "reginae_plugin_abi_version"]
+            extern "C" fn version() -> u32 {
+                $crate::ABI_VERSION
+            }
+
+            #[export_name = "reginae_plugin_manifest"]
+            extern "C" fn manifest() -> *const $crate::Manifest {
+                &MANIFEST
+            }
+        };
+    };
+}
+
+// the layouts are part of the ABI; changing any of them requires bumping `ABI_VERSION`
+const _: () = {
+    const WORD: usize = mem::size_of::<usize>();
+
+    assert!(mem::size_of::<BoardView>() == 3 * WORD);
+    assert!(mem::align_of::<BoardView>() == WORD);
+    assert!(WORD != 8 || mem::size_of::<EvaluatorEntry>() == 4 * WORD);
+    assert!(mem::align_of::<EvaluatorEntry>() >= WORD);
+    assert!(mem::size_of::<Manifest>() == 3 * WORD);
+    assert!(mem::align_of::<Manifest>() == WORD);
+    assert!(mem::size_of::<Option<EvaluatorFn>>() == WORD);
+};
+
+#[cfg(test)]
+mod tests {
+    pub struct Board {
+        width: usize,
+        queens: usize,
+    }
+
+    impl Board {
+        pub fn new(width: usize) -> Self {
+            Self { width, queens: 0 }
+        }
+
+        pub fn toggle(&mut self, _index: usize) -> &mut Self {
+            self.queens += 1;
+            self
+        }
+    }
+
+    pub fn density(board: &Board, _last_move: usize) -> f64 {
+        board.queens as f64 / board.width as f64
+    }
+
+    pub fn panicking(_board: &Board, _last_move: usize) -> f64 {
+        panic!("the evaluator panicked")
+    }
+
+    crate::export_manifest!(
+        crate::export_evaluator!(
+            Board,
+            density,
+            name = "density",
+            description = "the ratio of queens to the width",
+            weight = 2.5,
+        ),
+        crate::export_evaluator!(
+            Board,
+            panicking,
+            name = "panicking",
+            description = "",
+            weight = 1.0,
+        ),
+    );
+}
+
+#[test]
+fn exported_manifest_works() {
+    extern "C" {
+        fn reginae_plugin_abi_version() -> u32;
+        fn reginae_plugin_manifest() -> *const Manifest;
+    }
+
+    unsafe {
+        assert_eq!(reginae_plugin_abi_version(), ABI_VERSION);
+
+        let manifest = &*reginae_plugin_manifest();
+        assert_eq!(manifest.abi_version, ABI_VERSION);
+        assert_eq!(manifest.evaluators().len(), 2);
+        assert!(manifest.find("unknown").is_none());
+
+        let entry = manifest
+            .entry("density")
+            .expect("the evaluator is exported");
+        assert_eq!(
+            entry.description().to_bytes(),
+            b"the ratio of queens to the width"
+        );
+        assert_eq!(entry.default_weight, 2.5);
+
+        let density = manifest.find("density").expect("the evaluator is exported");
+        let view = BoardView::new(4, &[1, 7]);
+        assert_eq!(density(&view, 7), 0.5);
+
+        // the panic is caught by the guard
+        let panicking = manifest
+            .find("panicking")
+            .expect("the evaluator is exported");
+        assert_eq!(panicking(&view, 7), 0.0);
+    }
+}
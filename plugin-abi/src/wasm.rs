@@ -0,0 +1,58 @@
+//! The protocol of the evaluator plugins compiled to WebAssembly.
+//!
+//! The guest exports its memory and two functions:
+//! - [`BUFFER_EXPORT`], `fn(len: u32) -> u32`, returning the offset of a guest buffer of `len`
+//!   bytes the host writes the encoded board to.
+//! - [`EVALUATE_EXPORT`], `fn(last_move: u32) -> f64`, evaluating the board of the buffer.
+//!
+//! The board is encoded as little-endian `u32` words: the width, followed by the sorted queens.
+
+/// Name of the exported buffer function.
+pub const BUFFER_EXPORT: &str = "reginae_board_buffer";
+
+/// Name of the exported evaluation function.
+pub const EVALUATE_EXPORT: &str = "evaluate";
+
+const WORD: usize = 4;
+
+/// Number of bytes of an encoded board with the given number of queens.
+pub const fn encoded_len(queens: usize) -> usize {
+    (queens + 1) * WORD
+}
+
+/// Encodes the board into the buffer, returning the number of written bytes.
+///
+/// # Panics
+///
+/// Panics if the buffer is shorter than [`encoded_len`].
+pub fn encode<Q>(width: usize, queens: Q, buffer: &mut [u8]) -> usize
+where
+    Q: IntoIterator<Item = usize>,
+{
+    let mut len = 0;
+    for word in Some(width).into_iter().chain(queens) {
+        buffer[len..len + WORD].copy_from_slice(&(word as u32).to_le_bytes());
+        len += WORD;
+    }
+    len
+}
+
+/// Decodes the width and the queens of an encoded board, if the buffer isn't empty.
+pub fn decode(buffer: &[u8]) -> Option<(usize, impl Iterator<Item = usize> + '_)> {
+    let mut words = buffer
+        .chunks_exact(WORD)
+        .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]) as usize);
+    let width = words.next()?;
+    Some((width, words))
+}
+
+#[test]
+fn encoding_round_trip() {
+    let mut buffer = [0; encoded_len(3)];
+    assert_eq!(encode(8, [0, 12, 23], &mut buffer), buffer.len());
+
+    let (width, queens) = decode(&buffer).expect("the buffer isn't empty");
+    assert_eq!(width, 8);
+    assert!(queens.eq([0, 12, 23]));
+    assert!(decode(&[]).is_none());
+}
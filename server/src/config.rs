@@ -0,0 +1,77 @@
+use serde::Deserialize;
+use std::{fs, io, path::Path};
+
+/// Configuration of the server, usually read from a JSON file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Config {
+    /// Address to listen on; defaults to `127.0.0.1:8080`.
+    #[serde(default = "Config::default_address")]
+    pub address: String,
+    /// Key required in the `x-api-key` header of every request but `/health`; no authentication
+    /// if unset.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Upper bound of the jumps of a single solve, regardless of the budget of the request.
+    #[serde(default = "Config::default_max_jumps")]
+    pub max_jumps: usize,
+    /// Maximum width of the boards of the requests.
+    #[serde(default = "Config::default_max_width")]
+    pub max_width: usize,
+    /// Maximum number of idle solvers kept per evaluator configuration.
+    #[serde(default = "Config::default_pool_size")]
+    pub pool_size: usize,
+}
+
+impl Config {
+    fn default_address() -> String {
+        "127.0.0.1:8080".to_string()
+    }
+
+    const fn default_max_jumps() -> usize {
+        100_000
+    }
+
+    const fn default_max_width() -> usize {
+        64
+    }
+
+    const fn default_pool_size() -> usize {
+        8
+    }
+
+    /// Reads the configuration from a JSON file.
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let config = fs::read_to_string(path)?;
+        serde_json::from_str(&config).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid configuration `{}`: {e}", path.display()),
+            )
+        })
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            address: Self::default_address(),
+            api_key: None,
+            max_jumps: Self::default_max_jumps(),
+            max_width: Self::default_max_width(),
+            pool_size: Self::default_pool_size(),
+        }
+    }
+}
+
+#[test]
+fn config_defaults_work() {
+    let config: Config = serde_json::from_str(r#"{"api_key": "secret"}"#).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            api_key: Some("secret".to_string()),
+            ..Config::default()
+        }
+    );
+}
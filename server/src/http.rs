@@ -0,0 +1,125 @@
+use std::io::{self, BufRead, Write};
+
+/// Maximum size of the body of a request.
+const MAX_BODY: usize = 1 << 20;
+
+/// A minimal HTTP/1.1 request; the connection is closed after every response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn read<R: BufRead>(reader: &mut R) -> Result<Self, Response> {
+        let invalid = |e: io::Error| Response::error(400, &format!("invalid request: {e}"));
+
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(invalid)?;
+        let mut parts = line.split_whitespace();
+        let (method, path) = match (parts.next(), parts.next()) {
+            (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+            _ => return Err(Response::error(400, "invalid request line")),
+        };
+
+        let mut headers = Vec::new();
+        loop {
+            line.clear();
+            reader.read_line(&mut line).map_err(invalid)?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            let (name, value) = header
+                .split_once(':')
+                .ok_or_else(|| Response::error(400, "invalid header"))?;
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+
+        let mut request = Self {
+            method,
+            path,
+            headers,
+            body: Vec::new(),
+        };
+
+        let length = request
+            .header("content-length")
+            .map(str::parse::<usize>)
+            .transpose()
+            .map_err(|_| Response::error(400, "invalid content length"))?
+            .unwrap_or(0);
+        if length > MAX_BODY {
+            return Err(Response::error(413, "the body is too large"));
+        }
+        request.body.resize(length, 0);
+        reader.read_exact(&mut request.body).map_err(invalid)?;
+
+        Ok(request)
+    }
+
+    /// The value of the header, by its case-insensitive name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A JSON response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    pub fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        let message = serde_json::to_string(message).unwrap_or_default();
+        Self {
+            status,
+            body: format!("{{\"error\":{message}}}"),
+        }
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            _ => "Internal Server Error",
+        };
+        write!(
+            writer,
+            "HTTP/1.1 {} {reason}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            self.status,
+            self.body.len(),
+            self.body
+        )?;
+        writer.flush()
+    }
+}
+
+#[test]
+fn request_is_parsed() {
+    let raw =
+        "POST /solve HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\nX-Api-Key: k\r\n\r\n{}";
+    let request = Request::read(&mut raw.as_bytes()).unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/solve");
+    assert_eq!(request.header("x-api-key"), Some("k"));
+    assert_eq!(request.body, b"{}");
+
+    let raw = "GET\r\n\r\n";
+    assert_eq!(Request::read(&mut raw.as_bytes()).unwrap_err().status, 400);
+}
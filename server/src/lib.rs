@@ -0,0 +1,298 @@
+//! HTTP service exposing the solver.
+//!
+//! - `GET /health` replies `{"status":"ok"}`, without authentication.
+//! - `POST /solve` takes `{"board": {"width", "queens"}, "evaluators": [{"name", "weight"}],
+//!   "max_jumps"}` and replies the [`Solution`] of the board.
+//! - `POST /evaluate` takes `{"board", "evaluators", "candidates", "last_move"}` and replies the
+//!   `scores` of the candidates, from the highest to the lowest.
+
+use reginae_solver::{Board, Evaluator, PlacementError, Solution, Solver};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, BufReader},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+mod config;
+pub use config::Config;
+
+mod http;
+use http::{Request, Response};
+
+mod pool;
+use pool::Pools;
+
+/// Timeout of the reads and writes of a connection.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A running server; dropping it without [`Server::shutdown`] leaves it running in background.
+pub struct Server {
+    address: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    acceptor: JoinHandle<()>,
+}
+
+impl Server {
+    /// Binds the address of the configuration and starts serving in background.
+    pub fn bind(config: Config) -> io::Result<Self> {
+        let listener = TcpListener::bind(&config.address)?;
+        let address = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let state = Arc::new(State {
+            pools: Pools::new(config.pool_size),
+            config,
+        });
+
+        let flag = Arc::clone(&shutdown);
+        let acceptor = thread::spawn(move || accept(listener, state, flag));
+
+        Ok(Self {
+            address,
+            shutdown,
+            acceptor,
+        })
+    }
+
+    /// The bound address, with the actual port if the configuration asked for an ephemeral one.
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Blocks until the server is shut down.
+    pub fn join(self) -> thread::Result<()> {
+        self.acceptor.join()
+    }
+
+    /// Stops accepting connections and waits for the in-flight requests to be answered.
+    pub fn shutdown(self) -> thread::Result<()> {
+        self.shutdown.store(true, Ordering::SeqCst);
+
+        // wake the blocked accept up; it checks the flag before serving the connection
+        let _ = TcpStream::connect(self.address);
+        self.acceptor.join()
+    }
+}
+
+struct State {
+    config: Config,
+    pools: Pools,
+}
+
+fn accept(listener: TcpListener, state: Arc<State>, shutdown: Arc<AtomicBool>) {
+    let mut workers: Vec<JoinHandle<()>> = Vec::new();
+    for stream in listener.incoming() {
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else {
+            continue;
+        };
+
+        workers.retain(|w| !w.is_finished());
+        let state = Arc::clone(&state);
+        workers.push(thread::spawn(move || {
+            let _ = serve(stream, &state);
+        }));
+    }
+
+    workers.into_iter().for_each(|w| {
+        let _ = w.join();
+    });
+}
+
+fn serve(mut stream: TcpStream, state: &State) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let response = match Request::read(&mut BufReader::new(&stream)) {
+        Ok(request) => route(&request, state),
+        Err(response) => response,
+    };
+    response.write_to(&mut stream)
+}
+
+fn route(request: &Request, state: &State) -> Response {
+    let authorized = match &state.config.api_key {
+        Some(key) => request.header("x-api-key") == Some(key.as_str()),
+        None => true,
+    };
+
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/health") => return Response::ok(r#"{"status":"ok"}"#.to_string()),
+        _ if !authorized => return Response::error(401, "missing or invalid api key"),
+        ("POST", "/solve") => parse(request).and_then(|r| solve(r, state)),
+        ("POST", "/evaluate") => parse(request).and_then(|r| evaluate(r, state)),
+        (_, "/health" | "/solve" | "/evaluate") => Err(Response::error(405, "method not allowed")),
+        _ => Err(Response::error(404, "not found")),
+    };
+
+    result.unwrap_or_else(|e| e)
+}
+
+fn parse<T>(request: &Request) -> Result<T, Response>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    serde_json::from_slice(&request.body)
+        .map_err(|e| Response::error(400, &format!("invalid body: {e}")))
+}
+
+fn json<T: Serialize>(body: &T) -> Result<Response, Response> {
+    serde_json::to_string(body)
+        .map(Response::ok)
+        .map_err(|e| Response::error(500, &e.to_string()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct BoardSpec {
+    width: usize,
+    #[serde(default)]
+    queens: Vec<usize>,
+}
+
+impl BoardSpec {
+    /// Builds the board, refusing widths past the limit of the server and attacked queens.
+    fn build(&self, max_width: usize) -> Result<Board, Response> {
+        if self.width == 0 || self.width > max_width {
+            return Err(Response::error(
+                400,
+                &format!("the width must be between 1 and {max_width}"),
+            ));
+        }
+
+        Board::from_queens(self.width, self.queens.iter().copied()).map_err(|e| match e {
+            PlacementError::OutOfBounds(q) => {
+                Response::error(400, &format!("the queen {q} is out of bounds"))
+            }
+            PlacementError::Duplicate(q) => {
+                Response::error(400, &format!("the queen {q} is repeated"))
+            }
+            e @ PlacementError::Conflict { .. } => Response::error(400, &e.to_string()),
+            PlacementError::Attacked(q) | PlacementError::MissingQueen(q) => {
+                Response::error(400, &format!("the queen {q} is attacked"))
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct EvaluatorSpec {
+    name: String,
+    #[serde(default = "EvaluatorSpec::default_weight")]
+    weight: f64,
+}
+
+impl EvaluatorSpec {
+    const fn default_weight() -> f64 {
+        1.0
+    }
+
+    /// The key of the pool of the solvers with the given evaluators.
+    fn key(specs: &[EvaluatorSpec]) -> String {
+        specs
+            .iter()
+            .map(|s| format!("{}:{}", s.name, s.weight))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn builtin(&self) -> Result<fn(&Board, usize) -> f64, Response> {
+        reginae_evaluators::by_name(&self.name)
+            .ok_or_else(|| Response::error(400, &format!("unknown evaluator `{}`", self.name)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SolveRequest {
+    board: BoardSpec,
+    #[serde(default)]
+    evaluators: Vec<EvaluatorSpec>,
+    #[serde(default)]
+    max_jumps: Option<usize>,
+}
+
+fn solve(request: SolveRequest, state: &State) -> Result<Response, Response> {
+    let board = request.board.build(state.config.max_width)?;
+    let key = EvaluatorSpec::key(&request.evaluators);
+
+    let mut solver = match state.pools.checkout(&key) {
+        Some(solver) => solver,
+        None => {
+            let mut solver = Solver::default();
+            for spec in &request.evaluators {
+                solver.with_evaluator(spec.builtin()?, spec.weight);
+            }
+            solver
+        }
+    };
+
+    // the budget of the server always applies
+    let max_jumps = request
+        .max_jumps
+        .map_or(state.config.max_jumps, |m| m.min(state.config.max_jumps));
+    let before = solver.jumps();
+    let mut solution: Solution = solver.with_max_jumps(max_jumps).solve(board);
+    solution.jumps = solver.jumps() - before;
+
+    state.pools.checkin(key, solver);
+    json(&solution)
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct EvaluateRequest {
+    board: BoardSpec,
+    #[serde(default)]
+    evaluators: Vec<EvaluatorSpec>,
+    candidates: Vec<usize>,
+    #[serde(default)]
+    last_move: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct Score {
+    index: usize,
+    score: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Scores {
+    scores: Vec<Score>,
+}
+
+fn evaluate(request: EvaluateRequest, state: &State) -> Result<Response, Response> {
+    let mut board = request.board.build(state.config.max_width)?;
+    let mut evaluator = Evaluator::default();
+    for spec in &request.evaluators {
+        evaluator.inject_evaluator(spec.builtin()?, spec.weight);
+    }
+
+    let mut scores = request
+        .candidates
+        .iter()
+        .map(|index| {
+            if board.available().all(|i| i != *index) {
+                return Err(Response::error(
+                    400,
+                    &format!("the candidate {index} isn't available"),
+                ));
+            }
+            board.toggle(*index);
+            let score = evaluator.score(&board, request.last_move);
+            board.toggle(*index);
+            Ok(Score {
+                index: *index,
+                score,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // highest first, as the solver pops them
+    scores.sort_by(|a, b| b.score.cmp(&a.score).then(a.index.cmp(&b.index)));
+    json(&Scores { scores })
+}
@@ -0,0 +1,17 @@
+use reginae_server::{Config, Server};
+use std::{env, io};
+
+fn main() -> io::Result<()> {
+    // the configuration file is optional
+    let config = match env::args().nth(1) {
+        Some(path) => Config::read(path)?,
+        None => Config::default(),
+    };
+
+    let server = Server::bind(config)?;
+    eprintln!("listening on {}", server.local_addr());
+
+    server
+        .join()
+        .map_err(|_| io::Error::other("the server panicked"))
+}
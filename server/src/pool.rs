@@ -0,0 +1,40 @@
+use reginae_solver::Solver;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+/// Idle solvers, pooled by their evaluator configuration so the depleted boards of a
+/// configuration are reused between requests.
+#[derive(Default)]
+pub(crate) struct Pools {
+    pools: Mutex<HashMap<String, Vec<Solver>>>,
+    size: usize,
+}
+
+impl Pools {
+    pub fn new(size: usize) -> Self {
+        Self {
+            pools: Mutex::new(HashMap::new()),
+            size,
+        }
+    }
+
+    /// Takes an idle solver of the configuration, if any.
+    pub fn checkout(&self, key: &str) -> Option<Solver> {
+        self.pools
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_mut(key)
+            .and_then(Vec::pop)
+    }
+
+    /// Returns a solver to the pool of its configuration, dropping it if the pool is full.
+    pub fn checkin(&self, key: String, solver: Solver) {
+        let mut pools = self.pools.lock().unwrap_or_else(PoisonError::into_inner);
+        let pool = pools.entry(key).or_default();
+        if pool.len() < self.size {
+            pool.push(solver);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use reginae_server::{Config, Server};
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream},
+};
+
+fn start(config: Config) -> Server {
+    Server::bind(Config {
+        address: "127.0.0.1:0".to_string(),
+        ..config
+    })
+    .expect("failed to bind the server")
+}
+
+fn request(
+    address: SocketAddr,
+    method: &str,
+    path: &str,
+    key: Option<&str>,
+    body: &str,
+) -> (u16, String) {
+    let mut stream = TcpStream::connect(address).expect("failed to connect");
+    let key = key
+        .map(|k| format!("x-api-key: {k}\r\n"))
+        .unwrap_or_default();
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nhost: localhost\r\n{key}content-length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .expect("failed to write the request");
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .expect("failed to read the response");
+    let status = response[9..12].parse().expect("invalid status");
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, b)| b.to_string())
+        .unwrap_or_default();
+    (status, body)
+}
+
+#[test]
+fn health_works() {
+    let server = start(Config::default());
+    let (status, body) = request(server.local_addr(), "GET", "/health", None, "");
+    assert_eq!(status, 200);
+    assert_eq!(body, r#"{"status":"ok"}"#);
+
+    assert_eq!(
+        request(server.local_addr(), "GET", "/unknown", None, "").0,
+        404
+    );
+    assert_eq!(
+        request(server.local_addr(), "GET", "/solve", None, "").0,
+        405
+    );
+    server.shutdown().unwrap();
+}
+
+#[test]
+fn solve_works() {
+    let server = start(Config::default());
+    let body = r#"{"board": {"width": 8, "queens": [1]}, "evaluators": [{"name": "overlapping", "weight": 1.0}]}"#;
+
+    let (status, solution) = request(server.local_addr(), "POST", "/solve", None, body);
+    assert_eq!(status, 200);
+    let solution: serde_json::Value = serde_json::from_str(&solution).unwrap();
+    assert_eq!(
+        solution.get("outcome").and_then(|o| o.as_str()),
+        Some("solved")
+    );
+    let queens = solution
+        .get("board")
+        .and_then(|b| b.get("queens"))
+        .and_then(|q| q.as_array())
+        .unwrap();
+    assert_eq!(queens.len(), 8);
+
+    // the pooled solver reports the jumps of the request only
+    let (_, again) = request(server.local_addr(), "POST", "/solve", None, body);
+    let again: serde_json::Value = serde_json::from_str(&again).unwrap();
+    assert!(
+        again.get("jumps").and_then(|j| j.as_u64())
+            <= solution.get("jumps").and_then(|j| j.as_u64())
+    );
+
+    let (status, _) = request(
+        server.local_addr(),
+        "POST",
+        "/solve",
+        None,
+        r#"{"board": {"width": 8, "queens": [0, 1]}}"#,
+    );
+    assert_eq!(status, 400);
+    let (status, _) = request(
+        server.local_addr(),
+        "POST",
+        "/solve",
+        None,
+        r#"{"board": {"width": 1000}}"#,
+    );
+    assert_eq!(status, 400);
+    let (status, _) = request(server.local_addr(), "POST", "/solve", None, "{");
+    assert_eq!(status, 400);
+    server.shutdown().unwrap();
+}
+
+#[test]
+fn solve_budget_is_capped() {
+    let server = start(Config {
+        max_jumps: 3,
+        ..Config::default()
+    });
+    let body = r#"{"board": {"width": 12}, "max_jumps": 1000000}"#;
+
+    let (status, solution) = request(server.local_addr(), "POST", "/solve", None, body);
+    assert_eq!(status, 200);
+    let solution: serde_json::Value = serde_json::from_str(&solution).unwrap();
+    assert_eq!(
+        solution.get("outcome").and_then(|o| o.as_str()),
+        Some("interrupted")
+    );
+    assert_eq!(solution.get("jumps").and_then(|j| j.as_u64()), Some(3));
+    server.shutdown().unwrap();
+}
+
+#[test]
+fn evaluate_works() {
+    let server = start(Config::default());
+    let body = r#"{"board": {"width": 8, "queens": [0]}, "evaluators": [{"name": "overlapping"}], "candidates": [10, 12, 20], "last_move": 0}"#;
+
+    let (status, scores) = request(server.local_addr(), "POST", "/evaluate", None, body);
+    assert_eq!(status, 200);
+    let scores: serde_json::Value = serde_json::from_str(&scores).unwrap();
+    let scores = scores.get("scores").and_then(|s| s.as_array()).unwrap();
+    assert_eq!(scores.len(), 3);
+    let values: Vec<_> = scores
+        .iter()
+        .map(|s| s.get("score").and_then(|s| s.as_u64()).unwrap())
+        .collect();
+    assert!(values.windows(2).all(|w| w[0] >= w[1]));
+
+    // the index 9 is attacked by the queen 0
+    let body = r#"{"board": {"width": 8, "queens": [0]}, "candidates": [9]}"#;
+    assert_eq!(
+        request(server.local_addr(), "POST", "/evaluate", None, body).0,
+        400
+    );
+    let body = r#"{"board": {"width": 8}, "evaluators": [{"name": "unknown"}], "candidates": [9]}"#;
+    assert_eq!(
+        request(server.local_addr(), "POST", "/evaluate", None, body).0,
+        400
+    );
+    server.shutdown().unwrap();
+}
+
+#[test]
+fn api_key_is_required() {
+    let server = start(Config {
+        api_key: Some("secret".to_string()),
+        ..Config::default()
+    });
+    let body = r#"{"board": {"width": 5}}"#;
+
+    assert_eq!(
+        request(server.local_addr(), "GET", "/health", None, "").0,
+        200
+    );
+    assert_eq!(
+        request(server.local_addr(), "POST", "/solve", None, body).0,
+        401
+    );
+    assert_eq!(
+        request(server.local_addr(), "POST", "/solve", Some("wrong"), body).0,
+        401
+    );
+    assert_eq!(
+        request(server.local_addr(), "POST", "/solve", Some("secret"), body).0,
+        200
+    );
+    server.shutdown().unwrap();
+}
+
+#[test]
+fn shutdown_stops_accepting() {
+    let server = start(Config::default());
+    let address = server.local_addr();
+    assert_eq!(request(address, "GET", "/health", None, "").0, 200);
+
+    server.shutdown().unwrap();
+    assert!(TcpStream::connect(address).is_err());
+}
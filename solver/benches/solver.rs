@@ -0,0 +1,194 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use reginae_core::test_utils::XorShift;
+use reginae_solver::{Board, Evaluator, HypotheticalBoard, NormalizedBoard, Solver};
+
+/// Partial positions, as width and preset queens, completed by the solver.
+const PARTIAL: [(usize, &[usize]); 3] = [(8, &[1, 11]), (10, &[2, 15, 27]), (12, &[3, 17, 40])];
+
+/// Non-attacking queens of a width 64 board, far from any normalized position.
+const WIDE: [usize; 16] = [
+    183, 365, 999, 1244, 1318, 1657, 1753, 1934, 2241, 2508, 2751, 2831, 3040, 3126, 3326, 3792,
+];
+
+/// Non-attacking queens of the first half of the rows of a width 32 board, two columns apart,
+/// as deep in a search.
+const KNIGHTS: [usize; 16] = [
+    1, 35, 69, 103, 137, 171, 205, 239, 273, 307, 341, 375, 409, 443, 477, 511,
+];
+
+/// Number of boards of the depleted set benchmarks.
+const DEPLETED: usize = 10_000;
+
+fn board(width: usize, queens: &[usize]) -> Board {
+    let mut board = Board::new(width);
+    queens.iter().for_each(|q| {
+        board.toggle(*q);
+    });
+    board
+}
+
+/// Deterministic boards of width 12 with up to 6 queens, drawn from a fixed seed.
+fn depleted_boards() -> Vec<Board> {
+    let mut rng = XorShift::new(0x2545_f491_4f6c_dd1d);
+    (0..DEPLETED)
+        .map(|_| {
+            let mut board = Board::new(12);
+            for _ in 0..6 {
+                board.toggle(rng.below(144));
+            }
+            board
+        })
+        .collect()
+}
+
+fn solve_empty(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve_empty");
+    for width in [8, 10, 12] {
+        group.bench_with_input(BenchmarkId::new("plain", width), &width, |b, w| {
+            b.iter(|| Solver::default().solve(Board::new(*w)))
+        });
+        group.bench_with_input(BenchmarkId::new("builtin", width), &width, |b, w| {
+            b.iter(|| {
+                Solver::default()
+                    .with_evaluator(reginae_evaluators::overlapping, 1.0)
+                    .with_evaluator(reginae_evaluators::ladder, 0.5)
+                    .solve(Board::new(*w))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn solve_partial(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve_partial");
+    for (width, queens) in PARTIAL {
+        group.bench_function(BenchmarkId::from_parameter(width), |b| {
+            b.iter_batched(
+                || board(width, queens),
+                |board| Solver::default().solve(board),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Scores every candidate of a width 16 board with the built-in evaluators, toggling them on the
+/// board or on the hypothetical view.
+fn score_candidates(c: &mut Criterion) {
+    type Forms = (
+        fn(&Board, usize) -> f64,
+        fn(&HypotheticalBoard, usize) -> f64,
+    );
+    let forms: [Forms; 2] = [
+        (reginae_evaluators::overlapping, |b, m| {
+            reginae_evaluators::overlapping(b, m)
+        }),
+        (reginae_evaluators::ladder, |b, m| {
+            reginae_evaluators::ladder(b, m)
+        }),
+    ];
+
+    let mut group = c.benchmark_group("score_candidates");
+    for hypothetical in [false, true] {
+        let mut evaluator = Evaluator::default();
+        evaluator.set_hypothetical(hypothetical);
+        for (f, h) in forms {
+            evaluator.inject_hypothetical_evaluator(f, h, 1.0);
+        }
+
+        let mut board = board(16, &[1, 19, 36, 55]);
+        let candidates = board.available().collect::<Vec<_>>();
+        let name = if hypothetical {
+            "hypothetical"
+        } else {
+            "toggle"
+        };
+        group.bench_function(BenchmarkId::new(name, 16), |b| {
+            b.iter(|| {
+                candidates
+                    .iter()
+                    .map(|c| evaluator.score_candidate(&mut board, *c, 1))
+                    .max()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn normalization(c: &mut Criterion) {
+    let wide = board(64, &WIDE);
+    c.bench_function("normalize/64", |b| {
+        b.iter_batched(
+            || wide.clone(),
+            NormalizedBoard::from,
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("rotate_clockwise/64", |b| {
+        b.iter_batched(
+            || wide.clone(),
+            |mut board| {
+                board.rotate_clockwise();
+                board
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+/// The candidate scan and the attack updates, to compare the cells with the `bitboard` feature.
+fn candidates(c: &mut Criterion) {
+    let mut partial = board(32, &KNIGHTS);
+    c.bench_function("available/32", |b| {
+        b.iter(|| black_box(&partial).available().sum::<usize>())
+    });
+    let safe = partial
+        .available()
+        .last()
+        .expect("the board has safe cells");
+    c.bench_function("toggle/32", |b| {
+        b.iter(|| {
+            partial.toggle(black_box(safe)).toggle(black_box(safe));
+        })
+    });
+}
+
+fn depleted(c: &mut Criterion) {
+    let boards = depleted_boards();
+    let marked = |solver: &mut Solver| {
+        boards.iter().for_each(|board| {
+            solver.mark_depleted(board.width(), board.sorted_queens());
+        });
+    };
+
+    c.bench_function("depleted/insert", |b| {
+        b.iter(|| {
+            let mut solver = Solver::default();
+            marked(&mut solver);
+            solver
+        })
+    });
+
+    let mut solver = Solver::default();
+    marked(&mut solver);
+    c.bench_function("depleted/lookup", |b| {
+        b.iter(|| {
+            boards
+                .iter()
+                .filter(|board| solver.is_depleted(black_box(board)))
+                .count()
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    solve_empty,
+    solve_partial,
+    score_candidates,
+    normalization,
+    candidates,
+    depleted
+);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+//! Solves a batch of width 8 boards with a single solver, reporting the wall time, the heap
+//! allocations, and the total jumps.
+//!
+//! ```shell
+//! cargo run --release -p reginae-solver --example batch
+//! ```
+
+use reginae_solver::{Board, Solver};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+const BOARDS: usize = 10_000;
+const WIDTH: usize = 8;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn main() {
+    let mut solver = Solver::default();
+    let mut solved = 0;
+    let mut jumps = Vec::with_capacity(BOARDS);
+    let mut board = Board::new(WIDTH);
+
+    let start = Instant::now();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    for i in 0..BOARDS {
+        // the board of the previous solution is reused
+        board.reset(WIDTH);
+        board.toggle((i * 7) % (WIDTH * WIDTH));
+        board.toggle((i * 13 + 5) % (WIDTH * WIDTH));
+
+        let before = solver.jumps();
+        let solution = solver.solve(board);
+        solved += solution.success as usize;
+        board = solution.board;
+        jumps.push(solver.jumps() - before);
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    let elapsed = start.elapsed();
+
+    // a digest of the jumps of every board, to compare the behavior between versions
+    let digest = jumps.iter().fold(0xcbf2_9ce4_8422_2325_u64, |h, j| {
+        (h ^ *j as u64).wrapping_mul(0x100_0000_01b3)
+    });
+
+    println!("solved {solved} of {BOARDS} boards in {elapsed:?}");
+    println!("allocations: {allocations}");
+    println!("jumps: {} (digest {digest:016x})", solver.jumps());
+}
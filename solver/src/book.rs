@@ -0,0 +1,45 @@
+//! Closed-form solutions of the empty boards, for the widths where any solution is acceptable.
+
+use crate::{Board, Vec};
+
+/// Constructs a solution of the empty board of the width, without searching; `None` for the
+/// widths 2 and 3, which have no solution.
+///
+/// The queens of the rows are placed on the even columns, then on the odd columns, in ascending
+/// order (counting from one), with the explicit corrections of the widths with a remainder of 2
+/// or 3 modulo 6.
+pub fn construct(width: usize) -> Option<Board> {
+    let evens = (2..=width).step_by(2);
+    let odds = (1..=width).step_by(2);
+    let columns = match width % 6 {
+        _ if width == 2 || width == 3 => return None,
+        // swap 1 and 3, and move 5 to the end of the odds
+        2 => evens
+            .chain([3, 1])
+            .chain(odds.skip(3))
+            .chain([5])
+            .collect::<Vec<_>>(),
+        // move 2 to the end of the evens, and 1 and 3 to the end of the odds
+        3 => evens
+            .skip(1)
+            .chain([2])
+            .chain(odds.skip(2))
+            .chain([1, 3])
+            .collect(),
+        _ => evens.chain(odds).collect(),
+    };
+
+    let permutation = columns.into_iter().map(|c| c - 1).collect::<Vec<_>>();
+    Board::from_permutation(&permutation).ok()
+}
+
+#[test]
+fn construct_works() {
+    for width in (0..=64).filter(|w| *w != 2 && *w != 3) {
+        let board = construct(width).expect("the width is solvable");
+        assert_eq!(board.width(), width);
+        assert!(board.is_solved(), "width {width}");
+    }
+    assert_eq!(construct(2), None);
+    assert_eq!(construct(3), None);
+}
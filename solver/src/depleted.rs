@@ -0,0 +1,59 @@
+use crate::{Board, Vec};
+
+/// The set of depleted boards.
+///
+/// With `std`, the keys are stored in a radix trie to mitigate the memory cost; otherwise, in a
+/// b-tree set.
+#[derive(Default, Clone)]
+pub(crate) struct Depleted {
+    #[cfg(feature = "std")]
+    keys: radix_trie::Trie<Vec<usize>, ()>,
+    #[cfg(not(feature = "std"))]
+    keys: crate::BTreeSet<Vec<usize>>,
+    /// Reused by the lookups, so they don't allocate.
+    key: Vec<usize>,
+}
+
+impl Depleted {
+    pub fn insert(&mut self, board: &Board) {
+        #[cfg(feature = "std")]
+        self.keys.insert(Self::key(board), ());
+
+        #[cfg(not(feature = "std"))]
+        self.keys.insert(Self::key(board));
+    }
+
+    pub fn contains(&mut self, board: &Board) -> bool {
+        self.key.clear();
+        self.key.push(board.width());
+        self.key.push(Self::topology(board));
+        self.key.extend(board.blocked_cells());
+        self.key.push(usize::MAX);
+        self.key.extend(board.sorted_queens());
+
+        #[cfg(feature = "std")]
+        return self.keys.get(&self.key).is_some();
+
+        #[cfg(not(feature = "std"))]
+        return self.keys.contains(&self.key);
+    }
+
+    /// The keys are prefixed with the width, the topology and the blocked cells, closed by
+    /// `usize::MAX`, so a solver can be reused between widths, topologies and blocks.
+    fn key(board: &Board) -> Vec<usize> {
+        [board.width(), Self::topology(board)]
+            .into_iter()
+            .chain(board.blocked_cells())
+            .chain(Some(usize::MAX))
+            .chain(board.sorted_queens())
+            .collect()
+    }
+
+    /// The flags of the lines and the piece of the board, as they change the attacks of the same
+    /// queens.
+    fn topology(board: &Board) -> usize {
+        usize::from(board.is_toroidal())
+            | usize::from(board.cuts_rays()) << 1
+            | (board.piece() as usize) << 2
+    }
+}
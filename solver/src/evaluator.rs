@@ -1,35 +1,283 @@
-use crate::Board;
+use crate::{Arc, Board, Vec};
+use reginae_core::HypotheticalBoard;
+use reginae_plugin_abi::{BoardView, EvaluatorFn};
 
 #[derive(Default, Clone)]
 pub struct Evaluator {
     evaluators: Vec<WeightedEvaluator>,
+    quantization: Quantization,
+    reducer: Reducer,
+    hypothetical: bool,
+}
+
+/// How the outputs of the evaluators are combined into the score of a candidate.
+///
+/// Unlike the weighted sum, the other reducers use the weights only for their sign: an
+/// evaluator of positive weight contributes its output, one of negative weight the complement
+/// of its output, so it still prefers the lower outputs, and one of zero weight is ignored.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Reducer {
+    /// The weighted average of the outputs, over the total of the absolute weights.
+    #[default]
+    WeightedSum,
+    /// The greatest contribution, so a single confident evaluator ranks the candidate.
+    Max,
+    /// The least contribution, so every evaluator must agree on the candidate.
+    Min,
+    /// The product of the contributions, so a single evaluator rejecting the candidate sinks it.
+    Product,
+}
+
+/// How the weighted score, between `0.0` and `1.0`, is mapped to the integer score that orders the
+/// candidates.
+///
+/// The default cast keeps the full precision of the score, but the low bits of the weighted sum
+/// may differ between platforms (e.g. the extended precision of x87, or evaluators built with a
+/// different libm), changing the order of close candidates and thus the solution found first.
+/// The fixed point quantization rounds the score to [`Quantization::FIXED_POINT_BITS`] bits with
+/// explicit ties to even, so the jumps are reproducible across platforms, at the cost of
+/// treating scores closer than `2^-32` as ties, which are broken by the order of the candidates.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Quantization {
+    /// Scales the score to the range of `u64` with a saturating cast.
+    #[default]
+    Cast,
+    /// Rounds the score to a fixed point of [`Quantization::FIXED_POINT_BITS`] fractional bits.
+    FixedPoint,
+}
+
+impl Quantization {
+    /// The fractional bits of [`Quantization::FixedPoint`].
+    pub const FIXED_POINT_BITS: u32 = 32;
+
+    /// Maps a score between `0.0` and `1.0` to the ordered integer score.
+    pub fn quantize(self, score: f64) -> u64 {
+        match self {
+            Self::Cast => (score * u64::MAX as f64) as u64,
+            Self::FixedPoint => {
+                // scaling by a power of two is exact, and so is the fraction below 2^53
+                let scaled = score * (1_u64 << Self::FIXED_POINT_BITS) as f64;
+                let mut fixed = scaled as u64;
+                let fraction = scaled - fixed as f64;
+                if fraction > 0.5 || (fraction == 0.5 && fixed & 1 == 1) {
+                    fixed += 1;
+                }
+
+                // spread over the range of `u64`, so both quantizations order alike
+                fixed
+                    .checked_shl(64 - Self::FIXED_POINT_BITS)
+                    .filter(|_| fixed < 1 << Self::FIXED_POINT_BITS)
+                    .unwrap_or(u64::MAX)
+            }
+        }
+    }
 }
 
 impl Evaluator {
-    pub fn score(&self, board: &Board, last_move: usize) -> u64 {
-        let total_weight = self
-            .evaluators
-            .iter()
-            .map(|w| w.weight.abs())
-            .sum::<f64>()
-            .max(f64::MIN_POSITIVE);
+    /// The warnings logged for the misbehaving outputs of every evaluator, before going silent.
+    pub const MISBEHAVIOR_WARNINGS: usize = 3;
+
+    /// The score of the evaluators for the last move of the board, combined by the reducer; see
+    /// [`Evaluator::with_reducer`].
+    ///
+    /// The outputs of the evaluators are sanitized, so a misbehaving evaluator can't poison the
+    /// score: a NaN output is taken as `0.0`, and any other output out of `0.0..=1.0`, infinite
+    /// or not, is clamped, keeping the weight of the evaluator. Both are counted as misbehaviors
+    /// of the evaluator; see [`Evaluator::misbehaviors`].
+    pub fn score(&mut self, board: &Board, last_move: usize) -> u64 {
+        let score = self.score_f64(board, last_move);
+        self.quantization.quantize(score)
+    }
+
+    /// The score of [`Evaluator::score`] before its quantization, between `0.0` and `1.0`.
+    ///
+    /// The default [`Quantization::Cast`] keeps the order of the scores, so the integer scores
+    /// sort the candidates as these do.
+    pub fn score_f64(&mut self, board: &Board, last_move: usize) -> f64 {
+        self.reduce(|f| f.call(board, last_move))
+    }
+
+    /// The score of the evaluators for a queen hypothetically placed on the candidate,
+    /// without mutating the board; see [`Board::with_hypothetical`].
+    ///
+    /// Returns `None` unless every evaluator has a hypothetical form; see
+    /// [`Evaluator::is_hypothetical`].
+    pub fn score_hypothetical(
+        &mut self,
+        board: &Board,
+        candidate: usize,
+        last_move: usize,
+    ) -> Option<u64> {
+        if !self.is_hypothetical() {
+            return None;
+        }
+        let score = board.with_hypothetical(candidate, |view| {
+            self.reduce(|f| match f {
+                Function::Hypothetical(_, f) => f(view, last_move),
+                _ => unreachable!("the evaluators are hypothetical"),
+            })
+        });
+        Some(self.quantization.quantize(score))
+    }
+
+    /// The score of the candidate placed on the board, which is restored.
+    ///
+    /// With [`Evaluator::set_hypothetical`], the candidate is scored via
+    /// [`Evaluator::score_hypothetical`] if every evaluator supports it, saving the attack
+    /// updates of the two toggles.
+    pub fn score_candidate(
+        &mut self,
+        board: &mut Board,
+        candidate: usize,
+        last_move: usize,
+    ) -> u64 {
+        if self.hypothetical {
+            if let Some(score) = self.score_hypothetical(board, candidate, last_move) {
+                return score;
+            }
+        }
 
-        let score: f64 = self
-            .evaluators
+        board.toggle(candidate);
+        let score = self.score(board, last_move);
+        board.toggle(candidate);
+        score
+    }
+
+    /// Whether every evaluator has a hypothetical form, injected via
+    /// [`Evaluator::inject_hypothetical_evaluator`].
+    pub fn is_hypothetical(&self) -> bool {
+        self.evaluators
             .iter()
-            .map(|w| (w.f)(board, last_move) * w.weight / total_weight)
-            .sum();
+            .all(|w| matches!(w.f, Function::Hypothetical(..)))
+    }
+
+    /// Sanitizes the outputs of the evaluators and combines them by the reducer, as documented by
+    /// [`Evaluator::score`].
+    fn reduce<F>(&mut self, mut output: F) -> f64
+    where
+        F: FnMut(&Function) -> f64,
+    {
+        let mut total_weight = 0.0;
+        let mut score = 0.0;
+        let (mut max, mut min, mut product) = (0.0_f64, 1.0_f64, 1.0);
+        let mut contributed = false;
+        for (i, w) in self.evaluators.iter_mut().enumerate() {
+            let output = output(&w.f);
+            if !(0.0..=1.0).contains(&output) {
+                w.misbehave(i, output);
+            }
+            // the clamp would keep a NaN, so it's scored as the least output
+            let output = match output.is_nan() {
+                true => 0.0,
+                false => output.clamp(0.0, 1.0),
+            };
+            total_weight += w.weight.abs();
+            if self.reducer == Reducer::WeightedSum {
+                score += output * w.weight;
+                continue;
+            }
+
+            let contribution = match w.weight {
+                weight if weight > 0.0 => output,
+                weight if weight < 0.0 => 1.0 - output,
+                _ => continue,
+            };
+            product *= contribution;
+            max = max.max(contribution);
+            min = min.min(contribution);
+            contributed = true;
+        }
 
-        let score = score.max(f64::MIN_POSITIVE).min(1.0);
+        let score = match self.reducer {
+            Reducer::WeightedSum => score / total_weight.max(f64::MIN_POSITIVE),
+            _ if !contributed => 0.0,
+            Reducer::Max => max,
+            Reducer::Min => min,
+            Reducer::Product => product,
+        };
+        let score = score.clamp(f64::MIN_POSITIVE, 1.0);
 
         #[cfg(feature = "tracing")]
-        tracing::debug!("computed weighted score {score}");
+        tracing::debug!("computed score {score} by {:?}", self.reducer);
 
-        (score * u64::MAX as f64) as u64
+        score
+    }
+
+    /// The misbehaving outputs of every evaluator, in the order of injection; see
+    /// [`Evaluator::score`].
+    pub fn misbehaviors(&self) -> Vec<usize> {
+        self.evaluators.iter().map(|w| w.misbehaviors).collect()
+    }
+
+    pub fn set_quantization(&mut self, quantization: Quantization) -> &mut Self {
+        self.quantization = quantization;
+        self
+    }
+
+    /// Sets how the outputs of the evaluators are combined; the weighted sum by default.
+    pub fn with_reducer(&mut self, reducer: Reducer) -> &mut Self {
+        self.reducer = reducer;
+        self
+    }
+
+    /// Scores the candidates on a [`HypotheticalBoard`] in [`Evaluator::score_candidate`],
+    /// instead of toggling them, if every evaluator has a hypothetical form; disabled by
+    /// default, so the evaluators are audited for the equivalence before opting in.
+    pub fn set_hypothetical(&mut self, hypothetical: bool) -> &mut Self {
+        self.hypothetical = hypothetical;
+        self
     }
 
     pub fn inject_evaluator(&mut self, f: fn(&Board, usize) -> f64, weight: f64) -> &mut Self {
-        self.evaluators.push(WeightedEvaluator { f, weight });
+        self.evaluators.push(WeightedEvaluator {
+            f: Function::Native(f),
+            weight,
+            misbehaviors: 0,
+        });
+        self
+    }
+
+    /// Injects an evaluator with a form for the boards and a form for the hypothetical boards,
+    /// which must return the same output for the same queens, as the instances of an evaluator
+    /// generic over [`reginae_core::Position`].
+    pub fn inject_hypothetical_evaluator(
+        &mut self,
+        f: fn(&Board, usize) -> f64,
+        hypothetical: HypotheticalFn,
+        weight: f64,
+    ) -> &mut Self {
+        self.evaluators.push(WeightedEvaluator {
+            f: Function::Hypothetical(f, hypothetical),
+            weight,
+            misbehaviors: 0,
+        });
+        self
+    }
+
+    /// Injects a stateful evaluator, such as a sandboxed plugin instance.
+    pub fn inject_evaluator_fn<F>(&mut self, f: F, weight: f64) -> &mut Self
+    where
+        F: Fn(&Board, usize) -> f64 + Send + Sync + 'static,
+    {
+        self.evaluators.push(WeightedEvaluator {
+            f: Function::Dynamic(Arc::new(f)),
+            weight,
+            misbehaviors: 0,
+        });
+        self
+    }
+
+    /// Injects an evaluator loaded from a plugin.
+    ///
+    /// # Safety
+    ///
+    /// The function must remain loaded for as long as this evaluator is used.
+    pub unsafe fn inject_plugin_evaluator(&mut self, f: EvaluatorFn, weight: f64) -> &mut Self {
+        self.evaluators.push(WeightedEvaluator {
+            f: Function::Plugin(f),
+            weight,
+            misbehaviors: 0,
+        });
         self
     }
 
@@ -41,6 +289,236 @@ impl Evaluator {
 
 #[derive(Clone)]
 struct WeightedEvaluator {
-    pub f: fn(&Board, usize) -> f64,
+    pub f: Function,
     pub weight: f64,
+    pub misbehaviors: usize,
+}
+
+impl WeightedEvaluator {
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn misbehave(&mut self, index: usize, output: f64) {
+        self.misbehaviors += 1;
+
+        #[cfg(feature = "tracing")]
+        if self.misbehaviors <= Evaluator::MISBEHAVIOR_WARNINGS {
+            tracing::warn!("the evaluator {index} returned {output}, out of 0.0..=1.0");
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Function {
+    Native(fn(&Board, usize) -> f64),
+    Hypothetical(fn(&Board, usize) -> f64, HypotheticalFn),
+    Plugin(EvaluatorFn),
+    Dynamic(Arc<DynFn>),
+}
+
+type DynFn = dyn Fn(&Board, usize) -> f64 + Send + Sync;
+
+/// The form of an evaluator for the hypothetical boards; see
+/// [`Evaluator::inject_hypothetical_evaluator`].
+pub type HypotheticalFn = fn(&HypotheticalBoard, usize) -> f64;
+
+impl Function {
+    fn call(&self, board: &Board, last_move: usize) -> f64 {
+        match self {
+            Function::Native(f) | Function::Hypothetical(f, _) => f(board, last_move),
+            Function::Dynamic(f) => f(board, last_move),
+            Function::Plugin(f) => {
+                let queens: Vec<_> = board.sorted_queens().collect();
+                let view = BoardView::new(board.width(), &queens);
+                // safety: the plugin is kept loaded, as required by `inject_plugin_evaluator`
+                unsafe { f(&view, last_move) }
+            }
+        }
+    }
+}
+
+#[test]
+fn fixed_point_quantization_works() {
+    let q = Quantization::FixedPoint;
+    let step = 1.0 / (1_u64 << Quantization::FIXED_POINT_BITS) as f64;
+    let unit = 1_u64 << (64 - Quantization::FIXED_POINT_BITS);
+
+    assert_eq!(q.quantize(0.0), 0);
+    assert_eq!(q.quantize(0.5), 1 << 63);
+    assert_eq!(q.quantize(0.25), 1 << 62);
+    assert_eq!(q.quantize(1.0), u64::MAX);
+    assert_eq!(q.quantize(f64::MIN_POSITIVE), 0);
+
+    // the rounding error of the sum is below the resolution
+    assert_eq!(q.quantize(0.1 + 0.2), q.quantize(0.3));
+    assert_eq!(q.quantize(0.7 - 1e-15), q.quantize(0.7 + 1e-15));
+
+    // exact ties round to even
+    assert_eq!(q.quantize(2.5 * step), 2 * unit);
+    assert_eq!(q.quantize(3.5 * step), 4 * unit);
+    assert_eq!(q.quantize(3.5 * step + step / 1024.0), 4 * unit);
+    assert_eq!(q.quantize(2.5 * step + step / 1024.0), 3 * unit);
+
+    // both quantizations order alike
+    let cast = Quantization::Cast;
+    assert!(q.quantize(0.3) < q.quantize(0.31) && cast.quantize(0.3) < cast.quantize(0.31));
+}
+
+#[test]
+fn misbehaving_evaluators_are_sanitized() {
+    type F = fn(&Board, usize) -> f64;
+
+    let mut board = Board::new(6);
+    board.toggle(14);
+    let score = |evaluators: &[(F, f64)]| {
+        let mut evaluator = Evaluator::default();
+        for (f, weight) in evaluators {
+            evaluator.inject_evaluator(*f, *weight);
+        }
+        (evaluator.score(&board, 14), evaluator.misbehaviors())
+    };
+    let good: F = |_, _| 0.25;
+
+    // a NaN output is the least one, and the infinite outputs are clamped, keeping their weight
+    for (bad, clamped) in [
+        ((|_, _| f64::NAN) as F, (|_, _| 0.0) as F),
+        (|_, _| f64::INFINITY, |_, _| 1.0),
+        (|_, _| f64::NEG_INFINITY, |_, _| 0.0),
+    ] {
+        let (scored, misbehaviors) = score(&[(good, 1.0), (bad, 5.0)]);
+        assert_eq!(scored, score(&[(good, 1.0), (clamped, 5.0)]).0);
+        assert_ne!(scored, score(&[(good, 1.0)]).0);
+        assert_eq!(misbehaviors, [0, 1]);
+    }
+    assert_eq!(
+        score(&[(good, 1.0), (|_, _| f64::INFINITY, 1.0)]).0,
+        Quantization::Cast.quantize(0.625)
+    );
+
+    // an output out of range is clamped
+    let (scored, misbehaviors) = score(&[(good, 1.0), (|_, _| 5.0, 2.0)]);
+    assert_eq!(scored, score(&[(good, 1.0), (|_, _| 1.0, 2.0)]).0);
+    assert_eq!(misbehaviors, [0, 1]);
+    let (scored, misbehaviors) = score(&[(good, 1.0), (|_, _| -3.0, 2.0)]);
+    assert_eq!(scored, score(&[(good, 1.0), (|_, _| 0.0, 2.0)]).0);
+    assert_eq!(misbehaviors, [0, 1]);
+
+    // a lone NaN scores as the least output
+    let (scored, misbehaviors) = score(&[(|_, _| f64::NAN, 1.0)]);
+    assert_eq!(scored, score(&[]).0);
+    assert_eq!(misbehaviors, [1]);
+}
+
+#[test]
+fn reducers_combine_the_outputs() {
+    type F = fn(&Board, usize) -> f64;
+
+    let mut board = Board::new(6);
+    board.toggle(14);
+    let evaluators: [(F, f64); 4] = [
+        (|_, _| 0.25, 1.0),
+        (|_, _| 0.75, 3.0),
+        // the complement of the output, for the reducers other than the sum
+        (|_, _| 0.5, -1.0),
+        (|_, _| 0.0, 0.0),
+    ];
+    let score = |reducer| {
+        let mut evaluator = Evaluator::default();
+        evaluator.with_reducer(reducer);
+        for (f, weight) in evaluators {
+            evaluator.inject_evaluator(f, weight);
+        }
+        evaluator.score(&board, 14)
+    };
+    let cast = |score| Quantization::Cast.quantize(score);
+
+    assert_eq!(score(Reducer::default()), cast((0.25 + 2.25 - 0.5) / 5.0));
+    assert_eq!(score(Reducer::Max), cast(0.75));
+    assert_eq!(score(Reducer::Min), cast(0.25));
+    assert_eq!(score(Reducer::Product), cast(0.25 * 0.75 * 0.5));
+    assert!(score(Reducer::Product) < score(Reducer::Min));
+    assert!(score(Reducer::Min) < score(Reducer::WeightedSum));
+    assert!(score(Reducer::WeightedSum) < score(Reducer::Max));
+
+    // without evaluators, every reducer scores the least
+    let empty = Evaluator::default().score(&board, 14);
+    for reducer in [Reducer::Max, Reducer::Min, Reducer::Product] {
+        assert_eq!(
+            Evaluator::default().with_reducer(reducer).score(&board, 14),
+            empty
+        );
+    }
+}
+
+#[test]
+fn close_scores_sort_distinctly() {
+    use crate::{Frontier, Orderer, ScoreOrderer};
+
+    // the candidates differ in the seventh decimal of their scores
+    let mut evaluator = Evaluator::default();
+    evaluator.inject_evaluator(|_, last_move| 0.5 + last_move as f64 * 1e-7, 1.0);
+    let board = Board::new(4);
+    let close = [3, 1, 2].map(|i| evaluator.score_f64(&board, i));
+    assert!(close[1] < close[2] && close[2] < close[0]);
+
+    // the quantized scores keep the order, as the fixed point does at its resolution
+    let mut candidates = [3, 1, 2].map(|index| Frontier {
+        index,
+        score: evaluator.score(&board, index),
+        node: None,
+    });
+    ScoreOrderer.order(&board, &mut candidates);
+    assert_eq!(candidates.map(|f| f.index), [1, 2, 3]);
+    evaluator.set_quantization(Quantization::FixedPoint);
+    assert!(evaluator.score(&board, 1) < evaluator.score(&board, 2));
+}
+
+#[cfg(all(test, feature = "std"))]
+proptest::proptest! {
+    #![proptest_config(proptest::prelude::ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn hypothetical_scores_match_the_toggles(
+        board in crate::test_support::partial_board(4..=10, 0..=4),
+        last_move in proptest::prelude::any::<usize>(),
+    ) {
+        use crate::test_support::BUILTINS;
+
+        let last_move = last_move % (board.width() * board.width());
+        let mut toggled = Evaluator::default();
+        let mut hypothetical = Evaluator::default();
+        hypothetical.set_hypothetical(true);
+        for (i, builtin) in BUILTINS.iter().enumerate() {
+            let (f, h) = (builtin.evaluator, builtin.hypothetical);
+            toggled.inject_evaluator(f, i as f64 + 1.0);
+            hypothetical.inject_hypothetical_evaluator(f, h, i as f64 + 1.0);
+
+            // the outputs of every evaluator are the same, bit for bit
+            for candidate in board.available() {
+                for last_move in [candidate, last_move] {
+                    let mut placed = board.clone();
+                    placed.toggle(candidate);
+                    let expected = f(&placed, last_move);
+                    let output = board.with_hypothetical(candidate, |view| h(view, last_move));
+                    proptest::prop_assert_eq!(output.to_bits(), expected.to_bits(), "{}", builtin.name);
+                }
+            }
+        }
+
+        // and so are the weighted scores, leaving the board untouched
+        proptest::prop_assert!(hypothetical.is_hypothetical());
+        let mut board = board;
+        let before = board.clone();
+        for candidate in before.available() {
+            let expected = toggled.score_candidate(&mut board, candidate, last_move);
+            proptest::prop_assert_eq!(
+                hypothetical.score_hypothetical(&board, candidate, last_move),
+                Some(expected)
+            );
+            proptest::prop_assert_eq!(
+                hypothetical.score_candidate(&mut board, candidate, last_move),
+                expected
+            );
+        }
+        proptest::prop_assert_eq!(&board, &before);
+        proptest::prop_assert_eq!(toggled.score_hypothetical(&board, 0, 0), None);
+    }
 }
@@ -0,0 +1,89 @@
+use crate::Box;
+
+/// A decision of the solver, emitted to the sink of [`crate::Solver::set_event_sink`].
+///
+/// The `depth` is the number of moves of the current path, and `jump` is the total number of
+/// jumps of the solver when the event was emitted. The indexes are relative to the given board.
+///
+/// With the `serde` feature, the events are serialized as objects tagged by a snake case `event`
+/// field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "event", rename_all = "snake_case")
+)]
+pub enum SolverEvent {
+    /// A node of the search was entered.
+    NodeEntered { jump: usize, depth: usize },
+    /// A candidate move of the current node was scored.
+    CandidateScored {
+        index: usize,
+        score: u64,
+        jump: usize,
+        depth: usize,
+    },
+    /// A move was undone, as its path didn't lead to a solution.
+    Backtracked {
+        index: usize,
+        jump: usize,
+        depth: usize,
+    },
+    /// The board of the current node is depleted.
+    DepletedHit { jump: usize, depth: usize },
+    /// The board of the current node is solved.
+    Solved { jump: usize, depth: usize },
+}
+
+type Sink = dyn FnMut(SolverEvent) + Send;
+
+/// The optional sink of the events; it isn't carried over when the solver is cloned.
+#[derive(Default)]
+pub(crate) struct EventSink {
+    sink: Option<Box<Sink>>,
+    sampling: usize,
+    scored: usize,
+}
+
+impl EventSink {
+    pub fn set_sink<F>(&mut self, sink: F)
+    where
+        F: FnMut(SolverEvent) + Send + 'static,
+    {
+        self.sink.replace(Box::new(sink));
+    }
+
+    pub const fn is_set(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    pub fn set_sampling(&mut self, sampling: usize) {
+        self.sampling = sampling;
+    }
+
+    pub fn emit(&mut self, event: SolverEvent) {
+        let Some(sink) = self.sink.as_mut() else {
+            return;
+        };
+
+        // the candidates dominate the stream, so only them are sampled
+        if let SolverEvent::CandidateScored { .. } = event {
+            self.scored += 1;
+            if self.sampling > 1 && !(self.scored - 1).is_multiple_of(self.sampling) {
+                return;
+            }
+        }
+
+        sink(event);
+    }
+}
+
+impl Clone for EventSink {
+    fn clone(&self) -> Self {
+        Self {
+            sink: None,
+            sampling: self.sampling,
+            scored: 0,
+        }
+    }
+}
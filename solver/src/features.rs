@@ -0,0 +1,214 @@
+//! Fixed-size feature vectors of the candidate moves, so the move-ordering models are trained
+//! and served with the same layout.
+//!
+//! Every feature is a ratio between `0.0` and `1.0`, with `w` as the width of the board:
+//!
+//! | index | name                | value                                                          |
+//! |-------|---------------------|----------------------------------------------------------------|
+//! | 0     | `row`               | the row of the candidate over `w - 1`                          |
+//! | 1     | `column`            | the column of the candidate over `w - 1`                       |
+//! | 2     | `center_distance`   | the manhattan distance to the center over the distance of a corner |
+//! | 3     | `row_free`          | the other free cells of the row over `w - 1`                   |
+//! | 4     | `column_free`       | the other free cells of the column over `w - 1`                |
+//! | 5     | `principal_free`    | the other free cells of the principal diagonal over `w - 1`    |
+//! | 6     | `antidiagonal_free` | the other free cells of the antidiagonal over `w - 1`          |
+//! | 7     | `knight_queens`     | the queens a knight move away over `8`                         |
+//! | 8     | `fill`              | the queens of the board over `w`                               |
+//! | 9     | `free`              | the free cells of the board over `w * w`                       |
+//! | 10    | `attacked_free`     | the other free cells attacked by the candidate over the free cells of the board |
+//!
+//! A ratio with a zero denominator, as the ones of a single cell board, is `0.0`. The layout is
+//! pinned by the tests, so a model trained on it isn't silently invalidated.
+
+use crate::{vec, Board, Vec};
+
+/// The number of features of a candidate.
+pub const FEATURE_COUNT: usize = 11;
+
+/// The features of a candidate, in the layout of [`FEATURE_NAMES`].
+pub type Features = [f32; FEATURE_COUNT];
+
+/// The names of the features, in the order of the layout.
+pub const FEATURE_NAMES: [&str; FEATURE_COUNT] = [
+    "row",
+    "column",
+    "center_distance",
+    "row_free",
+    "column_free",
+    "principal_free",
+    "antidiagonal_free",
+    "knight_queens",
+    "fill",
+    "free",
+    "attacked_free",
+];
+
+/// Extracts the features of a candidate cell of the board.
+///
+/// The board is scanned once for every call; prefer [`extract_batch`] for all the candidates of
+/// a board.
+pub fn extract(board: &Board, candidate: usize) -> Features {
+    Scan::new(board).features(board, candidate)
+}
+
+/// Extracts the features of every available cell of the board, in ascending order of the cells,
+/// sharing a single scan of the board.
+pub fn extract_batch(board: &Board) -> Vec<(usize, Features)> {
+    let scan = Scan::new(board);
+    board
+        .available()
+        .map(|candidate| (candidate, scan.features(board, candidate)))
+        .collect()
+}
+
+/// The free cells of every line of the board.
+struct Scan {
+    queens: usize,
+    free: usize,
+    rows: Vec<usize>,
+    columns: Vec<usize>,
+    /// Indexed by `column + w - 1 - row`.
+    principals: Vec<usize>,
+    /// Indexed by `row + column`.
+    antidiagonals: Vec<usize>,
+}
+
+impl Scan {
+    fn new(board: &Board) -> Self {
+        let width = board.width();
+        let diagonals = (2 * width).saturating_sub(1);
+        let mut scan = Self {
+            queens: board.sorted_queens().count(),
+            free: 0,
+            rows: vec![0; width],
+            columns: vec![0; width],
+            principals: vec![0; diagonals],
+            antidiagonals: vec![0; diagonals],
+        };
+        for (row, cells) in board.rows().enumerate() {
+            for (column, _) in cells.iter().enumerate().filter(|(_, c)| c.is_free()) {
+                scan.free += 1;
+                scan.rows[row] += 1;
+                scan.columns[column] += 1;
+                scan.principals[column + width - 1 - row] += 1;
+                scan.antidiagonals[row + column] += 1;
+            }
+        }
+        scan
+    }
+
+    fn features(&self, board: &Board, candidate: usize) -> Features {
+        let width = board.width();
+        let (row, column) = (candidate / width, candidate % width);
+        let last = width.saturating_sub(1);
+
+        // the lines of the candidate count it once if it is free
+        let own = board
+            .rows()
+            .nth(row)
+            .map_or(0, |cells| usize::from(cells[column].is_free()));
+        let row_free = self.rows[row] - own;
+        let column_free = self.columns[column] - own;
+        let principal_free = self.principals[column + last - row] - own;
+        let antidiagonal_free = self.antidiagonals[row + column] - own;
+
+        let knights = [(1, 2), (2, 1)]
+            .into_iter()
+            .flat_map(|(r, c)| [(r, c), (r, -c), (-r, c), (-r, -c)])
+            .filter_map(|(r, c)| {
+                let r = row.checked_add_signed(r).filter(|r| *r < width)?;
+                let c = column.checked_add_signed(c).filter(|c| *c < width)?;
+                board.is_queen(r * width + c).then_some(())
+            })
+            .count();
+
+        let center_distance = (2 * row + 1).abs_diff(width) + (2 * column + 1).abs_diff(width);
+
+        [
+            ratio(row, last),
+            ratio(column, last),
+            ratio(center_distance, 2 * last),
+            ratio(row_free, last),
+            ratio(column_free, last),
+            ratio(principal_free, last),
+            ratio(antidiagonal_free, last),
+            ratio(knights, 8),
+            ratio(self.queens, width),
+            ratio(self.free, width * width),
+            ratio(
+                row_free + column_free + principal_free + antidiagonal_free,
+                self.free,
+            ),
+        ]
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f32 {
+    match denominator {
+        0 => 0.0,
+        d => numerator as f32 / d as f32,
+    }
+}
+
+#[test]
+fn extract_works() {
+    // the center of an empty board
+    assert_eq!(
+        extract(&Board::new(4), 5),
+        [
+            1.0 / 3.0,
+            1.0 / 3.0,
+            1.0 / 3.0,
+            1.0,
+            1.0,
+            1.0,
+            2.0 / 3.0,
+            0.0,
+            0.0,
+            1.0,
+            11.0 / 16.0,
+        ]
+    );
+
+    // a knight move away from a queen on the corner, which leaves six free cells
+    let mut board = Board::new(4);
+    board.toggle(0);
+    assert_eq!(
+        extract(&board, 6),
+        [
+            1.0 / 3.0,
+            2.0 / 3.0,
+            1.0 / 3.0,
+            1.0 / 3.0,
+            1.0 / 3.0,
+            1.0 / 3.0,
+            1.0 / 3.0,
+            1.0 / 8.0,
+            1.0 / 4.0,
+            6.0 / 16.0,
+            4.0 / 6.0,
+        ]
+    );
+
+    // the single cell board has no lines
+    assert_eq!(
+        extract(&Board::new(1), 0),
+        [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+    );
+}
+
+#[test]
+fn extract_batch_works() {
+    let mut board = Board::new(8);
+    board.toggle(3).toggle(9).toggle(20);
+
+    let batch = extract_batch(&board);
+    assert_eq!(
+        batch.iter().map(|(c, _)| *c).collect::<Vec<_>>(),
+        board.available().collect::<Vec<_>>()
+    );
+    for (candidate, features) in batch {
+        assert_eq!(features, extract(&board, candidate));
+        assert!(features.iter().all(|f| (0.0..=1.0).contains(f)));
+    }
+}
@@ -1,10 +1,59 @@
-pub use reginae_core::{Board, Cell};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, collections::BTreeSet, string::String, sync::Arc, vec, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::{boxed::Box, collections::BTreeSet, string::String, sync::Arc, vec, vec::Vec};
+
+pub use reginae_core::{
+    Board, Cell, HypotheticalBoard, ParseBoardError, Piece, PlacementError, Position, ResizeError,
+    ToggleResult,
+};
 
 mod solver;
-pub use solver::{Solution, Solver};
+pub use solver::{FirstMove, Outcome, Solution, Solutions, Solver};
+
+#[cfg(feature = "std")]
+mod parallel;
+#[cfg(feature = "std")]
+pub use parallel::ParallelSolver;
 
 mod evaluator;
-pub use evaluator::Evaluator;
+pub use evaluator::{Evaluator, HypotheticalFn, Quantization, Reducer};
+
+pub mod book;
+
+pub mod features;
+
+mod orderer;
+pub use orderer::{CenterFirst, FeatureOrderer, Frontier, Orderer, OrdererClone, ScoreOrderer};
+
+mod permutation;
+pub use permutation::PermutationBoard;
 
 mod normalized;
 pub use normalized::NormalizedBoard;
+
+mod depleted;
+
+mod scratch;
+
+mod events;
+pub use events::SolverEvent;
+
+mod stats;
+pub use stats::SolverStats;
+
+mod tree;
+pub use tree::{NodeResult, SearchTree, TreeNode, DEFAULT_TREE_CAP};
+
+#[cfg(feature = "image")]
+pub mod render;
+
+// the strategies of `proptest` need `std`, so the tests without it don't share them
+#[cfg(any(all(test, feature = "std"), feature = "test-utils"))]
+pub mod test_support;
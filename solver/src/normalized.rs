@@ -1,17 +1,32 @@
 use crate::Board;
 use core::ops::{Deref, DerefMut};
+use reginae_core::Direction;
 
+/// A board rotated, and possibly mirrored, so its queen nearest to a corner, scanning outwards
+/// from the corner, is nearest to the top-left one, tracking the transform to restore it on
+/// conversion.
+///
+/// The transform is the reflection across the vertical axis, if any, followed by the rotations.
+/// The normalization isn't canonical, as the distances of the transforms may tie; the canonical
+/// key of [`Board::canonical_key`] compares the queens of all the 8 images instead.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NormalizedBoard {
     board: Board,
     rotations: usize,
+    reflected: bool,
 }
 
 impl NormalizedBoard {
     pub fn merge(self, rhs: Self) -> Self {
+        // a reflection reverses the rotations applied before it
+        let rotations = match rhs.reflected {
+            true => 4 - self.rotations % 4 + rhs.rotations,
+            false => self.rotations + rhs.rotations,
+        };
         Self {
             board: rhs.board,
-            rotations: (self.rotations + rhs.rotations) % 4,
+            rotations: rotations % 4,
+            reflected: self.reflected != rhs.reflected,
         }
     }
 
@@ -21,28 +36,34 @@ impl NormalizedBoard {
             return self;
         }
 
-        let mut distances = [0; 4];
-        distances.iter_mut().for_each(|d| {
-            // safety: the board isn't empty so we are guaranteed to find a queen
-            *d = unsafe {
-                PolarScan::new(width)
-                    .enumerate()
-                    .find_map(|(i, q)| self.board.is_queen(q).then_some(i))
-                    .unwrap_unchecked()
-            };
-            self.rotate_clockwise();
+        // the distances of the 4 rotations, then of the 4 rotations of the mirrored board
+        let mut distances = [0; 8];
+        distances.chunks_mut(4).for_each(|chunk| {
+            chunk.iter_mut().for_each(|d| {
+                // safety: the board isn't empty so we are guaranteed to find a queen
+                *d = unsafe {
+                    PolarScan::new(width)
+                        .enumerate()
+                        .find_map(|(i, q)| self.board.is_queen(q).then_some(i))
+                        .unwrap_unchecked()
+                };
+                self.rotate_clockwise();
+            });
+            self.reflect_vertical();
         });
 
-        let rotations = if distances[0] <= distances[1].min(distances[2]).min(distances[3]) {
-            0
-        } else if distances[1] <= distances[2].min(distances[3]) {
-            1
-        } else if distances[2] <= distances[3] {
-            2
-        } else {
-            3
-        };
+        // the first of the least distances, so the rotations are preferred to the reflections
+        let transform = (1..8).fold(0, |best, t| match distances[t] < distances[best] {
+            true => t,
+            false => best,
+        });
+        let (reflected, rotations) = (transform >= 4, transform % 4);
 
+        if reflected {
+            self.reflect_vertical();
+            self.rotations = (4 - self.rotations) % 4;
+            self.reflected = !self.reflected;
+        }
         for _ in 0..rotations {
             self.rotate_clockwise();
         }
@@ -52,21 +73,51 @@ impl NormalizedBoard {
         self
     }
 
-    pub(crate) fn rotate_clockwise(&mut self) -> &mut Self {
+    /// Maps an index of the normalized board to the board it was created from.
+    pub(crate) fn denormalize_index(&self, index: usize) -> usize {
+        let lines = self.board.lines();
+        let width = lines.width();
+        let turns = (4 - self.rotations % 4) % 4;
+        let index = (0..turns).fold(index, |i, _| {
+            let ids = lines.line_ids(i);
+            ids.column * width + (width - 1 - ids.row)
+        });
+        match self.reflected {
+            true => {
+                let ids = lines.line_ids(index);
+                ids.row * width + (width - 1 - ids.column)
+            }
+            false => index,
+        }
+    }
+
+    /// Rotates the queens of the board by a quarter turn, without tracking the rotation; the
+    /// board won't be restored on conversion.
+    pub fn rotate_clockwise(&mut self) -> &mut Self {
         #[cfg(feature = "tracing")]
         tracing::trace!("rotating");
 
-        // clear the cells
-        let queens = self.board.take_queens();
+        self.board.rotate_clockwise();
+        self
+    }
 
-        // rotate each queen and update the board
-        let width = self.board.width();
-        queens.into_iter().for_each(|q| {
-            let truncated = q / width;
-            let term = 1 + q - truncated * width;
-            let q = width * term - truncated - 1;
-            self.board.toggle(q);
-        });
+    /// Mirrors the queens of the board across the horizontal axis, reversing the rows, without
+    /// tracking the reflection; the board won't be restored on conversion.
+    pub fn reflect_horizontal(&mut self) -> &mut Self {
+        #[cfg(feature = "tracing")]
+        tracing::trace!("reflecting horizontally");
+
+        self.board.flip(Direction::Horizontal);
+        self
+    }
+
+    /// Mirrors the queens of the board across the vertical axis, reversing the columns, without
+    /// tracking the reflection; the board won't be restored on conversion.
+    pub fn reflect_vertical(&mut self) -> &mut Self {
+        #[cfg(feature = "tracing")]
+        tracing::trace!("reflecting vertically");
+
+        self.board.flip(Direction::Vertical);
         self
     }
 }
@@ -76,6 +127,7 @@ impl From<Board> for NormalizedBoard {
         let mut normalized = Self {
             board,
             rotations: 0,
+            reflected: false,
         };
         normalized.normalize();
         normalized
@@ -85,10 +137,13 @@ impl From<Board> for NormalizedBoard {
 impl From<NormalizedBoard> for Board {
     fn from(mut board: NormalizedBoard) -> Self {
         let mut rotations = board.rotations;
-        while (rotations % 4) != 0 {
+        while !rotations.is_multiple_of(4) {
             board.rotate_clockwise();
             rotations += 1;
         }
+        if board.reflected {
+            board.reflect_vertical();
+        }
         board.board
     }
 }
@@ -252,38 +307,147 @@ fn polar_scan_works() {
 }
 
 #[test]
-fn rotate_cases() {
-    fn case<Q>(width: usize, queens: Q, output: Q)
+fn reflect_cases() {
+    use crate::Vec;
+
+    fn case<Q>(width: usize, queens: Q, horizontal: Q, vertical: Q)
     where
         Q: IntoIterator<Item = usize>,
     {
-        let board = Board::new(width);
-        let board = NormalizedBoard::from(board);
-        let queens = queens
-            .into_iter()
-            .fold(board, |mut board, q| {
-                board.toggle(q);
-                board
-            })
-            .rotate_clockwise()
-            .queens()
-            .collect::<Vec<_>>();
-        let output = output.into_iter().collect::<Vec<_>>();
-        assert_eq!(queens, output, "failed for width {width}");
+        let board =
+            queens
+                .into_iter()
+                .fold(NormalizedBoard::from(Board::new(width)), |mut board, q| {
+                    board.toggle(q);
+                    board
+                });
+        let reflected = |f: fn(&mut NormalizedBoard) -> &mut NormalizedBoard| {
+            f(&mut board.clone()).sorted_queens().collect::<Vec<_>>()
+        };
+        let horizontal = horizontal.into_iter().collect::<Vec<_>>();
+        let vertical = vertical.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            reflected(NormalizedBoard::reflect_horizontal),
+            horizontal,
+            "failed for width {width}"
+        );
+        assert_eq!(
+            reflected(NormalizedBoard::reflect_vertical),
+            vertical,
+            "failed for width {width}"
+        );
     }
 
     case(
         8,
         [3, 14, 18, 31, 33, 44, 48, 61],
-        [1, 11, 21, 31, 34, 40, 54, 60],
+        [5, 8, 20, 25, 39, 42, 54, 59],
+        [4, 9, 21, 24, 38, 43, 55, 58],
     );
-    case(8, [27], [28]);
-    case(8, [28], [36]);
-    case(8, [36], [35]);
-    case(8, [35], [27]);
-    case(9, [40], [40]);
-    case(9, [31], [41]);
-    case(9, [41], [49]);
-    case(9, [49], [39]);
-    case(9, [39], [31]);
+    case(8, [27], [35], [28]);
+    case(8, [0], [56], [7]);
+    case(9, [40], [40], [40]);
+    case(9, [31], [49], [31]);
+    case(9, [30], [48], [32]);
+}
+
+#[test]
+fn normalization_round_trips() {
+    // seeded, so the boards are reproducible
+    let mut rng = reginae_core::test_utils::XorShift::new(0x9e37_79b9_7f4a_7c15);
+    let mut next = |max: usize| rng.below(max);
+
+    let mut reflected = 0;
+    for _ in 0..200 {
+        let width = 1 + next(10);
+        let mut board = Board::new(width);
+        for _ in 0..next(width + 1) {
+            board.toggle(next(width * width));
+        }
+
+        // the normalized board is one of the 8 images, and is restored on conversion
+        let normalized = NormalizedBoard::from(board.clone());
+        assert!(board.symmetries().contains(&normalized));
+        reflected += usize::from(normalized.reflected);
+        for q in normalized.sorted_queens() {
+            assert!(board.is_queen(normalized.denormalize_index(q)));
+        }
+
+        assert_eq!(Board::from(normalized), board);
+    }
+    assert!(reflected > 0);
+
+    // the merged transforms are undone on conversion
+    let transformed = |board: &Board, rotations: usize, reflected: bool| {
+        let mut board = NormalizedBoard {
+            board: board.clone(),
+            rotations,
+            reflected,
+        };
+        if reflected {
+            board.reflect_vertical();
+        }
+        for _ in 0..rotations {
+            board.rotate_clockwise();
+        }
+        board
+    };
+    let board = Board::from_permutation(&[1, 3, 5, 0, 2, 4]).unwrap();
+    for (a, b) in (0..8).flat_map(|a| (0..8).map(move |b| (a, b))) {
+        let first = transformed(&board, a % 4, a >= 4);
+        let second = transformed(&first, b % 4, b >= 4);
+        assert_eq!(Board::from(first.merge(second)), board);
+    }
+}
+
+#[test]
+fn random_operations_keep_the_invariants() {
+    // seeded, so the sequences are reproducible
+    let mut rng = reginae_core::test_utils::XorShift::new(0x2545_f491_4f6c_dd1d);
+    let mut next = |max: usize| rng.below(max);
+
+    for _ in 0..200 {
+        let mut board = NormalizedBoard::from(Board::new(2 + next(11)));
+
+        for _ in 0..100 {
+            let width = board.width();
+            match next(10) {
+                0 => {
+                    board.rotate_clockwise();
+                }
+                1 => {
+                    board.clear();
+                }
+                2 => {
+                    // shrink, keeping the queens of the top-left region
+                    let resized = board.subboard(0, 0, 2 + next(width - 1));
+                    board = NormalizedBoard::from(resized);
+                }
+                3 => {
+                    // grow, keeping the queens on the same coordinates
+                    let mut resized = Board::new(width + 1 + next(3));
+                    board.sorted_queens().for_each(|q| {
+                        resized.toggle_with_pair(q % width, q / width);
+                    });
+                    board = NormalizedBoard::from(resized);
+                }
+                _ => {
+                    board.toggle(next(width * width));
+                }
+            }
+
+            reginae_core::test_utils::assert_board_consistent(&board);
+        }
+    }
+}
+
+#[test]
+fn removing_a_queen_keeps_shared_attacks() {
+    // regression: the cells attacked by both queens must stay attacked by the remaining one
+    let mut board = Board::new(8);
+    board.toggle(0).toggle(10);
+    board.toggle(0);
+    assert_eq!(board.validate_invariants(), Ok(()));
+    assert!(board.cells().nth(2).is_some_and(|c| c.is_attacked()));
+    assert!(board.cells().nth(8).is_some_and(|c| c.is_attacked()));
 }
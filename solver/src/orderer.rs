@@ -0,0 +1,169 @@
+use crate::{
+    features::{self, Features},
+    Board, Box,
+};
+#[cfg(test)]
+use crate::{NodeResult, Solver};
+
+/// A candidate move of a node of the search, with the weighted score of the evaluators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frontier {
+    /// The index of the cell of the move, on the board passed to the [`Orderer`].
+    pub index: usize,
+    /// The quantized score of the move; see [`crate::Quantization`].
+    pub score: u64,
+    pub(crate) node: Option<usize>,
+}
+
+/// Orders the scored candidates of a node, replacing the sort by score of the solver.
+///
+/// The candidates are explored from the last to the first, so the move explored first must be
+/// moved to the end. The board is the normalized board of the node, which the indexes refer to.
+///
+/// The orderer is cloned with the solver, as the subtrees of a [`crate::ParallelSolver`] are.
+pub trait Orderer: OrdererClone + Send {
+    fn order(&mut self, board: &Board, candidates: &mut [Frontier]);
+}
+
+/// Clones a boxed [`Orderer`]; implemented for every orderer that is [`Clone`].
+pub trait OrdererClone {
+    fn clone_box(&self) -> Box<dyn Orderer>;
+}
+
+impl<T> OrdererClone for T
+where
+    T: Orderer + Clone + 'static,
+{
+    fn clone_box(&self) -> Box<dyn Orderer> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Orderer> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// The default orderer: a stable sort by score, so the highest score is explored first and the
+/// ties are explored from the last available cell.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScoreOrderer;
+
+impl Orderer for ScoreOrderer {
+    fn order(&mut self, _board: &Board, candidates: &mut [Frontier]) {
+        candidates.sort_by_key(|f| f.score);
+    }
+}
+
+/// Explores the cells closest to the center of the board first, regardless of their scores,
+/// which only break the ties of the distance.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CenterFirst;
+
+impl Orderer for CenterFirst {
+    fn order(&mut self, board: &Board, candidates: &mut [Frontier]) {
+        // twice the distance to the center, so the center of even widths is an integer
+        let width = board.width();
+        let distance = |index: usize| {
+            let (row, column) = (index / width, index % width);
+            let (row, column) = (2 * row + 1, 2 * column + 1);
+            row.abs_diff(width) + column.abs_diff(width)
+        };
+        candidates.sort_by_key(|f| (core::cmp::Reverse(distance(f.index)), f.score));
+    }
+}
+
+/// Orders the candidates by a model over their [`features`], such as a learned ranking; the
+/// highest output is explored first, and the scores only break the ties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FeatureOrderer<F> {
+    model: F,
+}
+
+impl<F> FeatureOrderer<F>
+where
+    F: FnMut(&Features) -> f32 + Clone + Send + 'static,
+{
+    pub const fn new(model: F) -> Self {
+        Self { model }
+    }
+}
+
+impl<F> Orderer for FeatureOrderer<F>
+where
+    F: FnMut(&Features) -> f32 + Clone + Send + 'static,
+{
+    fn order(&mut self, board: &Board, candidates: &mut [Frontier]) {
+        // the candidates are the available cells, in any order
+        let batch = features::extract_batch(board);
+        let mut ranked = candidates
+            .iter()
+            .map(|f| {
+                let output = batch
+                    .binary_search_by_key(&f.index, |(c, _)| *c)
+                    .map_or(f32::NEG_INFINITY, |i| (self.model)(&batch[i].1));
+                (output, *f)
+            })
+            .collect::<crate::Vec<_>>();
+        ranked.sort_by(|(a, f), (b, g)| a.total_cmp(b).then(f.score.cmp(&g.score)));
+        candidates
+            .iter_mut()
+            .zip(ranked)
+            .for_each(|(candidate, (_, f))| *candidate = f);
+    }
+}
+
+#[test]
+fn center_first_changes_the_first_move() {
+    // the budget expands only the root and the move explored first
+    let first_move = |solver: &mut Solver| {
+        let tree = solver
+            .record_tree(true)
+            .with_max_jumps(2)
+            .solve(Board::new(8))
+            .tree
+            .expect("the tree is recorded");
+        let nodes = tree.nodes();
+        nodes
+            .iter()
+            .find(|n| n.parent == Some(0) && n.result == NodeResult::Expanded)
+            .map(|n| n.index)
+            .expect("a move of the root is expanded")
+    };
+
+    let mut solver = Solver::default();
+    solver.with_evaluator(reginae_evaluators::overlapping, 10.0);
+    let scored = first_move(&mut solver.clone());
+    let centered = first_move(solver.with_orderer(Box::new(CenterFirst)));
+    assert_ne!(scored, centered);
+
+    // the move is one of the four central cells, up to the rotation of the normalized board
+    let (row, column) = (centered / 8, centered % 8);
+    assert!((3..=4).contains(&row) && (3..=4).contains(&column));
+
+    // the default orderer is the sort by score
+    let mut solver = Solver::default();
+    solver.with_evaluator(reginae_evaluators::overlapping, 10.0);
+    let expected = solver.clone().solve(Board::new(8));
+    assert_eq!(
+        solver
+            .clone()
+            .with_orderer(Box::new(ScoreOrderer))
+            .solve(Board::new(8)),
+        expected
+    );
+
+    // a model of the distance to the center ranks the cells as the center first orderer
+    let model = FeatureOrderer::new(|f: &Features| -f[2]);
+    assert_eq!(
+        solver
+            .clone()
+            .with_orderer(Box::new(model))
+            .solve(Board::new(8)),
+        solver
+            .clone()
+            .with_orderer(Box::new(CenterFirst))
+            .solve(Board::new(8))
+    );
+}
@@ -0,0 +1,246 @@
+use crate::{Board, NormalizedBoard, Solution, Solver, SolverStats};
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+/// Solves a board on multiple threads, exploring the moves of its root concurrently.
+///
+/// Every move of the root is searched by a clone of the template solver, so the subtrees have
+/// independent jump counters and depleted boards. The jump budget of the template applies to
+/// every subtree, and neither the search tree nor the events are recorded.
+///
+/// By default, the first subtree to find a solution wins and the others are cancelled, so both
+/// the solution and the jumps depend on the scheduling of the threads. In the
+/// [deterministic](ParallelSolver::deterministic) mode, the results are committed in the order
+/// the sequential search explores the moves of the root:
+/// - the solution is the one of the first move, in that order, whose subtree is solvable; it is
+///   the solution [`Solver::solve`] finds, as the depleted boards shared by the sequential search
+///   only prune paths without solutions.
+/// - the jumps are the jump of the root plus the jumps of the subtrees of the moves up to the
+///   solved one. They are reproducible, but usually higher than the sequential jumps, as the
+///   subtrees don't share their depleted boards.
+/// - a subtree is cancelled only once a move explored before it is solved, so the latency is
+///   bounded by the slowest of the earlier subtrees.
+#[derive(Clone)]
+pub struct ParallelSolver {
+    solver: Solver,
+    threads: usize,
+    deterministic: bool,
+}
+
+impl ParallelSolver {
+    /// Creates a parallel solver cloning the given solver for every subtree, with a thread per
+    /// available core.
+    pub fn new(solver: Solver) -> Self {
+        Self {
+            solver,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            deterministic: false,
+        }
+    }
+
+    /// Sets the number of worker threads, with a minimum of one.
+    pub fn with_threads(&mut self, threads: usize) -> &mut Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Commits the results in the order of the sequential search, so the solution and the jumps
+    /// don't depend on the scheduling of the threads.
+    pub fn deterministic(&mut self, deterministic: bool) -> &mut Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Searches a completion of the board as [`Solver::solve`], with the same guarantee that the
+    /// board of a failed solution equals the given board.
+    pub fn solve(&self, board: Board) -> Solution {
+        let mut root = NormalizedBoard::from(board.clone());
+        let mut solver = self.solver.clone();
+        let seeded = solver.seed_root(&mut root);
+        let (mut winner, mut jumps, mut interrupted) = self.search(&mut solver, &mut root, None);
+
+        // the seed is only the first guess of the root, as for the sequential search, so the
+        // other moves of the root are searched once its subtree is exhausted
+        if let (None, Some(index), false) = (&winner, seeded, interrupted) {
+            root.toggle(index);
+            let (fallback, more, stopped) = self.search(&mut solver, &mut root, Some(index));
+            (winner, interrupted) = (fallback, stopped);
+            jumps += more;
+        }
+
+        match winner {
+            Some(winner) => Self::solution(winner.into(), true, false, jumps),
+            None => Self::solution(board, false, interrupted, jumps),
+        }
+    }
+
+    /// Searches the moves of the root but the excluded one concurrently, returning the solved
+    /// board, the jumps, and whether the search was interrupted.
+    fn search(
+        &self,
+        solver: &mut Solver,
+        root: &mut NormalizedBoard,
+        excluded: Option<usize>,
+    ) -> (Option<NormalizedBoard>, usize, bool) {
+        if solver.is_goal(root) {
+            return (Some(root.clone()), 0, false);
+        }
+        if solver.is_depleted(root) {
+            return (None, 0, false);
+        }
+
+        let mut moves = solver.root_moves(root);
+        moves.retain(|m| Some(*m) != excluded);
+        let cancels = (0..moves.len())
+            .map(|_| AtomicBool::new(false))
+            .collect::<Vec<_>>();
+        let results = Mutex::new(vec![None; moves.len()]);
+        let solved = AtomicUsize::new(usize::MAX);
+        let next = AtomicUsize::new(0);
+
+        let deterministic = self.deterministic;
+        let (root, moves, cancels, results, solved, next) =
+            (&*root, &moves, &cancels, &results, &solved, &next);
+        thread::scope(|s| {
+            for _ in 0..self.threads.min(moves.len()) {
+                // the solver isn't shared between threads, as its event sink isn't `Sync`
+                let template = self.solver.clone();
+                s.spawn(move || loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= moves.len() {
+                        break;
+                    }
+                    if solved.load(Ordering::Acquire) < i {
+                        continue;
+                    }
+
+                    let mut board = root.clone();
+                    let mut solver = template.clone();
+                    let success = solver.solve_move(&mut board, moves[i], &cancels[i]);
+                    let result = Subtree {
+                        success,
+                        interrupted: !success && solver.is_cancelled(Some(&cancels[i])),
+                        jumps: solver.jumps() - template.jumps(),
+                        board: success.then_some(board),
+                    };
+                    results.lock().expect("the results aren't poisoned")[i] = Some(result);
+
+                    if success {
+                        // the subtrees after the solved one can't change the result, and without
+                        // the deterministic mode, neither can the ones before it
+                        let first = solved.fetch_min(i, Ordering::AcqRel).min(i);
+                        cancels
+                            .iter()
+                            .enumerate()
+                            .filter(|(j, _)| !deterministic || *j > first)
+                            .for_each(|(_, c)| c.store(true, Ordering::Relaxed));
+                    }
+                });
+            }
+        });
+
+        let results = results
+            .lock()
+            .expect("the results aren't poisoned")
+            .split_off(0);
+        let mut jumps = 1;
+        let mut interrupted = false;
+        let mut winner = None;
+
+        if self.deterministic {
+            for result in results.into_iter().flatten() {
+                jumps += result.jumps;
+                interrupted |= result.interrupted;
+                if result.success {
+                    winner = result.board;
+                    break;
+                }
+            }
+        } else {
+            // the first subtree to be solved cancelled the others
+            let first = solved.load(Ordering::Acquire);
+            for (i, result) in results.into_iter().enumerate() {
+                let Some(result) = result else { continue };
+                jumps += result.jumps;
+                interrupted |= result.interrupted && first == usize::MAX;
+                if i == first {
+                    winner = result.board;
+                }
+            }
+        }
+
+        (winner, jumps, interrupted)
+    }
+
+    fn solution(board: Board, success: bool, interrupted: bool, jumps: usize) -> Solution {
+        Solution {
+            board,
+            success,
+            interrupted,
+            jumps,
+            tree: None,
+            path: Vec::new(),
+            stats: SolverStats {
+                jumps,
+                ..SolverStats::default()
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Subtree {
+    success: bool,
+    interrupted: bool,
+    jumps: usize,
+    board: Option<NormalizedBoard>,
+}
+
+#[test]
+fn deterministic_solve_is_reproducible() {
+    let mut solver = Solver::default();
+    solver
+        .with_evaluator(reginae_evaluators::overlapping, 10.0)
+        .with_evaluator(reginae_evaluators::ladder, 5.0);
+    let sequential = solver.clone().solve(Board::new(10));
+
+    let mut parallel = ParallelSolver::new(solver);
+    parallel.with_threads(4).deterministic(true);
+
+    let expected = parallel.solve(Board::new(10));
+    assert!(expected.success);
+    assert_eq!(expected.board, sequential.board);
+    for _ in 0..5 {
+        assert_eq!(parallel.solve(Board::new(10)), expected);
+    }
+
+    // the thread count doesn't change the result either
+    assert_eq!(parallel.with_threads(1).solve(Board::new(10)), expected);
+}
+
+#[test]
+fn parallel_solve_works() {
+    let parallel = ParallelSolver::new(Solver::default());
+    for width in [1, 5, 8] {
+        let solution = parallel.solve(Board::new(width));
+        assert!(solution.success);
+        assert!(solution.board.is_solved());
+    }
+
+    let solution = parallel.solve(Board::new(3));
+    assert!(!solution.success && !solution.interrupted);
+    assert_eq!(solution.board, Board::new(3));
+    assert_eq!(
+        solution.jumps,
+        ParallelSolver::new(Solver::default())
+            .deterministic(true)
+            .solve(Board::new(3))
+            .jumps
+    );
+}
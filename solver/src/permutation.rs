@@ -0,0 +1,276 @@
+use crate::{vec, Board, Vec};
+use reginae_core::PlacementError;
+
+/// A board with exactly one queen per row, as `columns[row] = column`, for the local search
+/// algorithms that move queens within their rows.
+///
+/// The queens of every column and diagonal are counted, and their rows combined by exclusive or,
+/// so the conflicts are O(1) to query and to update. Unlike [`Board`], the queens may attack each other; see
+/// [`Board::from_permutation`] and [`Board::to_permutation`] for the conflict-free boards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PermutationBoard {
+    columns: Vec<usize>,
+    column_queens: Vec<usize>,
+    /// Indexed by `column + width - 1 - row`.
+    principal_queens: Vec<usize>,
+    /// Indexed by `row + column`.
+    antidiagonal_queens: Vec<usize>,
+    /// The rows of the queens of every line, combined by exclusive or, indexed as the counts.
+    column_rows: Vec<usize>,
+    principal_rows: Vec<usize>,
+    antidiagonal_rows: Vec<usize>,
+}
+
+impl PermutationBoard {
+    /// Creates a board with the width of the columns, placing a queen on the given column of
+    /// every row.
+    pub fn new(columns: Vec<usize>) -> Result<Self, PlacementError> {
+        let width = columns.len();
+        if let Some((row, column)) = columns.iter().enumerate().find(|(_, c)| **c >= width) {
+            return Err(PlacementError::OutOfBounds(row * width + column));
+        }
+
+        let diagonals = (2 * width).saturating_sub(1);
+        let mut board = Self {
+            columns,
+            column_queens: vec![0; width],
+            principal_queens: vec![0; diagonals],
+            antidiagonal_queens: vec![0; diagonals],
+            column_rows: vec![0; width],
+            principal_rows: vec![0; diagonals],
+            antidiagonal_rows: vec![0; diagonals],
+        };
+        for row in 0..width {
+            board.count(row, board.columns[row], true);
+        }
+        Ok(board)
+    }
+
+    /// Converts a board with exactly one queen per row.
+    pub fn from_board(board: &Board) -> Option<Self> {
+        board.to_permutation().and_then(|p| Self::new(p).ok())
+    }
+
+    /// Converts into a board, failing with the first queen attacked by the queens of the
+    /// previous rows.
+    pub fn to_board(&self) -> Result<Board, PlacementError> {
+        Board::from_permutation(&self.columns)
+    }
+
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// The column of the queen of every row.
+    pub fn columns(&self) -> &[usize] {
+        &self.columns
+    }
+
+    /// The number of queens attacking the queen of the row.
+    pub fn conflicts(&self, row: usize) -> usize {
+        self.conflicts_at(row, self.columns[row])
+    }
+
+    /// The number of queens of the other rows that would attack the queen of the row if it was
+    /// moved to the column.
+    pub fn conflicts_at(&self, row: usize, column: usize) -> usize {
+        let (principal, antidiagonal) = self.diagonals(row, column);
+        let queens = self.column_queens[column]
+            + self.principal_queens[principal]
+            + self.antidiagonal_queens[antidiagonal];
+
+        // the queen of the row shares the lines of the cell only if it is on the cell
+        match self.columns[row] == column {
+            true => queens - 3,
+            false => queens,
+        }
+    }
+
+    /// The rows of the queens attacking the queen of the row on a line holding only the two of
+    /// them, such as the queens the queen of the row attacks since its last move, unless they
+    /// were attacked on that line before.
+    pub fn sole_attackers(&self, row: usize) -> impl Iterator<Item = usize> + '_ {
+        let column = self.columns[row];
+        let (principal, antidiagonal) = self.diagonals(row, column);
+        [
+            (self.column_queens[column], self.column_rows[column]),
+            (self.principal_queens[principal], self.principal_rows[principal]),
+            (
+                self.antidiagonal_queens[antidiagonal],
+                self.antidiagonal_rows[antidiagonal],
+            ),
+        ]
+        .into_iter()
+        .filter_map(move |(queens, rows)| (queens == 2).then_some(rows ^ row))
+    }
+
+    /// The number of pairs of queens attacking each other.
+    pub fn total_conflicts(&self) -> usize {
+        self.column_queens
+            .iter()
+            .chain(&self.principal_queens)
+            .chain(&self.antidiagonal_queens)
+            .map(|queens| queens * queens.saturating_sub(1) / 2)
+            .sum()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.total_conflicts() == 0
+    }
+
+    /// Moves the queen of the row to the column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column is out of the bounds of the board.
+    pub fn move_queen(&mut self, row: usize, column: usize) -> &mut Self {
+        assert!(column < self.width());
+
+        self.count(row, self.columns[row], false);
+        self.columns[row] = column;
+        self.count(row, column, true);
+        self
+    }
+
+    const fn diagonals(&self, row: usize, column: usize) -> (usize, usize) {
+        (column + self.columns.len() - 1 - row, row + column)
+    }
+
+    fn count(&mut self, row: usize, column: usize, queen: bool) {
+        let (principal, antidiagonal) = self.diagonals(row, column);
+        for queens in [
+            &mut self.column_queens[column],
+            &mut self.principal_queens[principal],
+            &mut self.antidiagonal_queens[antidiagonal],
+        ] {
+            if queen {
+                *queens += 1;
+            } else {
+                *queens -= 1;
+            }
+        }
+        // the exclusive or is its own inverse, so the row is removed as it was added
+        for rows in [
+            &mut self.column_rows[column],
+            &mut self.principal_rows[principal],
+            &mut self.antidiagonal_rows[antidiagonal],
+        ] {
+            *rows ^= row;
+        }
+    }
+}
+
+impl From<PermutationBoard> for Vec<usize> {
+    fn from(board: PermutationBoard) -> Self {
+        board.columns
+    }
+}
+
+#[test]
+fn permutation_board_round_trips() {
+    let solution = [1, 3, 0, 2];
+    let board = PermutationBoard::new(solution.to_vec()).unwrap();
+    assert!(board.is_solved());
+    assert_eq!(board.columns(), solution);
+
+    let full = board.to_board().unwrap();
+    assert!(full.is_solved());
+    assert_eq!(PermutationBoard::from_board(&full), Some(board.clone()));
+    assert_eq!(Vec::from(board), solution);
+
+    // the queens of the second row are attacked, and the last row has none
+    let mut full = Board::new(4);
+    full.toggle(1).toggle(11);
+    assert_eq!(PermutationBoard::from_board(&full), None);
+
+    assert_eq!(
+        PermutationBoard::new(vec![0, 4, 1]),
+        Err(PlacementError::OutOfBounds(7))
+    );
+    assert_eq!(
+        PermutationBoard::new(vec![0, 2, 1]).unwrap().to_board(),
+        Err(PlacementError::Attacked(7))
+    );
+}
+
+#[test]
+fn permutation_conflicts_match_the_board() {
+    // every arrangement of a queen per row of the width
+    let width: usize = 5;
+    let arrangements = (0..width.pow(width as u32)).map(|mut i| {
+        (0..width)
+            .map(|_| {
+                let column = i % width;
+                i /= width;
+                column
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let mut solutions = 0;
+    for columns in arrangements {
+        let mut board = PermutationBoard::new(columns.clone()).unwrap();
+
+        // the naive conflicts, pairwise
+        let attacks = |(r1, c1): (usize, &usize), (r2, c2): (usize, &usize)| {
+            r1 != r2 && (c1 == c2 || r1.abs_diff(r2) == c1.abs_diff(*c2))
+        };
+        let pairs = columns
+            .iter()
+            .enumerate()
+            .flat_map(|a| columns.iter().enumerate().map(move |b| (a, b)))
+            .filter(|(a, b)| a.0 < b.0 && attacks(*a, *b))
+            .count();
+        assert_eq!(board.total_conflicts(), pairs);
+        for row in 0..width {
+            let attackers = columns
+                .iter()
+                .enumerate()
+                .filter(|q| attacks((row, &columns[row]), *q))
+                .count();
+            assert_eq!(board.conflicts(row), attackers);
+
+            // the attackers alone with the queen on a line
+            let column = columns[row];
+            let shares = |line: usize, r: usize| match line {
+                0 => columns[r] == column,
+                1 => columns[r] + row == column + r,
+                _ => columns[r] + r == column + row,
+            };
+            let mut sole = board.sole_attackers(row).collect::<Vec<_>>();
+            let mut expected = (0..3)
+                .filter_map(|line| {
+                    let others = (0..width)
+                        .filter(|r| *r != row && shares(line, *r))
+                        .collect::<Vec<_>>();
+                    (others.len() == 1).then(|| others[0])
+                })
+                .collect::<Vec<_>>();
+            sole.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(sole, expected);
+        }
+
+        // a conflict-free arrangement is a solved board, and the free cells of a row without its
+        // queen are the moves without conflicts
+        assert_eq!(board.is_solved(), board.to_board().is_ok());
+        if let Ok(full) = board.to_board() {
+            solutions += 1;
+            for (row, queen) in columns.iter().enumerate() {
+                let mut full = full.clone();
+                full.toggle(row * width + queen);
+                for column in 0..width {
+                    let free = full.cells().nth(row * width + column).unwrap().is_free();
+                    assert_eq!(free, board.conflicts_at(row, column) == 0);
+                }
+            }
+        }
+
+        // the moves keep the counts of a fresh board
+        board.move_queen(0, (columns[0] + 1) % width);
+        let mut moved = columns.clone();
+        moved[0] = (columns[0] + 1) % width;
+        assert_eq!(board, PermutationBoard::new(moved).unwrap());
+    }
+    assert_eq!(solutions, 10);
+}
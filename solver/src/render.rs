@@ -0,0 +1,396 @@
+use crate::Board;
+use std::{collections::BTreeSet, fmt::Write};
+
+/// The crown glyph of a queen, as a polygon in the unit square of a cell.
+const QUEEN: [(f64, f64); 7] = [
+    (0.2, 0.8),
+    (0.15, 0.35),
+    (0.35, 0.55),
+    (0.5, 0.25),
+    (0.65, 0.55),
+    (0.85, 0.35),
+    (0.8, 0.8),
+];
+
+/// The opacity of the attack shading.
+const ATTACK_ALPHA: f64 = 0.35;
+
+/// A RGB color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+
+    fn blend(&self, other: Color, alpha: f64) -> Color {
+        let mix = |a: u8, b: u8| (a as f64 * (1.0 - alpha) + b as f64 * alpha).round() as u8;
+        Color(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+}
+
+/// The colors of a rendered board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorScheme {
+    pub light: Color,
+    pub dark: Color,
+    pub queen: Color,
+    /// Blended over the attacked cells, if the attacks are shaded.
+    pub attack: Color,
+    /// Outline of the highlighted cells.
+    pub highlight: Color,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self {
+            light: Color(0xf0, 0xd9, 0xb5),
+            dark: Color(0xb5, 0x88, 0x63),
+            queen: Color(0x1a, 0x1a, 0x1a),
+            attack: Color(0xd0, 0x30, 0x30),
+            highlight: Color(0x20, 0x80, 0xf0),
+        }
+    }
+}
+
+/// Options of [`to_svg`] and [`to_png`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Width of a cell, in pixels.
+    pub cell_size: u32,
+    pub colors: ColorScheme,
+    /// Draw the chess coordinates along the borders; ignored by [`to_png`].
+    pub coordinates: bool,
+    /// Shade the cells attacked by the queens.
+    pub attacks: bool,
+    /// Indexes of cells to outline, such as the last moves or conflicts.
+    pub highlights: BTreeSet<usize>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 48,
+            colors: ColorScheme::default(),
+            coordinates: true,
+            attacks: false,
+            highlights: BTreeSet::new(),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// The background color of a cell, with the attack shading applied.
+    fn background(&self, board: &Board, index: usize) -> Color {
+        let width = board.width();
+        let color = match (index % width + index / width) % 2 {
+            0 => self.colors.light,
+            _ => self.colors.dark,
+        };
+        let attacked = board
+            .cells()
+            .nth(index)
+            .is_some_and(|c| c.is_attacked() && !c.is_queen());
+        if self.attacks && attacked {
+            color.blend(self.colors.attack, ATTACK_ALPHA)
+        } else {
+            color
+        }
+    }
+
+    /// The width of the highlight outline, in pixels.
+    fn stroke(&self) -> u32 {
+        (self.cell_size / 12).max(1)
+    }
+}
+
+/// The file of a column, as in chess notation; numbered past `z`.
+fn file(column: usize) -> String {
+    match u8::try_from(column) {
+        Ok(c) if c < 26 => char::from(b'a' + c).to_string(),
+        _ => (column + 1).to_string(),
+    }
+}
+
+/// Renders the board as a SVG checkerboard, with a `queen` polygon per queen.
+pub fn to_svg(board: &Board, options: &RenderOptions) -> String {
+    let width = board.width();
+    let size = options.cell_size as usize;
+    let side = width * size;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{side}\" height=\"{side}\" viewBox=\"0 0 {side} {side}\">"
+    );
+
+    for index in 0..width * width {
+        let (x, y) = (index % width * size, index / width * size);
+        let _ = writeln!(
+            svg,
+            "  <rect x=\"{x}\" y=\"{y}\" width=\"{size}\" height=\"{size}\" fill=\"{}\"/>",
+            options.background(board, index).hex()
+        );
+    }
+
+    for index in &options.highlights {
+        if *index >= width * width {
+            continue;
+        }
+        let stroke = options.stroke() as usize;
+        let (x, y) = (
+            index % width * size + stroke / 2,
+            index / width * size + stroke / 2,
+        );
+        let _ = writeln!(
+            svg,
+            "  <rect class=\"highlight\" x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{stroke}\"/>",
+            size - stroke,
+            size - stroke,
+            options.colors.highlight.hex()
+        );
+    }
+
+    for index in board.sorted_queens() {
+        let (x, y) = (index % width * size, index / width * size);
+        let points = QUEEN
+            .iter()
+            .map(|(px, py)| {
+                format!(
+                    "{:.1},{:.1}",
+                    x as f64 + px * size as f64,
+                    y as f64 + py * size as f64
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            svg,
+            "  <polygon class=\"queen\" points=\"{points}\" fill=\"{}\"/>",
+            options.colors.queen.hex()
+        );
+    }
+
+    if options.coordinates {
+        let font = size / 5;
+        for i in 0..width {
+            // the files along the bottom row, and the ranks along the left column
+            let _ = writeln!(
+                svg,
+                "  <text x=\"{}\" y=\"{}\" font-size=\"{font}\" text-anchor=\"end\">{}</text>",
+                (i + 1) * size - 2,
+                side - 2,
+                file(i)
+            );
+            let _ = writeln!(
+                svg,
+                "  <text x=\"2\" y=\"{}\" font-size=\"{font}\">{}</text>",
+                i * size + font,
+                width - i
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Renders the board as a RGB PNG image, with the same layout of [`to_svg`].
+///
+/// The coordinates are not drawn, as the rasterizer has no font support.
+pub fn to_png(board: &Board, options: &RenderOptions) -> Vec<u8> {
+    let width = board.width();
+    let size = options.cell_size as usize;
+    let side = width * size;
+    let stroke = options.stroke() as usize;
+
+    let mut pixels = vec![0u8; side * side * 3];
+    for index in 0..width * width {
+        let background = options.background(board, index);
+        let queen = board.is_queen(index);
+        let highlight = options.highlights.contains(&index);
+        let (x0, y0) = (index % width * size, index / width * size);
+
+        for y in 0..size {
+            for x in 0..size {
+                // sample at the center of the pixel
+                let (px, py) = (
+                    (x as f64 + 0.5) / size as f64,
+                    (y as f64 + 0.5) / size as f64,
+                );
+                let border = x < stroke || y < stroke || x >= size - stroke || y >= size - stroke;
+                let color = if queen && contains(&QUEEN, px, py) {
+                    options.colors.queen
+                } else if highlight && border {
+                    options.colors.highlight
+                } else {
+                    background
+                };
+
+                let offset = ((y0 + y) * side + x0 + x) * 3;
+                pixels[offset..offset + 3].copy_from_slice(&[color.0, color.1, color.2]);
+            }
+        }
+    }
+
+    encode_png(side as u32, side as u32, &pixels)
+}
+
+/// Even-odd test of a point against a polygon.
+fn contains(polygon: &[(f64, f64)], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let ((xi, yi), (xj, yj)) = (polygon[i], polygon[j]);
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, b| {
+        (0..8).fold(crc ^ *b as u32, |c, _| {
+            if c & 1 == 1 {
+                (c >> 1) ^ 0xedb8_8320
+            } else {
+                c >> 1
+            }
+        })
+    })
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), x| {
+        let a = (a + *x as u32) % 65_521;
+        (a, (b + a) % 65_521)
+    });
+    (b << 16) | a
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Encodes 8-bit RGB pixels as a PNG, with uncompressed deflate blocks.
+fn encode_png(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    // every scanline is prefixed with the `none` filter
+    let raw = pixels
+        .chunks(width as usize * 3)
+        .flat_map(|line| [&[0u8][..], line])
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        zlib.push(blocks.peek().is_none() as u8);
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &zlib);
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+#[test]
+fn svg_has_a_queen_per_queen() {
+    let mut board = Board::new(8);
+    board.toggle(1).toggle(11).toggle(63);
+
+    let options = RenderOptions {
+        attacks: true,
+        highlights: [63].into_iter().collect(),
+        ..RenderOptions::default()
+    };
+    let svg = to_svg(&board, &options);
+
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches("class=\"queen\"").count(), 3);
+    assert_eq!(svg.matches("class=\"highlight\"").count(), 1);
+    assert_eq!(svg.matches("<text").count(), 16);
+
+    // the elements are balanced, and nested in the single root
+    let mut open = Vec::new();
+    let mut roots = 0;
+    for tag in svg.split('<').skip(1).map(|t| &t[..t.find('>').unwrap()]) {
+        let name = tag.trim_start_matches('/').split(' ').next().unwrap();
+        if tag.starts_with('/') {
+            assert_eq!(open.pop(), Some(name));
+        } else if !tag.ends_with('/') {
+            roots += usize::from(open.is_empty());
+            open.push(name);
+        }
+    }
+    assert!(open.is_empty());
+    assert_eq!(roots, 1);
+
+    // the cell size and colors are applied
+    let options = RenderOptions {
+        cell_size: 10,
+        colors: ColorScheme {
+            queen: Color(1, 2, 3),
+            ..ColorScheme::default()
+        },
+        ..RenderOptions::default()
+    };
+    let svg = to_svg(&board, &options);
+    assert!(svg.contains("width=\"80\" height=\"80\""));
+    assert_eq!(svg.matches("fill=\"#010203\"").count(), 3);
+}
+
+#[test]
+fn png_has_the_board_dimensions() {
+    let mut board = Board::new(5);
+    board.toggle(0).toggle(7);
+
+    let options = RenderOptions {
+        cell_size: 10,
+        ..RenderOptions::default()
+    };
+    let png = to_png(&board, &options);
+
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 50);
+    assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 50);
+    assert_eq!(
+        u32::from_be_bytes(png[29..33].try_into().unwrap()),
+        crc32(&png[12..29])
+    );
+    assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+
+    // the uncompressed pixels follow the zlib and block headers of the data chunk
+    let data = &png[41 + 2 + 5..];
+    let line = 1 + 50 * 3;
+    assert_eq!(data[0], 0);
+    assert_eq!(&data[1..4], &[0xf0, 0xd9, 0xb5]);
+    assert_eq!(data[line * 50 - 3..line * 50], [0xf0, 0xd9, 0xb5]);
+}
@@ -0,0 +1,65 @@
+use crate::{solver::Frame, Frontier, Vec};
+use core::mem;
+
+/// Working memory of the solver, cleared but not reallocated between solves.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SolverScratch {
+    /// The frontier of every depth of the current path.
+    frontiers: Vec<Vec<Frontier>>,
+    /// The available indexes of the node being expanded.
+    candidates: Vec<usize>,
+    path: Vec<usize>,
+    /// The stack of the expanded nodes of the current path.
+    frames: Vec<Frame>,
+}
+
+impl SolverScratch {
+    /// Takes the cleared frontier buffer of the depth; it must be returned via
+    /// [`SolverScratch::put_frontier`].
+    pub fn take_frontier(&mut self, depth: usize) -> Vec<Frontier> {
+        if self.frontiers.len() <= depth {
+            self.frontiers.resize_with(depth + 1, Vec::new);
+        }
+        let mut frontier = mem::take(&mut self.frontiers[depth]);
+        frontier.clear();
+        frontier
+    }
+
+    pub fn put_frontier(&mut self, depth: usize, frontier: Vec<Frontier>) {
+        self.frontiers[depth] = frontier;
+    }
+
+    /// Takes the cleared candidates buffer; it must be returned via
+    /// [`SolverScratch::put_candidates`].
+    pub fn take_candidates(&mut self) -> Vec<usize> {
+        let mut candidates = mem::take(&mut self.candidates);
+        candidates.clear();
+        candidates
+    }
+
+    pub fn put_candidates(&mut self, candidates: Vec<usize>) {
+        self.candidates = candidates;
+    }
+
+    /// Takes the cleared path buffer; it must be returned via [`SolverScratch::put_path`].
+    pub fn take_path(&mut self) -> Vec<usize> {
+        let mut path = mem::take(&mut self.path);
+        path.clear();
+        path
+    }
+
+    pub fn put_path(&mut self, path: Vec<usize>) {
+        self.path = path;
+    }
+
+    /// Takes the cleared stack buffer; it must be returned via [`SolverScratch::put_frames`].
+    pub fn take_frames(&mut self) -> Vec<Frame> {
+        let mut frames = mem::take(&mut self.frames);
+        frames.clear();
+        frames
+    }
+
+    pub fn put_frames(&mut self, frames: Vec<Frame>) {
+        self.frames = frames;
+    }
+}
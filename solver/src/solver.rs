@@ -1,11 +1,69 @@
-use crate::{Board, Evaluator, NormalizedBoard};
-use radix_trie::Trie;
+use crate::{
+    depleted::Depleted,
+    events::{EventSink, SolverEvent},
+    scratch::SolverScratch,
+    tree::{NodeResult, SearchTree, DEFAULT_TREE_CAP},
+    vec, BTreeSet, Board, Box, Evaluator, Frontier, HypotheticalFn, NormalizedBoard, Orderer,
+    PermutationBoard, Piece, Quantization, Reducer, SolverStats, String, Vec,
+};
+use core::sync::atomic::{AtomicBool, Ordering};
+use reginae_core::{Direction, XorShift};
 
 #[derive(Default, Clone)]
 pub struct Solver {
-    depleted: Trie<Vec<usize>, ()>,
+    depleted: Depleted,
     evaluator: Evaluator,
     jumps: usize,
+    max_jumps: Option<usize>,
+    limit: Option<usize>,
+    record_tree: bool,
+    /// The canonical keys of the expanded boards, if tracked.
+    canonical: Option<BTreeSet<Vec<usize>>>,
+    tree_cap: Option<usize>,
+    tree: Option<SearchTree>,
+    events: EventSink,
+    goal: Option<fn(&Board) -> bool>,
+    scratch: SolverScratch,
+    /// Orders the scored candidates; defaults to the sort by score.
+    orderer: Option<Box<dyn Orderer>>,
+    root: RootMove,
+    opening_book: bool,
+    book_solutions: usize,
+    /// The deepest placement of the searches of `best_root`, in the coordinates of the board.
+    best_partial: Vec<usize>,
+    best_root: Option<Board>,
+}
+
+/// The move of the root of an empty board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RootMove {
+    /// Places the first queen on the cell, if available.
+    Cell(usize),
+    /// Places the first queen on the central cell.
+    Center,
+    /// Places the first queen on the cell of the highest score.
+    Best,
+    /// Scores all the cells, as any other node.
+    Scored,
+}
+
+impl Default for RootMove {
+    fn default() -> Self {
+        Self::Cell(0)
+    }
+}
+
+/// How the first queen of an empty board is placed; see [`Solver::with_first_move_strategy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FirstMove {
+    /// The top left corner, as the default root hint.
+    #[default]
+    Corner,
+    /// The central cell, or the bottom right of the four central cells of an even width.
+    Center,
+    /// The cell of the highest weighted score of the evaluators, with the ties broken by the
+    /// first cell.
+    EvaluatorBest,
 }
 
 impl Solver {
@@ -14,87 +72,2210 @@ impl Solver {
         self
     }
 
+    /// Injects an evaluator with a form for the hypothetical boards of
+    /// [`Solver::score_hypothetically`], as the instances of an evaluator generic over
+    /// [`crate::Position`]: `with_hypothetical_evaluator(overlapping, |b, m| overlapping(b, m), w)`.
+    pub fn with_hypothetical_evaluator(
+        &mut self,
+        f: fn(&Board, usize) -> f64,
+        hypothetical: HypotheticalFn,
+        weight: f64,
+    ) -> &mut Self {
+        self.evaluator
+            .inject_hypothetical_evaluator(f, hypothetical, weight);
+        self
+    }
+
+    /// Scores the candidates on a [`crate::HypotheticalBoard`] instead of toggling them on the
+    /// board, if every evaluator was injected via [`Solver::with_hypothetical_evaluator`];
+    /// disabled by default.
+    ///
+    /// The solutions and jumps are the same either way, as long as the two forms of every
+    /// evaluator agree.
+    pub fn score_hypothetically(&mut self, hypothetical: bool) -> &mut Self {
+        self.evaluator.set_hypothetical(hypothetical);
+        self
+    }
+
+    /// Injects a stateful evaluator, such as a sandboxed plugin instance.
+    pub fn with_evaluator_fn<F>(&mut self, f: F, weight: f64) -> &mut Self
+    where
+        F: Fn(&Board, usize) -> f64 + Send + Sync + 'static,
+    {
+        self.evaluator.inject_evaluator_fn(f, weight);
+        self
+    }
+
+    /// Injects an evaluator loaded from a plugin; see [`reginae_plugin_abi`].
+    ///
+    /// # Safety
+    ///
+    /// The plugin must remain loaded for as long as the solver is used.
+    pub unsafe fn with_plugin_evaluator(
+        &mut self,
+        f: reginae_plugin_abi::EvaluatorFn,
+        weight: f64,
+    ) -> &mut Self {
+        self.evaluator.inject_plugin_evaluator(f, weight);
+        self
+    }
+
+    /// Sets how the scores of the candidates are quantized; see [`Quantization`] for the
+    /// tradeoff of reproducible jumps across platforms.
+    pub fn with_quantization(&mut self, quantization: Quantization) -> &mut Self {
+        self.evaluator.set_quantization(quantization);
+        self
+    }
+
+    /// Sets how the outputs of the evaluators are combined into the scores of the candidates; see
+    /// [`Reducer`].
+    pub fn with_reducer(&mut self, reducer: Reducer) -> &mut Self {
+        self.evaluator.with_reducer(reducer);
+        self
+    }
+
+    /// Replaces the ordering of the scored candidates of every node, which defaults to
+    /// [`crate::ScoreOrderer`]; see [`Orderer`].
+    pub fn with_orderer(&mut self, orderer: Box<dyn Orderer>) -> &mut Self {
+        self.orderer.replace(orderer);
+        self
+    }
+
+    /// Sets the sink of the decisions of the solver; see [`SolverEvent`].
+    ///
+    /// Without a sink, the events are never built. The sink isn't carried over when the solver is
+    /// cloned.
+    pub fn set_event_sink<F>(&mut self, sink: F) -> &mut Self
+    where
+        F: FnMut(SolverEvent) + Send + 'static,
+    {
+        self.events.set_sink(sink);
+        self
+    }
+
+    /// Emits only one of every `sampling` [`SolverEvent::CandidateScored`] events, which dominate
+    /// the stream of huge searches.
+    pub fn with_event_sampling(&mut self, sampling: usize) -> &mut Self {
+        self.events.set_sampling(sampling);
+        self
+    }
+
+    /// Replaces the goal of the search, which defaults to [`Board::is_solved`].
+    ///
+    /// The goal must be invariant under the symmetries of the square, as the root is normalized
+    /// by its rotations and reflections, and the depleted boards are shared between rotations.
+    /// The depleted boards of the previous goal are discarded.
+    pub fn with_goal(&mut self, goal: fn(&Board) -> bool) -> &mut Self {
+        self.goal.replace(goal);
+        self.depleted = Depleted::default();
+        self
+    }
+
+    /// Records the explored search tree of the next solves into [`Solution::tree`].
+    pub fn record_tree(&mut self, record: bool) -> &mut Self {
+        self.record_tree = record;
+        self
+    }
+
+    /// Sets the cell of the first queen of an empty board, which defaults to `Some(0)`.
+    ///
+    /// With `None`, or a cell that isn't available on the board, the root is expanded as any other
+    /// node, scoring all its cells, so the first queen isn't biased towards the corner at the cost
+    /// of a wider root. An empty board isn't normalized, so the hint is a cell of the board as
+    /// given. The hinted queen is part of the solution unless its subtree is exhausted, when the
+    /// root is expanded as any other node.
+    pub fn with_root_hint(&mut self, hint: Option<usize>) -> &mut Self {
+        self.root = hint.map_or(RootMove::Scored, RootMove::Cell);
+        self
+    }
+
+    /// Sets the strategy that places the first queen of an empty board, replacing the root hint of
+    /// [`Solver::with_root_hint`].
+    ///
+    /// As with the hint, the first queen is backtracked only once its subtree is exhausted, so
+    /// the strategy changes the jumps to find a completion by orders of magnitude.
+    pub fn with_first_move_strategy(&mut self, strategy: FirstMove) -> &mut Self {
+        self.root = match strategy {
+            FirstMove::Corner => RootMove::Cell(0),
+            FirstMove::Center => RootMove::Center,
+            FirstMove::EvaluatorBest => RootMove::Best,
+        };
+        self
+    }
+
+    /// Answers the solves of empty boards with the closed-form solution of [`crate::book`], with
+    /// zero jumps, as long as it satisfies the goal; disabled by default.
+    ///
+    /// The book never applies to a board with queens or blocked cells, nor to a toroidal board or
+    /// a board of amazons, and the answered solves are counted by
+    /// [`SolverStats::book_solutions`].
+    pub fn use_opening_book(&mut self, use_book: bool) -> &mut Self {
+        self.opening_book = use_book;
+        self
+    }
+
+    /// Tracks the distinct boards expanded by the next solves, up to rotations and reflections,
+    /// into [`SolverStats::canonical_visits`].
+    ///
+    /// Every expanded board is mapped to its [`Board::canonical_key`], so the tracking costs the
+    /// symmetries of every jump and a key per distinct board.
+    pub fn track_canonical_visits(&mut self, track: bool) -> &mut Self {
+        self.canonical = track.then(|| self.canonical.take().unwrap_or_default());
+        self
+    }
+
+    /// Limits the number of nodes of the recorded search tree; defaults to [`DEFAULT_TREE_CAP`].
+    pub fn with_tree_cap(&mut self, cap: usize) -> &mut Self {
+        self.tree_cap.replace(cap);
+        self
+    }
+
+    /// Limits the number of jumps a single call to [`Solver::solve`] can perform.
+    ///
+    /// The depleted paths are kept between calls, so solving the same board again with a budget
+    /// will resume the search from where it was interrupted, as long as the budget is greater
+    /// than the width of the board.
+    pub fn with_max_jumps(&mut self, max_jumps: usize) -> &mut Self {
+        self.max_jumps.replace(max_jumps);
+        self
+    }
+
+    /// Marks the board produced by placing the given queens on an empty board of the given width
+    /// as depleted, so the search will never expand it (or any of its rotations).
+    ///
+    /// This allows injecting domain knowledge of known-bad partial configurations to prune the
+    /// search up front.
+    pub fn mark_depleted<Q>(&mut self, width: usize, queens: Q) -> &mut Self
+    where
+        Q: IntoIterator<Item = usize>,
+    {
+        let mut board = NormalizedBoard::from(Board::new(width));
+        queens.into_iter().for_each(|q| {
+            board.toggle(q);
+        });
+        self.deplete(&mut board);
+        self
+    }
+
+    /// Whether the board, or any of its rotations, was marked as depleted.
+    pub fn is_depleted(&mut self, board: &Board) -> bool {
+        self.depleted.contains(board)
+    }
+
+    /// Total number of jumps performed by this solver, accumulated between solves.
+    pub const fn jumps(&self) -> usize {
+        self.jumps
+    }
+
+    /// The queens placed by the deepest path reached by the searches of the last solved board, in
+    /// the order they were placed and in the coordinates of the board.
+    ///
+    /// The placement is kept across the backtracking, and across the budgeted solves of the same
+    /// board of [`Solver::with_max_jumps`], so its length never decreases until another board is
+    /// solved; the queens of the board itself aren't included.
+    pub fn best_partial(&self) -> &[usize] {
+        &self.best_partial
+    }
+
+    /// The counters of the searches of this solver, accumulated between solves.
+    pub fn stats(&self) -> SolverStats {
+        SolverStats {
+            jumps: self.jumps,
+            canonical_visits: self.canonical.as_ref().map(BTreeSet::len),
+            book_solutions: self.book_solutions,
+            evaluator_misbehaviors: self.evaluator.misbehaviors(),
+        }
+    }
+
+    /// Searches a completion of the board satisfying the goal.
+    ///
+    /// On success, the board of the solution holds the queens of the given board. Otherwise, the
+    /// search is unwound and the board of the solution equals the given board.
     pub fn solve(&mut self, board: Board) -> Solution {
+        self.solve_with(board, None)
+    }
+
+    /// Solves the board as [`Solver::solve`], interrupting the search once the flag is set, such
+    /// as by another thread.
+    ///
+    /// The flag is checked with the jump budget on every node, so the search unwinds promptly,
+    /// keeping the depleted paths. On failure, the board of the solution holds the deepest
+    /// placement reached, as [`Solver::best_partial`], on top of the queens of the given board,
+    /// so the progress of a cancelled search isn't lost.
+    pub fn solve_cancellable(&mut self, board: Board, cancel: &AtomicBool) -> Solution {
+        let mut solution = self.solve_with(board, Some(cancel));
+        if !solution.success {
+            self.best_partial.iter().for_each(|q| {
+                solution.board.toggle(*q);
+            });
+        }
+        solution
+    }
+
+    fn solve_with(&mut self, board: Board, cancel: Option<&AtomicBool>) -> Solution {
+        if let Some(solution) = self.solve_from_book(&board) {
+            return solution;
+        }
+
+        if self.best_root.as_ref() != Some(&board) {
+            self.best_partial.clear();
+            self.best_root.replace(board.clone());
+        }
+
         let mut normalized = NormalizedBoard::from(board);
-        let mut path = Vec::with_capacity(normalized.width());
-        let (success, jumps) = self._solve(&mut normalized, &mut path);
+        let mut path = self.scratch.take_path();
+        self.limit = self.max_jumps.map(|m| self.jumps.saturating_add(m));
+        self.tree = self.record_tree.then(|| {
+            SearchTree::new(
+                normalized.width(),
+                self.tree_cap.unwrap_or(DEFAULT_TREE_CAP),
+            )
+        });
+        let root = self.tree.as_mut().and_then(|t| t.push(None, 0, 0));
+
+        let (success, jumps) = self._solve(&mut normalized, &mut path, root, cancel);
+        let placed = match success {
+            true => path
+                .iter()
+                .map(|i| normalized.denormalize_index(*i))
+                .collect(),
+            false => Vec::new(),
+        };
+        self.scratch.put_path(path);
+        let interrupted = !success && self.is_cancelled(cancel);
+
+        // the recorded indexes are relative to the normalized board
+        let mut tree = self.tree.take();
+        if let Some(tree) = tree.as_mut() {
+            tree.map_indexes(|i| normalized.denormalize_index(i));
+        }
+
         let board = Board::from(normalized);
         Solution {
             board,
             success,
+            interrupted,
             jumps,
+            tree,
+            path: placed,
+            stats: self.stats(),
         }
     }
 
-    fn _solve(&mut self, board: &mut NormalizedBoard, path: &mut Vec<usize>) -> (bool, usize) {
-        if board.is_empty() {
-            board.toggle(0);
-        } else if board.is_solved() {
-            return (true, self.jumps);
+    /// Solves an empty board of the given width, returning the column of the queen of every row.
+    pub fn solve_permutation(&mut self, width: usize) -> Option<Vec<usize>> {
+        let solution = self.solve(Board::new(width));
+        solution
+            .success
+            .then(|| solution.board.to_permutation())
+            .flatten()
+    }
+
+    /// Finds a board of the given width satisfying the goal of [`Solver::with_goal`] with the
+    /// minimum number of queens, such as a minimum dominating set of queens.
+    ///
+    /// The search is iteratively deepened on the number of queens, enumerating the placements of
+    /// every depth in ascending order of their indexes, so the first goal found is the shallowest.
+    /// The queens are placed on the attacked cells too, as with [`Board::toggle_attacked`], so
+    /// the goal decides whether they may attack each other. The evaluators, the depleted boards,
+    /// and the recorded tree aren't used; the jump budget of [`Solver::with_max_jumps`] applies
+    /// to the whole search.
+    pub fn solve_min_queens(&mut self, width: usize) -> Solution {
+        self.limit = self.max_jumps.map(|m| self.jumps.saturating_add(m));
+
+        // the queens of a solution are the most the default goal accepts
+        let mut board = Board::new(width);
+        let mut success = self.is_goal(&board);
+        for depth in 1..=board.target_count() {
+            if success || self.is_interrupted() {
+                break;
+            }
+            success = self.deepen(&mut board, 0, depth);
         }
 
-        // check if the path is depleted
-        let mut sorted = path.clone();
-        sorted.sort();
-        if self.depleted.get(&sorted).is_some() {
-            return (false, self.jumps);
+        Solution {
+            board,
+            success,
+            interrupted: !success && self.is_interrupted(),
+            jumps: self.jumps,
+            tree: None,
+            path: Vec::new(),
+            stats: self.stats(),
         }
+    }
 
-        self.jumps += 1;
+    /// Finds the placement of the board satisfying the goal with the minimum total cost of the
+    /// queens, as set by [`Board::with_costs`].
+    ///
+    /// The queens are placed row by row, from the cheapest cells, and a branch is pruned once its
+    /// cost plus the cheapest cell of every remaining row can't improve the best placement. The
+    /// bishops share the rows, so they are placed in ascending order of their indexes instead, up
+    /// to [`Board::target_count`], and bounded by the cheapest free cells. If the jump budget is
+    /// depleted, the solution holds the best placement found so far, if any, but is flagged as
+    /// interrupted as it may not be the minimum.
+    pub fn solve_min_cost(&mut self, board: Board) -> Solution {
+        self.limit = self.max_jumps.map(|m| self.jumps.saturating_add(m));
 
-        // build the unexplored nodes list and score them
-        let last_move = path.last().copied().unwrap_or(0);
-        let mut unexplored: Vec<_> = board
-            .available()
-            .collect::<Vec<_>>()
+        let width = board.width();
+        let rows = board
+            .rows()
+            .enumerate()
+            .filter_map(|(r, row)| (!row.iter().any(|c| c.is_queen())).then_some(r))
+            .collect::<Vec<_>>();
+
+        let mut board = board;
+        let mut best = None;
+        if board.piece().attacks(Direction::Horizontal) {
+            // the cheapest cell of every remaining row, regardless of the attacks
+            let mut bounds = vec![0.0; rows.len() + 1];
+            for (i, row) in rows.iter().enumerate().rev() {
+                let cheapest = (row * width..(row + 1) * width)
+                    .map(|c| board.cost(c))
+                    .fold(f64::INFINITY, f64::min);
+                bounds[i] = bounds[i + 1] + cheapest;
+            }
+            self.minimize(&mut board, Next::Rows(&rows), &bounds, &mut best);
+        } else {
+            // the cheapest free cells of the remaining pieces, regardless of the attacks
+            let mut costs = board
+                .free_cells()
+                .filter(|c| board.cell(*c).is_some_and(|c| !c.is_blocked()))
+                .map(|c| board.cost(c))
+                .collect::<Vec<_>>();
+            costs.sort_by(f64::total_cmp);
+            let remaining = board
+                .target_count()
+                .saturating_sub(board.sorted_queens().count());
+            let bounds = (0..=remaining)
+                .map(|i| costs.iter().take(remaining - i).sum())
+                .collect::<Vec<f64>>();
+            self.minimize(&mut board, Next::From(0), &bounds, &mut best);
+        }
+
+        let interrupted = self.is_interrupted();
+        let success = best.is_some() && !interrupted;
+        Solution {
+            board: best.map(|(_, b)| b).unwrap_or(board),
+            success,
+            interrupted,
+            jumps: self.jumps,
+            tree: None,
+            path: Vec::new(),
+            stats: self.stats(),
+        }
+    }
+
+    /// Solves an empty board of the given width with the min-conflicts local search, which scales
+    /// to the widths out of the reach of the backtracking of [`Solver::solve`].
+    ///
+    /// A queen is placed on every row, and each is moved in turn to the column of the fewest
+    /// conflicts. Then, for up to `max_steps` steps, a random conflicted queen is moved to the
+    /// column of its row with the fewest conflicts, the ties broken at random. The random choices
+    /// are drawn from a fixed seed, so the search is reproducible.
+    ///
+    /// Every step is a jump, subject to the budget of [`Solver::with_max_jumps`]; the goal, the evaluators, and the depleted boards aren't used. On failure,
+    /// the solution holds the queens without conflicts. A local search can't prove a board
+    /// unsolvable, so the solution is interrupted once the steps are exhausted, unless the width
+    /// is 2 or 3, which have no solution.
+    pub fn solve_min_conflicts(&mut self, width: usize, max_steps: usize) -> Solution {
+        self.limit = self.max_jumps.map(|m| self.jumps.saturating_add(m));
+
+        // seeded, so the search is reproducible
+        let mut rng = XorShift::new(0x9e37_79b9_7f4a_7c15);
+        let mut random = |max: usize| rng.below(max);
+
+        let mut search =
+            PermutationBoard::new(vec![0; width]).expect("the first column is in bounds");
+        for row in 0..width {
+            let column = min_conflicts_column(&search, row, &mut random);
+            search.move_queen(row, column);
+        }
+        // every conflicted row is listed, but the rows whose conflicts were lifted by the moves of
+        // the others are only dropped once drawn
+        let mut rows = (0..width)
+            .filter(|r| search.conflicts(*r) > 0)
+            .collect::<Vec<_>>();
+        let mut listed = vec![false; width];
+        rows.iter().for_each(|r| listed[*r] = true);
+
+        let mut steps = 0;
+        let success = loop {
+            let row = loop {
+                if rows.is_empty() {
+                    break None;
+                }
+                let i = random(rows.len());
+                if search.conflicts(rows[i]) > 0 {
+                    break Some(rows[i]);
+                }
+                listed[rows.swap_remove(i)] = false;
+            };
+            let Some(row) = row else {
+                break true;
+            };
+            if steps == max_steps || self.is_interrupted() {
+                break false;
+            }
+            steps += 1;
+            self.jumps += 1;
+
+            let column = min_conflicts_column(&search, row, &mut random);
+            search.move_queen(row, column);
+
+            // the queens attacked by the moved one on the lines they had to themselves
+            for attacked in search.sole_attackers(row) {
+                if !listed[attacked] {
+                    listed[attacked] = true;
+                    rows.push(attacked);
+                }
+            }
+        };
+
+        let queens = (0..width)
+            .filter(|r| search.conflicts(*r) == 0)
+            .map(|r| r * width + search.columns()[r]);
+        let board =
+            Board::from_queens(width, queens).expect("the queens without conflicts are placed");
+        Solution {
+            board,
+            success,
+            // the widths 2 and 3 are the only ones without a solution
+            interrupted: !success && !matches!(width, 2 | 3),
+            jumps: self.jumps,
+            tree: None,
+            path: Vec::new(),
+            stats: self.stats(),
+        }
+    }
+
+    /// Lazily enumerates all the solutions reachable from the given board, in ascending order of
+    /// their queens.
+    ///
+    /// The queens are placed row by row, from the first row without a queen, so every solution is
+    /// produced exactly once; the bishops share the rows, so they are placed in ascending order of
+    /// their indexes instead, up to [`Board::target_count`]. The jump budget of
+    /// [`Solver::with_max_jumps`] applies to the whole enumeration.
+    pub fn solve_all(&mut self, board: Board) -> Solutions<'_> {
+        self.limit = self.max_jumps.map(|m| self.jumps.saturating_add(m));
+        Solutions::new(self, board)
+    }
+
+    /// Enumerates every completion of the board, keeping its queens as the fixed root, in the
+    /// ascending order of their queens of [`Solver::solve_all`].
+    ///
+    /// The order depends only on the board: the evaluators and the depleted boards aren't used,
+    /// and unlike [`Solver::solve_all`], the jump budget doesn't apply, so the enumeration is
+    /// never interrupted.
+    pub fn enumerate_from(&mut self, board: Board) -> Solutions<'_> {
+        self.limit = None;
+        Solutions::new(self, board)
+    }
+
+    /// Collects at most `max` solutions reachable from the given board, stopping the enumeration
+    /// as soon as they are found.
+    pub fn solve_all_limited(&mut self, board: Board, max: usize) -> Vec<Board> {
+        self.solve_all(board).take(max).collect()
+    }
+
+    /// Counts the solutions of the width that are distinct under the 8 symmetries of the square,
+    /// the 4 rotations and their mirrored images.
+    ///
+    /// Every solution is enumerated as by [`Solver::solve_all`], and counted only if it's the
+    /// representative of its orbit by [`Board::is_fundamental`]. Unlike the normalization of the
+    /// root of the search, which picks a transform by the distance of the queens to the corner
+    /// and may tie, the canonical key of a board takes the least queens of its 8 images, so every
+    /// orbit is counted once.
+    pub fn count_fundamental(&mut self, width: usize) -> usize {
+        self.count_fundamental_pieces(width, Piece::Queen)
+    }
+
+    /// Counts the solutions of the width and piece that are distinct under the 8 symmetries of
+    /// the square, as [`Solver::count_fundamental`] does for the queens.
+    pub fn count_fundamental_pieces(&mut self, width: usize, piece: Piece) -> usize {
+        self.solve_all(Board::with_piece(width, piece))
+            .filter(Board::is_fundamental)
+            .count()
+    }
+
+    /// Inserts all the rotations of the board into the depleted set; only the board itself if it
+    /// has blocked cells, as the rotated blocks are another puzzle.
+    fn deplete(&mut self, board: &mut NormalizedBoard) {
+        if board.blocked_cells().next().is_some() {
+            self.depleted.insert(board);
+            return;
+        }
+        for _ in 0..4 {
+            board.rotate_clockwise();
+            self.depleted.insert(board);
+        }
+    }
+
+    /// The moves of the root of the board, in the order the search explores them.
+    #[cfg(feature = "std")]
+    pub(crate) fn root_moves(&mut self, board: &mut NormalizedBoard) -> Vec<usize> {
+        let candidates = board.available().collect::<Vec<_>>();
+        let mut moves = candidates
             .into_iter()
             .map(|index| {
-                board.toggle(index);
-                let score = self.evaluator.score(board, last_move);
-                board.toggle(index);
+                let score = self.evaluator.score_candidate(board, index, 0);
                 Frontier {
-                    depleted: false,
                     index,
                     score,
+                    node: None,
                 }
             })
-            .collect();
+            .collect::<Vec<_>>();
 
-        // sort by score so we can pop the highest one
-        unexplored.sort_by_key(|f| f.score);
+        // the search pops the last move of the order
+        self.order(board, &mut moves);
+        moves.into_iter().rev().map(|f| f.index).collect()
+    }
+
+    /// Orders the scored candidates, so the last one is explored first.
+    fn order(&mut self, board: &Board, candidates: &mut [Frontier]) {
+        match self.orderer.as_mut() {
+            Some(orderer) => orderer.order(board, candidates),
+            None => candidates.sort_by_key(|f| f.score),
+        }
+    }
 
-        // A* the path recursively
-        while let Some(frontier) = unexplored.pop() {
-            path.push(frontier.index);
-            board.toggle(frontier.index);
+    /// Searches the subtree of a move of the root of the board, leaving the board solved on
+    /// success; the tree isn't recorded.
+    #[cfg(feature = "std")]
+    pub(crate) fn solve_move(
+        &mut self,
+        board: &mut NormalizedBoard,
+        index: usize,
+        cancel: &AtomicBool,
+    ) -> bool {
+        self.limit = self.max_jumps.map(|m| self.jumps.saturating_add(m));
+        self.tree = None;
 
-            let solution = self._solve(board, path);
-            if solution.0 {
-                return solution;
+        let mut path = self.scratch.take_path();
+        path.push(index);
+        board.toggle(index);
+        let (success, _) = self._solve(board, &mut path, None, Some(cancel));
+        path.clear();
+        self.scratch.put_path(path);
+
+        success
+    }
+
+    fn solve_from_book(&mut self, board: &Board) -> Option<Solution> {
+        if !self.opening_book
+            || !board.is_empty()
+            || board.is_toroidal()
+            || board.piece() != Piece::Queen
+            || board.blocked_cells().next().is_some()
+        {
+            return None;
+        }
+        let solved = crate::book::construct(board.width()).filter(|b| self.is_goal(b))?;
+        self.book_solutions += 1;
+
+        // the tree of a book solution is its solved root
+        let tree = self.record_tree.then(|| {
+            let mut tree =
+                SearchTree::new(board.width(), self.tree_cap.unwrap_or(DEFAULT_TREE_CAP));
+            let root = tree.push(None, 0, 0);
+            tree.set_result(root, NodeResult::Solved);
+            tree
+        });
+        Some(Solution {
+            path: solved.sorted_queens().collect(),
+            board: solved,
+            success: true,
+            interrupted: false,
+            jumps: self.jumps,
+            tree,
+            stats: self.stats(),
+        })
+    }
+
+    /// Places the first queen of an empty board on the cell of the root move, returning it.
+    pub(crate) fn seed_root(&mut self, board: &mut NormalizedBoard) -> Option<usize> {
+        if !board.is_empty() {
+            return None;
+        }
+
+        let width = board.width();
+        let index = match self.root {
+            RootMove::Cell(index) => index,
+            RootMove::Center => (width / 2) * width + width / 2,
+            RootMove::Best => {
+                let candidates = board.available().collect::<Vec<_>>();
+                let mut best = None;
+                for index in candidates {
+                    let score = self.evaluator.score_candidate(board, index, index);
+                    if best.is_none_or(|(s, _)| score > s) {
+                        best.replace((score, index));
+                    }
+                }
+                best.map_or(0, |(_, index)| index)
             }
-            path.pop();
-            board.toggle(frontier.index);
+            RootMove::Scored => return None,
+        };
+
+        if !board.available().any(|i| i == index) {
+            return None;
         }
+        board.toggle(index);
+        Some(index)
+    }
 
-        for _ in 0..4 {
-            board.rotate_clockwise();
-            self.depleted.insert(board.sorted_queens().collect(), ());
+    pub(crate) fn is_goal(&self, board: &Board) -> bool {
+        self.goal
+            .map_or_else(|| board.is_solved(), |goal| goal(board))
+    }
+
+    /// Places exactly `remaining` queens at indexes from `from`, returning whether the goal was
+    /// found; the board is restored otherwise.
+    fn deepen(&mut self, board: &mut Board, from: usize, remaining: usize) -> bool {
+        if remaining == 0 {
+            return self.is_goal(board);
+        }
+        if self.is_interrupted() {
+            return false;
+        }
+        self.jumps += 1;
+
+        let candidates = board
+            .free_cells()
+            .filter(|i| *i >= from && board.cell(*i).is_some_and(|c| !c.is_blocked()))
+            .collect::<Vec<_>>();
+        for index in candidates {
+            board.toggle_attacked(index);
+            if self.deepen(board, index + 1, remaining - 1) {
+                return true;
+            }
+            board.toggle_attacked(index);
+
+            if self.is_interrupted() {
+                return false;
+            }
+        }
+
+        false
+    }
+
+    /// Places the remaining queens, one per bound but the last, keeping the cheapest goal in
+    /// `best`; the board is restored before returning.
+    fn minimize(
+        &mut self,
+        board: &mut Board,
+        next: Next<'_>,
+        bounds: &[f64],
+        best: &mut Option<(f64, Board)>,
+    ) {
+        let cost = board.queens_cost();
+        if bounds.len() <= 1 {
+            if self.is_goal(board) && best.as_ref().is_none_or(|(b, _)| cost < *b) {
+                best.replace((cost, board.clone()));
+            }
+            return;
         }
 
-        (false, self.jumps)
+        if self.is_interrupted() || best.as_ref().is_some_and(|(b, _)| cost + bounds[0] >= *b) {
+            return;
+        }
+        self.jumps += 1;
+
+        let width = board.width();
+        let mut candidates = match next {
+            Next::Rows(rows) => board.available().filter(|i| i / width == rows[0]).collect(),
+            Next::From(from) => board.available().filter(|i| *i >= from).collect::<Vec<_>>(),
+        };
+        candidates.sort_by(|a, b| board.cost(*a).total_cmp(&board.cost(*b)));
+
+        for index in candidates {
+            let after = match next {
+                Next::Rows(rows) => Next::Rows(&rows[1..]),
+                Next::From(_) => Next::From(index + 1),
+            };
+            board.toggle(index);
+            self.minimize(board, after, &bounds[1..], best);
+            board.toggle(index);
+
+            if self.is_interrupted() {
+                return;
+            }
+        }
+    }
+
+    pub(crate) fn is_interrupted(&self) -> bool {
+        self.limit.is_some_and(|l| self.jumps >= l)
+    }
+
+    /// Whether the search is interrupted by the budget, or by the flag of a cancellable solve.
+    pub(crate) fn is_cancelled(&self, cancel: Option<&AtomicBool>) -> bool {
+        self.is_interrupted() || cancel.is_some_and(|c| c.load(Ordering::Relaxed))
+    }
+
+    fn set_node_result(&mut self, node: Option<usize>, result: NodeResult) {
+        if let Some(tree) = self.tree.as_mut() {
+            tree.set_result(node, result);
+        }
+    }
+
+    /// Searches the board from the seeded root, falling back to the other cells of the root once
+    /// the seed is exhausted. The board is left solved on success, and restored otherwise.
+    fn _solve(
+        &mut self,
+        board: &mut NormalizedBoard,
+        path: &mut Vec<usize>,
+        node: Option<usize>,
+        cancel: Option<&AtomicBool>,
+    ) -> (bool, usize) {
+        // the queen placed on an empty root isn't part of the board to restore
+        let seeded = self.seed_root(board);
+        let mut result = self.search(board, path, node, seeded, cancel);
+        match (seeded, result) {
+            // the winning line starts with the seeded queen
+            (Some(index), true) => path.insert(0, index),
+            (Some(index), false) => {
+                board.toggle(index);
+
+                // the seed is only the first guess of the root: once its subtree is exhausted,
+                // the root is expanded as any other node, and the seed is depleted by now
+                if !self.is_cancelled(cancel) {
+                    result = self.search(board, path, node, None, cancel);
+                }
+            }
+            (None, _) => (),
+        }
+        (result, self.jumps)
+    }
+
+    /// Searches the board depth first, with the queen of `seeded` placed on its root, and an
+    /// explicit stack of the expanded nodes of the path, so the depth is bounded only by the
+    /// heap.
+    fn search(
+        &mut self,
+        board: &mut NormalizedBoard,
+        path: &mut Vec<usize>,
+        node: Option<usize>,
+        seeded: Option<usize>,
+        cancel: Option<&AtomicBool>,
+    ) -> bool {
+        self.reach(board, seeded, path);
+
+        let mut frames = self.scratch.take_frames();
+        let mut returned = match self.enter(board, path, node, cancel) {
+            Entered::Done(result) => Some(result),
+            Entered::Expanded(frame) => {
+                frames.push(frame);
+                None
+            }
+        };
+
+        // the result of the last node is returned to the node of the top of the stack, as the
+        // recursion would
+        let result = loop {
+            let Some(frame) = frames.last_mut() else {
+                break returned.unwrap_or(false);
+            };
+            let depth = frame.depth;
+
+            match returned.take() {
+                Some(true) => {
+                    let frame = frames.pop().expect("the stack isn't empty");
+                    self.scratch.put_frontier(frame.depth, frame.unexplored);
+                    returned = Some(true);
+                    continue;
+                }
+                Some(false) => {
+                    let index = path.pop().expect("the move of the child is on the path");
+                    board.toggle(index);
+                    if self.events.is_set() {
+                        self.events.emit(SolverEvent::Backtracked {
+                            index: board.denormalize_index(index),
+                            jump: self.jumps,
+                            depth,
+                        });
+                    }
+
+                    // don't mark the path as depleted if the search was interrupted
+                    if self.is_cancelled(cancel) {
+                        let frame = frames.pop().expect("the stack isn't empty");
+                        self.scratch.put_frontier(frame.depth, frame.unexplored);
+                        returned = Some(false);
+                        continue;
+                    }
+                }
+                None => (),
+            }
+
+            // A* the path, popping the highest score
+            match frame.unexplored.pop() {
+                Some(frontier) => {
+                    path.push(frontier.index);
+                    board.toggle(frontier.index);
+                    self.reach(board, seeded, path);
+                    match self.enter(board, path, frontier.node, cancel) {
+                        Entered::Done(result) => returned = Some(result),
+                        Entered::Expanded(frame) => frames.push(frame),
+                    }
+                }
+                None => {
+                    let frame = frames.pop().expect("the stack isn't empty");
+                    self.scratch.put_frontier(frame.depth, frame.unexplored);
+                    self.deplete(board);
+                    returned = Some(false);
+                }
+            }
+        };
+
+        self.scratch.put_frames(frames);
+        result
+    }
+
+    /// Replaces the best partial placement if the path is deeper.
+    fn reach(&mut self, board: &NormalizedBoard, seeded: Option<usize>, path: &[usize]) {
+        let depth = seeded.iter().len() + path.len();
+        if depth > self.best_partial.len() {
+            self.best_partial.clear();
+            self.best_partial.extend(
+                seeded
+                    .iter()
+                    .chain(path)
+                    .map(|i| board.denormalize_index(*i)),
+            );
+        }
+    }
+
+    /// Enters a node of the search, either resolving it or expanding its scored candidates.
+    fn enter(
+        &mut self,
+        board: &mut NormalizedBoard,
+        path: &[usize],
+        node: Option<usize>,
+        cancel: Option<&AtomicBool>,
+    ) -> Entered {
+        let depth = path.len();
+        self.events.emit(SolverEvent::NodeEntered {
+            jump: self.jumps,
+            depth,
+        });
+
+        if self.is_goal(board) {
+            self.set_node_result(node, NodeResult::Solved);
+            self.events.emit(SolverEvent::Solved {
+                jump: self.jumps,
+                depth,
+            });
+            return Entered::Done(true);
+        }
+
+        // check if the path is depleted
+        if self.depleted.contains(board) {
+            self.set_node_result(node, NodeResult::Depleted);
+            self.events.emit(SolverEvent::DepletedHit {
+                jump: self.jumps,
+                depth,
+            });
+            return Entered::Done(false);
+        }
+
+        // the budget is depleted, but the path isn't
+        if self.is_cancelled(cancel) {
+            return Entered::Done(false);
+        }
+
+        self.jumps += 1;
+        self.set_node_result(node, NodeResult::Expanded);
+        if let Some(canonical) = self.canonical.as_mut() {
+            canonical.insert(board.canonical_key());
+        }
+
+        // build the unexplored nodes list and score them, reusing the buffers of the depth
+        let last_move = path.last().copied().unwrap_or(0);
+        let mut candidates = self.scratch.take_candidates();
+        candidates.extend(board.available());
+        let mut unexplored = self.scratch.take_frontier(depth);
+        unexplored.extend(candidates.drain(..).map(|index| {
+            let score = self.evaluator.score_candidate(board, index, last_move);
+            let node = node
+                .and(self.tree.as_mut())
+                .and_then(|t| t.push(node, index, score));
+            if self.events.is_set() {
+                self.events.emit(SolverEvent::CandidateScored {
+                    index: board.denormalize_index(index),
+                    score,
+                    jump: self.jumps,
+                    depth,
+                });
+            }
+            Frontier { index, score, node }
+        }));
+        self.scratch.put_candidates(candidates);
+
+        // order the candidates so we can pop the one to explore first
+        self.order(board, &mut unexplored);
+
+        Entered::Expanded(Frame { depth, unexplored })
     }
 }
 
+/// The cells of the next queen of [`Solver::minimize`].
+#[derive(Debug, Clone, Copy)]
+enum Next<'a> {
+    /// A cell of the first of the remaining rows.
+    Rows(&'a [usize]),
+    /// A cell from the index on, for the pieces sharing the rows.
+    From(usize),
+}
+
+/// A node of the search, either resolved on entry or expanded into a frame of the stack.
+enum Entered {
+    Done(bool),
+    Expanded(Frame),
+}
+
+/// The column of the row with the fewest conflicts for its queen, the ties broken at random.
+fn min_conflicts_column<R>(search: &PermutationBoard, row: usize, random: &mut R) -> usize
+where
+    R: FnMut(usize) -> usize,
+{
+    let mut best = (usize::MAX, 0, 0);
+    for column in 0..search.width() {
+        let conflicts = search.conflicts_at(row, column);
+        if conflicts < best.0 {
+            best = (conflicts, column, 1);
+        } else if conflicts == best.0 {
+            // reservoir sampling of the tied columns
+            best.2 += 1;
+            if random(best.2) == 0 {
+                best.1 = column;
+            }
+        }
+    }
+    best.1
+}
+
+/// An expanded node of the current path, with its unexplored candidates.
+#[derive(Debug, Clone)]
+pub(crate) struct Frame {
+    depth: usize,
+    unexplored: Vec<Frontier>,
+}
+
+/// The result of a solve.
+///
+/// With the `serde` feature, the solution is serialized as an object with the fields `board`
+/// (`width` and sorted `queens`, see [`Board`]), `outcome` (see [`Outcome`]), `jumps`, `path`,
+/// and `stats` (`jumps`, `canonical_visits`, `book_solutions`, and `evaluator_misbehaviors`, see
+/// [`SolverStats`]).
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "SolutionRepr", into = "SolutionRepr")
+)]
 pub struct Solution {
     pub board: Board,
     pub success: bool,
+    /// The search was interrupted before the board was either solved or exhausted.
+    pub interrupted: bool,
     pub jumps: usize,
+    /// The explored search tree, if recorded via [`Solver::record_tree`].
+    pub tree: Option<SearchTree>,
+    /// The queens placed by [`Solver::solve`], in the order of the winning line, so toggling
+    /// them on the given board reproduces the solved one; a solution of the opening book places
+    /// its queens in ascending order.
+    ///
+    /// The path is empty on failure, and for the solutions of the other searches.
+    pub path: Vec<usize>,
+    /// The counters of the solver once the search returned, as [`Solver::stats`]; the parallel
+    /// solver counts only the jumps of the search.
+    pub stats: SolverStats,
 }
 
+impl Solution {
+    /// Renders the recorded search tree as a GraphViz digraph; empty if it wasn't recorded.
+    pub fn tree_dot(&self) -> String {
+        self.tree
+            .as_ref()
+            .map(SearchTree::to_dot)
+            .unwrap_or_else(|| String::from("digraph search {\n}\n"))
+    }
+
+    pub const fn outcome(&self) -> Outcome {
+        if self.success {
+            Outcome::Solved
+        } else if self.interrupted {
+            Outcome::Interrupted
+        } else {
+            Outcome::Unsolvable
+        }
+    }
+}
+
+/// The outcome of a solve, serialized as a lowercase string.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Frontier {
-    depleted: bool,
-    index: usize,
-    score: u64,
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Outcome {
+    Solved,
+    /// The search was exhausted without a solution.
+    Unsolvable,
+    /// The search was interrupted before the board was either solved or exhausted.
+    Interrupted,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SolutionRepr {
+    board: Board,
+    outcome: Outcome,
+    jumps: usize,
+    #[serde(default)]
+    path: Vec<usize>,
+    #[serde(default)]
+    stats: SolverStats,
+}
+
+#[cfg(feature = "serde")]
+impl From<Solution> for SolutionRepr {
+    fn from(solution: Solution) -> Self {
+        Self {
+            outcome: solution.outcome(),
+            board: solution.board,
+            jumps: solution.jumps,
+            path: solution.path,
+            stats: solution.stats,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<SolutionRepr> for Solution {
+    fn from(repr: SolutionRepr) -> Self {
+        Self {
+            board: repr.board,
+            success: repr.outcome == Outcome::Solved,
+            interrupted: repr.outcome == Outcome::Interrupted,
+            jumps: repr.jumps,
+            tree: None,
+            path: repr.path,
+            stats: repr.stats,
+        }
+    }
+}
+
+/// Lazy iterator over the solutions of a board, created by [`Solver::solve_all`].
+pub struct Solutions<'a> {
+    solver: &'a mut Solver,
+    board: Board,
+    pending: Option<Board>,
+    candidates: Vec<Vec<usize>>,
+    placed: Vec<usize>,
+}
+
+impl<'a> Solutions<'a> {
+    fn new(solver: &'a mut Solver, board: Board) -> Self {
+        let mut solutions = Self {
+            solver,
+            board,
+            pending: None,
+            candidates: Vec::new(),
+            placed: Vec::new(),
+        };
+        if solutions.board.is_solved() {
+            solutions.pending.replace(solutions.board.clone());
+        } else {
+            solutions.expand();
+        }
+        solutions
+    }
+
+    /// Pushes the safe cells of the first row without a queen, reversed so they are popped in
+    /// ascending order; for the pieces sharing the rows, the safe cells past the last placed
+    /// piece, if they can complete the board.
+    fn expand(&mut self) {
+        let width = self.board.width();
+        if !self.board.piece().attacks(Direction::Horizontal) {
+            let from = self.placed.last().map_or(0, |i| i + 1);
+            let mut candidates = self
+                .board
+                .available()
+                .filter(|i| *i >= from)
+                .collect::<Vec<_>>();
+            let missing = self
+                .board
+                .target_count()
+                .saturating_sub(self.board.sorted_queens().count());
+            if candidates.len() < missing {
+                candidates.clear();
+            }
+            candidates.reverse();
+            self.candidates.push(candidates);
+            return;
+        }
+
+        let candidates = self
+            .board
+            .rows()
+            .enumerate()
+            .find(|(_, row)| !row.iter().any(|c| c.is_queen()))
+            .map(|(r, row)| {
+                row.iter()
+                    .enumerate()
+                    .rev()
+                    .filter_map(|(c, cell)| cell.is_free().then_some(r * width + c))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.candidates.push(candidates);
+    }
+}
+
+impl Iterator for Solutions<'_> {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        if let Some(board) = self.pending.take() {
+            return Some(board);
+        }
+
+        while !self.candidates.is_empty() {
+            // backtrack the placement of the current row, if any
+            if self.placed.len() == self.candidates.len() {
+                if let Some(index) = self.placed.pop() {
+                    self.board.toggle(index);
+                }
+            }
+
+            let Some(index) = self.candidates.last_mut().and_then(Vec::pop) else {
+                self.candidates.pop();
+                continue;
+            };
+
+            self.board.toggle(index);
+            self.placed.push(index);
+            if self.board.is_solved() {
+                return Some(self.board.clone());
+            }
+
+            if self.solver.is_interrupted() {
+                self.candidates.clear();
+                return None;
+            }
+
+            self.solver.jumps += 1;
+            self.expand();
+        }
+
+        None
+    }
+}
+
+#[test]
+fn mark_depleted_prunes_the_search() {
+    let expected = Solver::default().solve(Board::new(8));
+    assert!(expected.success);
+
+    // without evaluators, the first move explored from the root is the last available cell
+    let solution = Solver::default()
+        .mark_depleted(8, [0, 62])
+        .solve(Board::new(8));
+    assert!(solution.success);
+    assert_eq!(solution.board, expected.board);
+    assert!(solution.jumps < expected.jumps);
+
+    let mut solver = Solver::default();
+    let board = Board::from_queens(8, [0, 62]).unwrap();
+    solver.mark_depleted(8, [0, 62]);
+    assert!(solver.is_depleted(&board));
+    assert!(solver.is_depleted(&board.rotated(1)));
+    assert!(!solver.is_depleted(&Board::new(8)));
+
+    // the keys of a width don't collide with the keys of another
+    let solution = Solver::default().mark_depleted(7, [0]).solve(Board::new(8));
+    assert_eq!(solution.board, expected.board);
+    assert_eq!(solution.jumps, expected.jumps);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn event_sink_works() {
+    use std::sync::{Arc, Mutex};
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let solution = Solver::default()
+        .set_event_sink(move |e| sink.lock().unwrap().push(e))
+        .solve(Board::new(5));
+    assert!(solution.success);
+
+    let events = events.lock().unwrap();
+    assert!(matches!(
+        events.last(),
+        Some(SolverEvent::Solved { depth: 4, .. })
+    ));
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| matches!(e, SolverEvent::Solved { .. }))
+            .count(),
+        1
+    );
+
+    // the depths of the entered nodes never jump by more than one
+    let depths: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            SolverEvent::NodeEntered { depth, .. } => Some(*depth),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(depths.first(), Some(&0));
+    assert!(depths.windows(2).all(|w| w[1] <= w[0] + 1));
+
+    // the sampling only drops scored candidates
+    let sampled = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&sampled);
+    Solver::default()
+        .with_event_sampling(4)
+        .set_event_sink(move |e| sink.lock().unwrap().push(e))
+        .solve(Board::new(5));
+    let sampled = sampled.lock().unwrap();
+    let scored = |events: &[SolverEvent]| {
+        events
+            .iter()
+            .filter(|e| matches!(e, SolverEvent::CandidateScored { .. }))
+            .count()
+    };
+    assert_eq!(scored(&sampled), scored(&events).div_ceil(4));
+    assert_eq!(
+        sampled.len() - scored(&sampled),
+        events.len() - scored(&events)
+    );
+}
+
+#[test]
+fn record_tree_works() {
+    let solution = Solver::default().record_tree(true).solve(Board::new(5));
+    assert!(solution.success);
+
+    let tree = solution.tree.as_ref().expect("the tree is recorded");
+    assert!(!tree.is_truncated());
+
+    let solved: Vec<_> = tree
+        .nodes()
+        .iter()
+        .filter(|n| n.result == NodeResult::Solved)
+        .collect();
+    assert_eq!(solved.len(), 1);
+    assert!(solution.board.is_queen(solved[0].index));
+
+    // every node but the root has an edge to its parent
+    let dot = solution.tree_dot();
+    assert!(dot.starts_with("digraph search {"));
+    let edges = dot.lines().filter(|l| l.contains("->")).count();
+    let nodes = dot.lines().filter(|l| l.contains("[label=")).count();
+    assert_eq!(nodes, tree.nodes().len());
+    assert_eq!(edges, nodes - 1);
+    assert_eq!(dot.matches("fillcolor=green").count(), 1);
+
+    // the tree is truncated once the cap is reached
+    let solution = Solver::default()
+        .record_tree(true)
+        .with_tree_cap(10)
+        .solve(Board::new(5));
+    let tree = solution.tree.as_ref().expect("the tree is recorded");
+    assert!(tree.is_truncated());
+    assert_eq!(tree.nodes().len(), 10);
+    assert_eq!(tree.memory(), 10 * core::mem::size_of::<crate::TreeNode>());
+    assert!(solution.tree_dot().contains("truncated at 10 nodes"));
+
+    // the indexes are relative to the given board, even if it was rotated by the normalization
+    let board = Board::from_queens(5, [4]).unwrap();
+    let solution = Solver::default().record_tree(true).solve(board);
+    assert!(solution.success);
+    let tree = solution.tree.as_ref().expect("the tree is recorded");
+    let mut node = tree.nodes().iter().find(|n| n.result == NodeResult::Solved);
+    while let Some(n) = node.filter(|n| n.parent.is_some()) {
+        assert!(solution.board.is_queen(n.index));
+        node = n.parent.map(|p| &tree.nodes()[p]);
+    }
+
+    // the search is the same, regardless of the recording
+    let solution = Solver::default()
+        .record_tree(true)
+        .with_tree_cap(10)
+        .solve(Board::new(5));
+    let plain = Solver::default().solve(Board::new(5));
+    assert_eq!(plain.board, solution.board);
+    assert_eq!(plain.jumps, solution.jumps);
+    assert!(plain.tree.is_none());
+}
+
+#[test]
+fn solve_permutation_works() {
+    let permutation = Solver::default()
+        .solve_permutation(8)
+        .expect("width 8 is solvable");
+
+    let mut columns = permutation.clone();
+    columns.sort_unstable();
+    assert_eq!(columns, (0..8).collect::<Vec<_>>());
+
+    // no two queens share a diagonal
+    for (r1, c1) in permutation.iter().enumerate() {
+        for (r2, c2) in permutation.iter().enumerate().skip(r1 + 1) {
+            assert_ne!(r2 - r1, c1.abs_diff(*c2));
+        }
+    }
+
+    assert_eq!(Solver::default().solve_permutation(3), None);
+}
+
+#[cfg(all(test, feature = "std"))]
+proptest::proptest! {
+    #![proptest_config(proptest::prelude::ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn scratch_reuse_keeps_the_behavior(
+        board in crate::test_support::partial_board(4..=8, 0..=3),
+        config in crate::test_support::evaluator_config(),
+    ) {
+        let expected = config.solver().solve(board.clone());
+
+        // the depleted boards are keyed by width, so only the scratch is shared with the other
+        // widths
+        let mut solver = config.solver();
+        solver.solve(Board::new(board.width() + 2));
+        solver.solve(Board::new(board.width() - 1));
+        let before = solver.jumps();
+        let solution = solver.solve(board);
+
+        proptest::prop_assert_eq!(solution.board, expected.board);
+        proptest::prop_assert_eq!(solver.jumps() - before, expected.jumps);
+    }
+
+    #[test]
+    fn mark_depleted_covers_the_rotations(
+        board in crate::test_support::partial_board(1..=9, 1..=4),
+    ) {
+        let mut solver = Solver::default();
+        solver.mark_depleted(board.width(), board.sorted_queens());
+        for turns in 0..4 {
+            proptest::prop_assert!(solver.is_depleted(&board.rotated(turns)));
+        }
+
+        let mut partial = board.clone();
+        let first = board.sorted_queens().next().expect("the board has a queen");
+        partial.toggle(first);
+        proptest::prop_assert!(!solver.is_depleted(&partial));
+    }
+}
+
+#[test]
+fn solve_min_cost_works() {
+    // the width 4 has two solutions, and the costs pick one of them
+    let mut costs = vec![0.0; 16];
+    costs[1] = 10.0;
+    let solution = Solver::default().solve_min_cost(Board::with_costs(4, costs.clone()));
+    assert!(solution.success);
+    assert_eq!(
+        solution.board.sorted_queens().collect::<Vec<_>>(),
+        vec![2, 4, 11, 13]
+    );
+
+    costs[1] = 0.0;
+    costs[2] = 10.0;
+    let solution = Solver::default().solve_min_cost(Board::with_costs(4, costs));
+    assert_eq!(
+        solution.board.sorted_queens().collect::<Vec<_>>(),
+        vec![1, 7, 8, 14]
+    );
+    assert_eq!(solution.board.queens_cost(), 0.0);
+
+    // the minimum of a wider board, with the cost growing with the column
+    let costs = (0..64).map(|i| (i % 8) as f64).collect();
+    let solution = Solver::default().solve_min_cost(Board::with_costs(8, costs));
+    assert!(solution.board.is_solved());
+    assert_eq!(solution.board.queens_cost(), 28.0);
+
+    let solution = Solver::default().solve_min_cost(Board::new(3));
+    assert_eq!(solution.outcome(), Outcome::Unsolvable);
+
+    // the bishops share the rows, and the minimum is the cheapest of their solutions
+    let costs = (0..25).map(|i| (i * 7 % 11) as f64).collect::<Vec<_>>();
+    let bishops = Board::from_parts(reginae_core::BoardParts {
+        costs,
+        ..Board::with_piece(5, Piece::Bishop).into_parts()
+    })
+    .unwrap();
+    let cheapest = Solver::default()
+        .solve_all(bishops.clone())
+        .map(|b| b.queens_cost())
+        .fold(f64::INFINITY, f64::min);
+    let solution = Solver::default().solve_min_cost(bishops);
+    assert!(solution.success && solution.board.is_solved());
+    assert_eq!(solution.board.sorted_queens().count(), 8);
+    assert_eq!(solution.board.queens_cost(), cheapest);
+}
+
+#[test]
+fn solve_min_queens_works() {
+    // the domination number of the width 8 is 5
+    let mut solver = Solver::default();
+    let solution = solver
+        .with_goal(|b| b.safe_cells().next().is_none())
+        .solve_min_queens(8);
+    assert!(solution.success);
+    assert_eq!(solution.board.sorted_queens().count(), 5);
+    assert!(solution.board.safe_cells().next().is_none());
+    assert!(solution.board.validate_invariants().is_ok());
+
+    // the 2 queens dominating the width 4 attack each other, as no independent pair does
+    let solution = Solver::default()
+        .with_goal(|b| b.safe_cells().next().is_none())
+        .solve_min_queens(4);
+    assert!(solution.success);
+    assert_eq!(solution.board.sorted_queens().count(), 2);
+    assert!(!solution.board.is_valid());
+
+    // the default goal is a regular solution
+    let solution = Solver::default().solve_min_queens(5);
+    assert!(solution.success);
+    assert!(solution.board.is_solved());
+
+    let solution = Solver::default().solve_min_queens(3);
+    assert_eq!(solution.outcome(), Outcome::Unsolvable);
+
+    let solution = Solver::default().with_max_jumps(10).solve_min_queens(8);
+    assert_eq!(solution.outcome(), Outcome::Interrupted);
+}
+
+#[test]
+fn solve_all_limited_works() {
+    let all: Vec<_> = Solver::default().solve_all(Board::new(8)).collect();
+    assert_eq!(all.len(), 92);
+    assert!(all.iter().all(Board::is_solved));
+    let mut placements = all
+        .iter()
+        .map(|b| b.sorted_queens().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    placements.dedup();
+    assert_eq!(placements.len(), 92);
+
+    // the solutions are distinct placements, not classes of symmetries
+    let small: Vec<_> = Solver::default().solve_all(Board::new(5)).collect();
+    assert_eq!(small.len(), 10);
+    assert!(small
+        .iter()
+        .all(|b| b.is_solved() && b.validate_invariants().is_ok()));
+
+    let mut solver = Solver::default();
+    let limited = solver.solve_all_limited(Board::new(8), 3);
+    assert_eq!(limited, all[..3]);
+
+    // the enumeration stops as soon as the solutions are found
+    let mut exhaustive = Solver::default();
+    exhaustive.solve_all(Board::new(8)).for_each(drop);
+    assert!(solver.jumps() < exhaustive.jumps());
+
+    // a board with queens only enumerates its completions
+    let board = Board::from_queens(8, [0]).unwrap();
+    let completions = Solver::default().solve_all(board).count();
+    assert_eq!(completions, all.iter().filter(|b| b.is_queen(0)).count());
+
+    // the bishops share the rows, and the width n has 2^n solutions
+    let bishops: Vec<_> = Solver::default()
+        .solve_all(Board::with_piece(5, Piece::Bishop))
+        .collect();
+    assert_eq!(bishops.len(), 32);
+    assert!(bishops
+        .iter()
+        .all(|b| b.is_solved() && b.sorted_queens().count() == 8));
+    assert!(bishops
+        .windows(2)
+        .all(|w| w[0].sorted_queens().lt(w[1].sorted_queens())));
+    let limited = Solver::default().solve_all_limited(Board::with_piece(5, Piece::Bishop), 3);
+    assert_eq!(limited, bishops[..3]);
+}
+
+#[test]
+fn count_fundamental_works() {
+    let mut solver = Solver::default();
+    for (width, fundamental) in [(1, 1), (4, 1), (5, 2), (6, 1), (8, 12)] {
+        assert_eq!(solver.count_fundamental(width), fundamental);
+    }
+
+    // the mirrored solutions are counted once, unlike the rotations alone
+    let all: Vec<_> = solver.solve_all(Board::new(8)).collect();
+    let rotations = all
+        .iter()
+        .filter(|b| (1..4).all(|t| b.rotated(t).sorted_queens().ge(b.sorted_queens())))
+        .count();
+    assert!(rotations > solver.count_fundamental(8));
+
+    // the bishops are counted by their canonical keys as well
+    let keys = solver
+        .solve_all(Board::with_piece(4, Piece::Bishop))
+        .map(|b| b.canonical_key())
+        .collect::<BTreeSet<_>>();
+    assert_eq!(keys.len(), 3);
+    assert_eq!(solver.count_fundamental_pieces(4, Piece::Bishop), keys.len());
+    assert_eq!(
+        solver.count_fundamental_pieces(8, Piece::Queen),
+        solver.count_fundamental(8)
+    );
+}
+
+#[test]
+fn failed_solve_restores_the_board() {
+    // the queen placed on the empty root isn't left on the board
+    for width in [2, 3] {
+        let solution = Solver::default().solve(Board::new(width));
+        assert_eq!(solution.outcome(), Outcome::Unsolvable);
+        assert_eq!(solution.board, Board::new(width));
+
+        // the depleted root of a second solve is restored as well
+        let mut solver = Solver::default();
+        solver.solve(Board::new(width));
+        assert_eq!(solver.solve(Board::new(width)).board, Board::new(width));
+    }
+
+    // the corners of width 4 have no completion, and the boards are normalized by rotation
+    for corner in [0, 3, 12, 15] {
+        let board = Board::from_queens(4, [corner]).unwrap();
+        let solution = Solver::default().solve(board.clone());
+        assert_eq!(solution.outcome(), Outcome::Unsolvable);
+        assert_eq!(solution.board, board);
+    }
+
+    // an interrupted search is unwound
+    let board = Board::from_queens(10, [14]).unwrap();
+    let solution = Solver::default().with_max_jumps(5).solve(board.clone());
+    assert_eq!(solution.outcome(), Outcome::Interrupted);
+    assert_eq!(solution.board, board);
+
+    let solution = Solver::default().with_max_jumps(5).solve(Board::new(10));
+    assert_eq!(solution.outcome(), Outcome::Interrupted);
+    assert_eq!(solution.board, Board::new(10));
+}
+
+#[test]
+fn canonical_visits_are_tracked() {
+    let mut solver = Solver::default();
+    solver.solve(Board::new(8));
+    assert_eq!(solver.stats().canonical_visits, None);
+
+    let mut solver = Solver::default();
+    solver
+        .with_evaluator(reginae_evaluators::overlapping, 10.0)
+        .track_canonical_visits(true);
+    assert!(solver.solve(Board::new(8)).success);
+    let stats = solver.stats();
+    assert_eq!(stats.jumps, solver.jumps());
+    let visits = stats.canonical_visits.expect("the visits are tracked");
+    assert!(visits > 0 && visits <= stats.jumps);
+
+    // the visits accumulate between solves
+    solver.solve(Board::new(6));
+    let stats = solver.stats();
+    assert!(stats
+        .canonical_visits
+        .is_some_and(|v| v > visits && v <= stats.jumps));
+    assert_eq!(
+        solver
+            .track_canonical_visits(false)
+            .stats()
+            .canonical_visits,
+        None
+    );
+}
+
+#[test]
+fn root_hint_works() {
+    // the hinted cell is kept as the first queen
+    let solution = Solver::default()
+        .with_root_hint(Some(27))
+        .solve(Board::new(8));
+    assert!(solution.success);
+    assert!(solution.board.is_queen(27));
+
+    // the corner has no completion on the widths 4 and 6, so the default hint falls back to
+    // the other cells of the root
+    for width in [4, 6] {
+        let solution = Solver::default().solve(Board::new(width));
+        assert!(solution.success);
+        assert!(!solution.board.is_queen(0));
+
+        let solution = Solver::default()
+            .with_root_hint(None)
+            .solve(Board::new(width));
+        assert!(solution.success);
+        assert!(!solution.board.is_queen(0));
+    }
+
+    // a hint out of the board is expanded as any other node, and a board with queens ignores it
+    let solution = Solver::default()
+        .with_root_hint(Some(64))
+        .solve(Board::new(8));
+    assert!(solution.success);
+    let board = Board::from_queens(8, [1]).unwrap();
+    let solution = Solver::default().with_root_hint(Some(27)).solve(board);
+    assert!(solution.success && solution.board.is_queen(1) && !solution.board.is_queen(27));
+
+    // the parallel solver seeds the root as well
+    #[cfg(feature = "std")]
+    {
+        let mut solver = Solver::default();
+        solver.with_root_hint(None);
+        let solution = crate::ParallelSolver::new(solver).solve(Board::new(6));
+        assert!(solution.success);
+    }
+}
+
+#[test]
+fn first_move_strategy_works() {
+    let solve = |strategy| {
+        let mut solver = Solver::default();
+        solver
+            .with_evaluator(reginae_evaluators::overlapping, 10.0)
+            .with_first_move_strategy(strategy);
+        let solution = solver.solve(Board::new(9));
+        assert!(solution.success);
+        solution
+    };
+
+    let corner = solve(FirstMove::Corner);
+    let center = solve(FirstMove::Center);
+    let best = solve(FirstMove::EvaluatorBest);
+    assert!(corner.board.is_queen(0));
+    assert!(center.board.is_queen(40));
+
+    // the root alone changes the jumps of the width severalfold
+    assert!(best.jumps < center.jumps && center.jumps < corner.jumps);
+
+    // the corner is the default
+    assert_eq!(Solver::default().solve(Board::new(9)).jumps, {
+        let mut solver = Solver::default();
+        solver.with_first_move_strategy(FirstMove::Corner);
+        solver.solve(Board::new(9)).jumps
+    });
+
+    // without evaluators, all the cells tie and the best is the first
+    let mut solver = Solver::default();
+    solver.with_first_move_strategy(FirstMove::EvaluatorBest);
+    assert!(solver.solve(Board::new(5)).board.is_queen(0));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hypothetical_scoring_keeps_the_search() {
+    use crate::test_support::BUILTINS;
+
+    let mut toggled = Solver::default();
+    let mut hypothetical = Solver::default();
+    hypothetical.score_hypothetically(true);
+    for builtin in BUILTINS {
+        toggled.with_evaluator(builtin.evaluator, 1.0);
+        hypothetical.with_hypothetical_evaluator(builtin.evaluator, builtin.hypothetical, 1.0);
+    }
+
+    for width in [5, 8, 9, 12] {
+        assert_eq!(
+            hypothetical.clone().solve(Board::new(width)),
+            toggled.clone().solve(Board::new(width))
+        );
+    }
+
+    // a single evaluator without the hypothetical form falls back to the toggles
+    hypothetical.with_evaluator(reginae_evaluators::ladder, 1.0);
+    toggled.with_evaluator(reginae_evaluators::ladder, 1.0);
+    assert_eq!(
+        hypothetical.solve(Board::new(8)),
+        toggled.solve(Board::new(8))
+    );
+}
+
+#[test]
+fn degenerate_widths_work() {
+    let solution = Solver::default().solve(Board::new(0));
+    assert!(solution.success && solution.board.is_empty());
+    let solution = Solver::default().solve(Board::new(1));
+    assert!(solution.success && solution.board.is_queen(0));
+    let solution = Solver::default().solve(Board::new(2));
+    assert!(!solution.success && solution.board.is_empty());
+
+    // the root of the empty widths is scored as any other node
+    let mut solver = Solver::default();
+    solver.with_root_hint(None);
+    for (width, success) in [(0, true), (1, true), (2, false)] {
+        assert_eq!(solver.solve(Board::new(width)).success, success);
+        assert_eq!(solver.solve_permutation(width).is_some(), success);
+    }
+}
+
+#[test]
+fn opening_book_works() {
+    let mut solver = Solver::default();
+    solver.use_opening_book(true);
+    for width in [4, 6, 8, 9, 14, 15, 40] {
+        let solution = solver.solve(Board::new(width));
+        assert!(solution.success && solution.board.is_solved());
+        assert_eq!(solution.board, crate::book::construct(width).unwrap());
+    }
+    assert_eq!(solver.jumps(), 0);
+    assert_eq!(solver.stats().book_solutions, 7);
+
+    // the unsolvable widths are searched
+    let solution = solver.solve(Board::new(3));
+    assert_eq!(solution.outcome(), Outcome::Unsolvable);
+    assert!(solution.jumps > 0);
+
+    // the book is ignored for the boards with queens
+    let board = Board::from_queens(8, [1]).unwrap();
+    let solution = solver.solve(board.clone());
+    assert!(solution.success && solution.board.is_queen(1));
+    assert_eq!(solution.board, Solver::default().solve(board).board);
+    assert_eq!(solver.stats().book_solutions, 7);
+
+    // and when the book solution doesn't satisfy the goal
+    let solution = Solver::default()
+        .use_opening_book(true)
+        .with_goal(|b| b.is_solved() && !b.is_queen(1))
+        .solve(Board::new(8));
+    assert!(solution.success && !solution.board.is_queen(1));
+    assert!(solution.jumps > 0);
+}
+
+#[test]
+fn holes_are_solved() {
+    // the cells of the book solution are blocked, so the search must find another one
+    let book = crate::book::construct(8).unwrap();
+    let mut board = Board::new(8);
+    book.sorted_queens().for_each(|q| {
+        board.block(q);
+    });
+    let mut solver = Solver::default();
+    solver.use_opening_book(true);
+    let solution = solver.solve(board.clone());
+    assert!(solution.success && solution.board.is_solved());
+    assert!(book
+        .sorted_queens()
+        .all(|q| solution.board.cell(q).unwrap().is_blocked()));
+    assert_eq!(solution.board.blocked_cells().count(), 8);
+    assert_eq!(solver.stats().book_solutions, 0);
+
+    // no completion of these blocks holds the corner, so the root falls back to the other cells
+    let mut board = Board::new(8);
+    [12, 13, 14].into_iter().for_each(|b| {
+        board.block(b);
+    });
+    let mut cornered = board.clone();
+    cornered.toggle(0);
+    assert!(!Solver::default().solve(cornered).success);
+    let solution = Solver::default().solve(board.clone());
+    assert!(solution.success && solution.board.is_solved());
+    assert!(!solution.board.is_queen(0));
+    assert_eq!(solution.board.blocked_cells().count(), 3);
+    #[cfg(feature = "std")]
+    {
+        let solution = crate::ParallelSolver::new(Solver::default()).solve(board);
+        assert!(solution.success && solution.board.is_solved());
+    }
+
+    // the blocks cutting the rays shield the corners from each other
+    let mut board = Board::with_cut_rays(3);
+    [1, 3, 4, 5, 7].into_iter().for_each(|b| {
+        board.block(b);
+    });
+    let solution = Solver::default()
+        .with_goal(|b| b.sorted_queens().count() == 4)
+        .solve(board);
+    assert!(solution.success && solution.board.is_valid());
+    assert_eq!(
+        solution.board.sorted_queens().collect::<Vec<_>>(),
+        [0, 2, 6, 8]
+    );
+}
+
+#[test]
+fn toroidal_boards_are_solved() {
+    // the torus has solutions for the widths coprime with 6, so the book is skipped
+    let mut solver = Solver::default();
+    solver.use_opening_book(true);
+    for width in [1, 5, 7, 11] {
+        let solution = solver.solve(Board::new_toroidal(width));
+        assert!(solution.success && solution.board.is_solved(), "{width}");
+        assert!(solution.board.is_toroidal());
+    }
+    assert_eq!(solver.stats().book_solutions, 0);
+
+    // the other widths are exhausted, though the plain boards are solved
+    for width in [2, 4, 6, 8] {
+        let solution = solver.solve(Board::new_toroidal(width));
+        assert_eq!(solution.outcome(), Outcome::Unsolvable, "{width}");
+    }
+    assert!(solver.solve(Board::new(8)).success);
+}
+
+#[test]
+fn amazons_are_solved() {
+    // the amazons have no solution below the width 10, so the book is skipped; none of the
+    // solutions of the width 10 holds a corner, so an empty root exhausts the seeded corner
+    // first, which the rows of a solution skip
+    let mut solver = Solver::default();
+    solver.use_opening_book(true);
+    let mut root = Board::with_piece(10, Piece::Amazon);
+    root.toggle(2).toggle(15);
+    let solution = solver.solve(root);
+    assert!(solution.success && solution.board.is_solved());
+    assert_eq!(solution.board.knight_conflicts().count(), 0);
+    assert!(solution.board.is_queen(2) && solution.board.is_queen(15));
+    assert_eq!(solver.stats().book_solutions, 0);
+
+    // the search below a corner and a cell out of its reach exhausts every completion
+    let mut root = Board::with_piece(8, Piece::Amazon);
+    root.toggle(0).toggle(11);
+    assert_eq!(root.sorted_queens().count(), 2);
+    let solution = solver.solve(root);
+    assert_eq!(solution.outcome(), Outcome::Unsolvable);
+}
+
+#[test]
+fn rooks_and_bishops_are_solved() {
+    // any permutation places the rooks, so the search is a smoke test of the solver
+    let mut solver = Solver::default();
+    solver.use_opening_book(true);
+    let solution = solver.solve(Board::with_piece(8, Piece::Rook));
+    assert!(solution.success && solution.board.is_solved());
+    assert_eq!(solution.board.sorted_queens().count(), 8);
+    assert_eq!(solution.board.validate_invariants(), Ok(()));
+    assert_eq!(solver.stats().book_solutions, 0);
+
+    // the bishops of a solution share the rows
+    let solution = solver.solve(Board::with_piece(6, Piece::Bishop));
+    assert!(solution.success && solution.board.is_solved());
+    assert_eq!(solution.board.sorted_queens().count(), 10);
+    assert_eq!(solution.board.validate_invariants(), Ok(()));
+    assert_eq!(solution.path.len(), 10);
+}
+
+#[test]
+fn best_partial_grows_across_slices() {
+    // a corner queen of the last column is rotated by the normalization
+    let board = Board::from_queens(10, [9]).unwrap();
+
+    let mut solver = Solver::default();
+    solver.with_max_jumps(20);
+    let mut lengths = vec![];
+    let solution = loop {
+        let solution = solver.solve(board.clone());
+        lengths.push(solver.best_partial().len());
+
+        // the placement is consistent with the queens of the board
+        let mut partial = board.clone();
+        for q in solver.best_partial() {
+            assert_eq!(partial.toggle_reporting(*q), crate::ToggleResult::Placed);
+        }
+        if !solution.interrupted {
+            break solution;
+        }
+    };
+
+    assert!(solution.success && lengths.len() > 1);
+    assert!(lengths.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(lengths.last(), Some(&9));
+
+    let mut partial = board.clone();
+    solver.best_partial().iter().for_each(|q| {
+        partial.toggle(*q);
+    });
+    assert_eq!(partial, solution.board);
+
+    // another board starts a new placement
+    solver.solve(Board::new(5));
+    assert!(solver.best_partial().len() <= 5);
+}
+
+#[test]
+fn enumerate_from_works() {
+    let all = Solver::default()
+        .enumerate_from(Board::new(6))
+        .map(|b| b.to_permutation().expect("a solution is a permutation"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        all,
+        [
+            [1, 3, 5, 0, 2, 4],
+            [2, 5, 1, 4, 0, 3],
+            [3, 0, 4, 1, 5, 2],
+            [4, 2, 0, 5, 3, 1],
+        ]
+    );
+
+    // a fixed first queen only enumerates its completions, regardless of the budget
+    let mut solver = Solver::default();
+    solver.with_max_jumps(1);
+    for column in 0..6 {
+        let board = Board::from_queens(6, [column]).unwrap();
+        let completions = solver
+            .enumerate_from(board)
+            .map(|b| b.to_permutation().expect("a solution is a permutation"))
+            .collect::<Vec<_>>();
+        let expected = all.iter().filter(|p| p[0] == column).collect::<Vec<_>>();
+        assert_eq!(completions.iter().collect::<Vec<_>>(), expected);
+    }
+
+    let board = Board::from_queens(6, [2]).unwrap();
+    assert_eq!(
+        solver.enumerate_from(board).collect::<Vec<_>>(),
+        [Board::from_permutation(&[2, 5, 1, 4, 0, 3]).unwrap()]
+    );
+
+    // the fixed bishop of a corner is in half of the solutions of the width
+    let mut board = Board::with_piece(4, Piece::Bishop);
+    board.toggle(0);
+    let completions = solver.enumerate_from(board).collect::<Vec<_>>();
+    assert_eq!(completions.len(), 8);
+    assert!(completions.iter().all(|b| b.is_solved() && b.is_queen(0)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn solution_serde_round_trip() {
+    let solved = Solver::default().solve(Board::new(8));
+    assert_eq!(solved.outcome(), Outcome::Solved);
+
+    let json = serde_json::to_string(&solved).unwrap();
+    assert_eq!(
+        json,
+        format!(
+            r#"{{"board":{{"width":8,"queens":[0,13,23,26,38,43,49,60]}},"outcome":"solved","jumps":{0},"path":[0,60,49,43,38,26,23,13],"stats":{{"jumps":{0},"canonical_visits":null,"book_solutions":0,"evaluator_misbehaviors":[]}}}}"#,
+            solved.jumps
+        )
+    );
+    assert_eq!(serde_json::from_str::<Solution>(&json).unwrap(), solved);
+
+    // a payload without the path decodes with an empty one
+    let json =
+        r#"{"board":{"width":8,"queens":[0,13,23,26,38,43,49,60]},"outcome":"solved","jumps":3}"#;
+    let solution = serde_json::from_str::<Solution>(json).unwrap();
+    assert_eq!(solution.board, solved.board);
+    assert!(solution.path.is_empty());
+    assert_eq!(solution.stats, SolverStats::default());
+
+    let interrupted = Solver::default().with_max_jumps(1).solve(Board::new(20));
+    assert_eq!(interrupted.outcome(), Outcome::Interrupted);
+
+    let json = serde_json::to_string(&interrupted).unwrap();
+    assert!(json.contains(r#""outcome":"interrupted""#));
+    assert_eq!(
+        serde_json::from_str::<Solution>(&json).unwrap(),
+        interrupted
+    );
+
+    // the events are tagged by their snake case name
+    let event = SolverEvent::NodeEntered { jump: 3, depth: 1 };
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(json, r#"{"event":"node_entered","jump":3,"depth":1}"#);
+    assert_eq!(serde_json::from_str::<SolverEvent>(&json).unwrap(), event);
+
+    // the cells are serialized as their content word, rejecting unknown bits
+    assert_eq!(
+        serde_json::to_string(&crate::Cell::from_bits(3)).unwrap(),
+        "3"
+    );
+    assert_eq!(
+        serde_json::from_str::<crate::Cell>("3").unwrap(),
+        crate::Cell::from_bits(3)
+    );
+    assert!(serde_json::from_str::<crate::Cell>("4294967295").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn solution_json_shape_is_stable() {
+    use serde_json::Value;
+
+    // the documented fields, which the consumers of the JSON output rely on
+    let keys = |value: &Value| {
+        let mut keys = value
+            .as_object()
+            .expect("the value is an object")
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    };
+    for (solution, outcome) in [
+        (Solver::default().solve(Board::new(6)), "solved"),
+        (Solver::default().solve(Board::new(3)), "unsolvable"),
+        (
+            Solver::default().with_max_jumps(1).solve(Board::new(20)),
+            "interrupted",
+        ),
+    ] {
+        let value = serde_json::to_value(&solution).unwrap();
+        assert_eq!(keys(&value), ["board", "jumps", "outcome", "path", "stats"]);
+        assert_eq!(keys(&value["board"]), ["queens", "width"]);
+        assert_eq!(
+            keys(&value["stats"]),
+            [
+                "book_solutions",
+                "canonical_visits",
+                "evaluator_misbehaviors",
+                "jumps"
+            ]
+        );
+        assert_eq!(value["outcome"], outcome);
+        assert!(value["jumps"].is_u64());
+        assert!(value["path"].is_array());
+        assert!(value["board"]["width"].is_u64());
+        assert!(value["board"]["queens"].is_array());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn solve_cancellable_works() {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    // the goal is never reached, so only the flag stops the search
+    let mut solver = Solver::default();
+    solver.with_goal(|_| false);
+    let cancel = AtomicBool::new(false);
+    let start = Instant::now();
+    let solution = thread::scope(|s| {
+        s.spawn(|| {
+            thread::sleep(Duration::from_millis(50));
+            cancel.store(true, Ordering::Relaxed);
+        });
+        solver.solve_cancellable(Board::new(25), &cancel)
+    });
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(solution.outcome(), Outcome::Interrupted);
+
+    // the board holds the deepest placement instead of the empty root
+    assert!(!solver.best_partial().is_empty());
+    assert_eq!(
+        solution.board.sorted_queens().count(),
+        solver.best_partial().len()
+    );
+    assert!(solver.best_partial().iter().all(|q| solution.board.is_queen(*q)));
+    assert_eq!(solution.board.validate_invariants(), Ok(()));
+
+    // the flag isn't kept by the next solves
+    let solution = solver.with_goal(Board::is_solved).solve(Board::new(8));
+    assert!(solution.success);
+}
+
+#[test]
+fn fixed_point_quantization_solves() {
+    let solve = || {
+        let mut solver = Solver::default();
+        solver
+            .with_evaluator(reginae_evaluators::overlapping, 10.0)
+            .with_evaluator(reginae_evaluators::ladder, 5.0)
+            .with_quantization(Quantization::FixedPoint);
+        solver.solve(Board::new(10))
+    };
+
+    let solution = solve();
+    assert!(solution.success);
+    assert_eq!(solution, solve());
+}
+
+// the threads of `std` bound the stack
+#[cfg(feature = "std")]
+#[test]
+fn deep_solve_doesnt_grow_the_stack() {
+    // a solution of the even widths that aren't 2 modulo 6, without the queens of its last
+    // half, so every row of the half is a level of the search
+    let width = 120;
+    let permutation = (0..width)
+        .map(|row| match row < width / 2 {
+            true => 2 * row + 1,
+            false => 2 * (row - width / 2),
+        })
+        .collect::<Vec<_>>();
+    let mut board = Board::from_permutation(&permutation).expect("the permutation is a solution");
+    let queens = board.sorted_queens().collect::<Vec<_>>();
+    queens[width / 2..].iter().for_each(|q| {
+        board.toggle(*q);
+    });
+
+    // the stack would be exhausted by a frame per level
+    let solution = std::thread::Builder::new()
+        .stack_size(32 * 1024)
+        .spawn(move || Solver::default().solve(board))
+        .expect("the thread is spawned")
+        .join()
+        .expect("the stack isn't exhausted");
+    assert!(solution.success);
+    assert!(solution.board.is_solved());
+    assert_eq!(solution.jumps, width / 2);
+}
+
+#[test]
+fn solution_path_replays() {
+    // an empty root, where the search seeds its first queen
+    let mut solver = Solver::default();
+    let solution = solver.solve(Board::new(8));
+    assert!(solution.success);
+    assert_eq!(solution.path.len(), 8);
+    let mut board = Board::new(8);
+    for index in &solution.path {
+        board.toggle(*index);
+    }
+    assert_eq!(board, solution.board);
+
+    // a seeded root, where the path holds the queens of the search only
+    let root = Board::from_queens(8, [3]).unwrap();
+    let solution = solver.solve(root.clone());
+    assert!(solution.success);
+    assert_eq!(solution.path.len(), 7);
+    let mut board = root;
+    for index in &solution.path {
+        board.toggle(*index);
+    }
+    assert_eq!(board, solution.board);
+
+    // the path of a failure is empty
+    let solution = solver.solve(Board::new(3));
+    assert!(!solution.success);
+    assert!(solution.path.is_empty());
+}
+
+#[test]
+fn solve_min_conflicts_works() {
+    let mut solver = Solver::default();
+    for width in [1, 4, 8, 50, 200] {
+        let solution = solver.solve_min_conflicts(width, 10_000);
+        assert!(solution.success, "width {width}");
+        assert!(solution.board.is_solved());
+    }
+
+    // the widths 2 and 3 have no solution
+    for width in [2, 3] {
+        let solution = solver.solve_min_conflicts(width, 100);
+        assert_eq!(solution.outcome(), Outcome::Unsolvable);
+        assert!(solution.board.is_valid());
+    }
+
+    // the exhausted steps of a solvable width don't prove it unsolvable
+    let solution = Solver::default().solve_min_conflicts(50, 1);
+    assert_eq!(solution.outcome(), Outcome::Interrupted);
+    assert!(solution.board.is_valid());
+
+    // the jump budget interrupts the search
+    let solution = Solver::default()
+        .with_max_jumps(1)
+        .solve_min_conflicts(50, 10_000);
+    assert_eq!(solution.outcome(), Outcome::Interrupted);
+    assert_eq!(solution.jumps, 1);
 }
@@ -0,0 +1,19 @@
+/// Counters of the searches of a solver, accumulated between solves; see [`crate::Solver::stats`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolverStats {
+    /// The expanded nodes, as [`crate::Solver::jumps`].
+    pub jumps: usize,
+    /// The distinct boards expanded, up to rotations and reflections, if tracked via
+    /// [`crate::Solver::track_canonical_visits`].
+    ///
+    /// The depleted boards are shared only between rotations, so a ratio to the jumps well below
+    /// one means the search explores the reflections of the same boards.
+    pub canonical_visits: Option<usize>,
+    /// The solves answered by the opening book of [`crate::Solver::use_opening_book`], without
+    /// searching.
+    pub book_solutions: usize,
+    /// The misbehaving outputs of every evaluator, in the order of injection; see
+    /// [`crate::Evaluator::score`].
+    pub evaluator_misbehaviors: crate::Vec<usize>,
+}
@@ -0,0 +1,108 @@
+//! [`proptest`] strategies of boards and evaluator configurations, shared by the tests of the
+//! solver and of downstream crates through the `test-utils` feature.
+
+use crate::{Board, Solver};
+use core::ops::RangeInclusive;
+use proptest::{collection, prelude::*, sample};
+
+pub use reginae_evaluators::{Builtin, BUILTINS};
+
+/// Weights of a subset of the built-in evaluators, by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluatorConfig {
+    pub weights: Vec<(&'static str, f64)>,
+}
+
+impl EvaluatorConfig {
+    /// Injects the weighted evaluators into the solver.
+    pub fn apply(&self, solver: &mut Solver) {
+        for (name, weight) in &self.weights {
+            let f = reginae_evaluators::by_name(name)
+                .expect("the config holds only built-in evaluators");
+            solver.with_evaluator(f, *weight);
+        }
+    }
+
+    /// A solver with the weighted evaluators.
+    pub fn solver(&self) -> Solver {
+        let mut solver = Solver::default();
+        self.apply(&mut solver);
+        solver
+    }
+}
+
+/// Boards with a width of the range and a number of queens of the fill range, placed on cells
+/// that aren't attacked; the fill is capped by the free cells of the board.
+pub fn partial_board(
+    widths: RangeInclusive<usize>,
+    fill: RangeInclusive<usize>,
+) -> impl Strategy<Value = Board> {
+    (widths, fill).prop_flat_map(|(width, fill)| {
+        // every queen picks one of the free cells left by the previous ones
+        collection::vec(any::<usize>(), fill).prop_map(move |picks| {
+            let mut board = Board::new(width);
+            for pick in picks {
+                let free = board.available().collect::<Vec<_>>();
+                if free.is_empty() {
+                    break;
+                }
+                board.toggle(free[pick % free.len()]);
+            }
+            board
+        })
+    })
+}
+
+/// Boards of the width known to be completable: a symmetry of a solution, with a random subset
+/// of its queens removed.
+///
+/// # Panics
+///
+/// Panics if the width has no solution, such as the widths 2 and 3.
+pub fn solvable_board(width: usize) -> impl Strategy<Value = Board> {
+    let solution = Solver::default()
+        .solve_permutation(width)
+        .and_then(|p| Board::from_permutation(&p).ok())
+        .expect("the width must be solvable");
+
+    (0..8_usize)
+        .prop_map(move |symmetry| solution.symmetries()[symmetry].clone())
+        .prop_flat_map(move |board| {
+            let queens = board.sorted_queens().collect::<Vec<_>>();
+            sample::subsequence(queens, 0..=width).prop_map(move |kept| {
+                let mut board = Board::new(width);
+                kept.into_iter().for_each(|q| {
+                    board.toggle(q);
+                });
+                board
+            })
+        })
+}
+
+/// Random weights, between `-10.0` and `10.0`, of a non-empty subset of the built-in evaluators.
+pub fn evaluator_config() -> impl Strategy<Value = EvaluatorConfig> {
+    let names = BUILTINS.iter().map(|b| b.name).collect::<Vec<_>>();
+    sample::subsequence(names, 1..=BUILTINS.len()).prop_flat_map(|names| {
+        collection::vec(-10.0..10.0, names.len()).prop_map(move |weights| EvaluatorConfig {
+            weights: names.iter().copied().zip(weights).collect(),
+        })
+    })
+}
+
+#[cfg(test)]
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn partial_boards_are_valid(board in partial_board(1..=10, 0..=6)) {
+        reginae_core::test_utils::assert_board_consistent(&board);
+        prop_assert!(board.sorted_queens().count() <= 6);
+    }
+
+    #[test]
+    fn solvable_boards_are_solved(board in solvable_board(8), config in evaluator_config()) {
+        let solution = config.solver().solve(board.clone());
+        prop_assert!(solution.success);
+        prop_assert!(board.sorted_queens().all(|q| solution.board.is_queen(q)));
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{String, Vec};
+use core::{fmt::Write, mem};
+
+/// The default maximum number of nodes recorded by [`crate::Solver::record_tree`].
+pub const DEFAULT_TREE_CAP: usize = 10_000;
+
+/// The result of a node of the search tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NodeResult {
+    /// The node was scored as a candidate, but never explored.
+    Pruned,
+    /// The node was explored.
+    Expanded,
+    /// The node was skipped as its board is depleted.
+    Depleted,
+    /// The node solves the board.
+    Solved,
+}
+
+/// A node of the search tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TreeNode {
+    /// The node of the previous move, or `None` for the root.
+    pub parent: Option<usize>,
+    /// The index of the queen placed by this node, relative to the solved board.
+    pub index: usize,
+    /// The score of the move, as computed by the evaluator.
+    pub score: u64,
+    pub result: NodeResult,
+}
+
+/// The explored search tree of a solve, recorded up to a node cap.
+///
+/// Every node takes `size_of::<TreeNode>()` bytes (32 on 64-bit targets), so the memory of the
+/// tree is bounded by the cap. Once the cap is reached, the remaining nodes are dropped and the
+/// tree is flagged as truncated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchTree {
+    width: usize,
+    cap: usize,
+    nodes: Vec<TreeNode>,
+    truncated: bool,
+}
+
+impl SearchTree {
+    pub(crate) fn new(width: usize, cap: usize) -> Self {
+        Self {
+            width,
+            cap,
+            nodes: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    /// The recorded nodes, indexed by their id.
+    pub fn nodes(&self) -> &[TreeNode] {
+        &self.nodes
+    }
+
+    /// Whether nodes were dropped after the cap was reached.
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Memory used by the recorded nodes, in bytes.
+    pub fn memory(&self) -> usize {
+        self.nodes.len() * mem::size_of::<TreeNode>()
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        parent: Option<usize>,
+        index: usize,
+        score: u64,
+    ) -> Option<usize> {
+        if self.nodes.len() >= self.cap {
+            self.truncated = true;
+            return None;
+        }
+        self.nodes.push(TreeNode {
+            parent,
+            index,
+            score,
+            result: NodeResult::Pruned,
+        });
+        Some(self.nodes.len() - 1)
+    }
+
+    pub(crate) fn set_result(&mut self, node: Option<usize>, result: NodeResult) {
+        if let Some(n) = node.and_then(|n| self.nodes.get_mut(n)) {
+            n.result = result;
+        }
+    }
+
+    /// Maps the indexes of the nodes with the given function.
+    pub(crate) fn map_indexes<F>(&mut self, f: F)
+    where
+        F: Fn(usize) -> usize,
+    {
+        self.nodes.iter_mut().for_each(|n| n.index = f(n.index));
+    }
+
+    /// Renders the tree as a GraphViz digraph, with the nodes labeled by their `(column, row)`
+    /// coordinate and colored by their result.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph search {\n    node [style=filled];\n");
+
+        for (id, node) in self.nodes.iter().enumerate() {
+            let (label, color) = match node.result {
+                NodeResult::Pruned => ("pruned", "white"),
+                NodeResult::Expanded => ("expanded", "lightblue"),
+                NodeResult::Depleted => ("depleted", "gray"),
+                NodeResult::Solved => ("solved", "green"),
+            };
+            match node.parent {
+                Some(parent) => {
+                    let score = node.score as f64 / u64::MAX as f64;
+                    let _ = writeln!(
+                        dot,
+                        "    n{id} [label=\"({}, {})\\n{score:.3} {label}\", fillcolor={color}];",
+                        node.index % self.width,
+                        node.index / self.width,
+                    );
+                    let _ = writeln!(dot, "    n{parent} -> n{id};");
+                }
+                None => {
+                    let _ = writeln!(
+                        dot,
+                        "    n{id} [label=\"root\\n{label}\", fillcolor={color}];"
+                    );
+                }
+            }
+        }
+
+        if self.truncated {
+            let _ = writeln!(
+                dot,
+                "    truncated [shape=note, label=\"truncated at {} nodes\"];",
+                self.cap
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -0,0 +1,37 @@
+use reginae_solver::{Board, Solver};
+
+#[test]
+fn budgeted_solves_resume_the_search() {
+    let expected = Solver::default().solve(Board::new(8));
+    assert!(expected.success);
+    assert!(!expected.interrupted);
+
+    // the budget is greater than the width, so every call depletes a path at least
+    let mut solver = Solver::default();
+    solver.with_max_jumps(10);
+    let mut calls = 1;
+    let mut solution = solver.solve(Board::new(8));
+    while solution.interrupted {
+        assert!(!solution.success);
+        solution = solver.solve(Board::new(8));
+        calls += 1;
+    }
+
+    // the exhausted subtrees are skipped, so the budgeted search finds the same solution
+    assert!(solution.success);
+    assert!(calls > 1);
+    assert_eq!(solution.board, expected.board);
+}
+
+#[test]
+fn exhausted_boards_are_skipped_by_the_next_solves() {
+    // an unsolvable width exhausts the whole tree, so a second solve is a lookup of the root
+    let mut solver = Solver::default();
+    let first = solver.solve(Board::new(3));
+    assert!(!first.success);
+    assert!(!first.interrupted);
+
+    let second = solver.solve(Board::new(3));
+    assert!(!second.success);
+    assert_eq!(second.jumps, first.jumps);
+}
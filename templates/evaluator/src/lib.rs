@@ -0,0 +1,52 @@
+//! A minimal evaluator plugin, to be copied as the start of a new one.
+//!
+//! The plugin is built as a `cdylib` and loaded by the CLI via
+//! `-l target/release/libreginae_evaluator_template.so:centrality:weight`. Rename the crate, and
+//! replace [`centrality`] with the new heuristic; the `std` feature of `reginae-plugin-abi`
+//! catches the panics of the evaluators, so a bug scores `0.0` instead of aborting the host.
+
+#![no_std]
+
+use reginae_core::Board;
+
+reginae_plugin_abi::export_manifest!(reginae_plugin_abi::export_evaluator!(
+    Board,
+    centrality,
+    name = "centrality",
+    description = "prefers boards with the queens close to the center",
+    weight = 1.0,
+));
+
+/// score higher as the queens are closer to the center of the board, from `0.0` when all of them
+/// are on the corners to `1.0` when all of them are on the center.
+pub fn centrality(board: &Board, _last_move: usize) -> f64 {
+    let width = board.width();
+    if width < 2 || board.is_empty() {
+        return 1.0;
+    }
+
+    let middle = (width - 1) as f64 / 2.0;
+    let (count, sum) = board.sorted_queens().fold((0, 0.0), |(count, sum), q| {
+        let row = (q / width) as f64;
+        let column = (q % width) as f64;
+        let distance = (row - middle).abs() + (column - middle).abs();
+        (count + 1, sum + 1.0 - distance / (2.0 * middle))
+    });
+
+    sum / count as f64
+}
+
+#[test]
+fn centrality_works() {
+    let mut board = Board::new(5);
+    assert_eq!(centrality(&board, 0), 1.0);
+
+    board.toggle(0);
+    assert_eq!(centrality(&board, 0), 0.0);
+
+    board.toggle(7);
+    assert_eq!(centrality(&board, 7), 0.375);
+
+    board.toggle(0).toggle(7).toggle(12);
+    assert_eq!(centrality(&board, 12), 1.0);
+}
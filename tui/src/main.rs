@@ -6,16 +6,29 @@ use crossterm::{
     terminal,
     terminal::{disable_raw_mode, enable_raw_mode},
 };
-use reginae_solver::{Board, Solution, Solver};
+use reginae_solver::{Board, Position, Solution, Solver, ToggleResult};
 use std::io::{self, Write};
 
 const QUEEN: char = '\u{2588}';
 const ATTACKED: char = '\u{2593}';
 const FREE: char = '\u{2591}';
 
+/// Famous solutions, as the column of the queen of every row, cycled by the `f` command.
+const FAMOUS: &[(&str, &[usize])] = &[
+    (
+        "the symmetric solution of width 8",
+        &[2, 4, 1, 7, 0, 6, 3, 5],
+    ),
+    ("the first solution of width 8", &[0, 4, 7, 5, 2, 6, 1, 3]),
+    ("the unique solution of width 4", &[1, 3, 0, 2]),
+    ("the staircase solution of width 5", &[0, 2, 4, 1, 3]),
+    ("the staircase solution of width 6", &[1, 3, 5, 0, 2, 4]),
+];
+
 #[derive(Debug)]
 struct State {
     board: Board,
+    famous: usize,
     messages: Vec<String>,
     pos: (u16, u16),
     stdout: io::Stdout,
@@ -25,6 +38,7 @@ impl State {
     pub fn new(width: usize) -> Self {
         Self {
             board: Board::new(width),
+            famous: 0,
             messages: Vec::with_capacity(8),
             pos: (0, 0),
             stdout: io::stdout(),
@@ -50,30 +64,46 @@ impl State {
                 self.pos.0 = self.pos.0.saturating_sub(1);
             }
             KeyCode::Char('j') | KeyCode::Down => {
-                self.pos.1 = (self.pos.1 + 1).min(width - 1);
+                self.pos.1 = (self.pos.1 + 1).min(width.saturating_sub(1));
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 self.pos.1 = self.pos.1.saturating_sub(1);
             }
             KeyCode::Char('l') | KeyCode::Right => {
-                self.pos.0 = (self.pos.0 + 1).min(width - 1);
+                self.pos.0 = (self.pos.0 + 1).min(width.saturating_sub(1));
             }
             KeyCode::Char(' ') => {
-                self.board
-                    .toggle_with_pair(self.pos.0 as usize, self.pos.1 as usize);
-                if self.board.is_solved() {
-                    self.messages.push("solved!".to_string());
+                let (column, row) = (self.pos.0 as usize, self.pos.1 as usize);
+                let Some(index) = self.board.index_of(column, row) else {
+                    return Ok(true);
+                };
+                match self.board.toggle_reporting(index) {
+                    ToggleResult::Placed if self.board.is_solved() => {
+                        self.messages.push("solved!".to_string())
+                    }
+                    ToggleResult::Placed => self.messages.push(format!("placed ({column}, {row})")),
+                    ToggleResult::Removed => {
+                        self.messages.push(format!("removed ({column}, {row})"))
+                    }
+                    ToggleResult::Ignored => {
+                        self.messages.push(format!("({column}, {row}) is attacked"))
+                    }
+                    ToggleResult::Blocked => {
+                        self.messages.push(format!("({column}, {row}) is blocked"))
+                    }
                 }
             }
             KeyCode::Char('c') => {
                 self.board.clear();
             }
+            KeyCode::Char('f') => self.load_famous(),
             KeyCode::Char('x') => {
                 let board = self.board.clone();
                 let Solution {
                     board,
                     success,
                     jumps,
+                    ..
                 } = Solver::default().solve(board);
                 if success {
                     self.board = board;
@@ -95,10 +125,11 @@ impl State {
                 enable_raw_mode()?;
                 input.retain(|c| c.is_ascii_digit());
                 match input.parse::<u16>() {
-                    Ok(w) => {
-                        self.pos = (0, 0);
-                        self.board = Board::new(w as usize);
-                    }
+                    // the empty board has no cell for the cursor
+                    Ok(0) => self
+                        .messages
+                        .push("the width must be at least 1".to_string()),
+                    Ok(w) => self.resize(w as usize),
                     Err(e) => self.messages.push(e.to_string()),
                 }
             }
@@ -108,6 +139,42 @@ impl State {
         Ok(true)
     }
 
+    /// Resizes the board, keeping the queens that fit and clamping the cursor to it.
+    fn resize(&mut self, width: usize) {
+        let former = self.board.width();
+        match self.board.resize(width) {
+            Ok(dropped) => {
+                let max = width.saturating_sub(1) as u16;
+                self.pos = (self.pos.0.min(max), self.pos.1.min(max));
+                if !dropped.is_empty() {
+                    let dropped = dropped
+                        .iter()
+                        .map(|q| format!("({}, {})", q % former, q / former))
+                        .collect::<Vec<_>>();
+                    self.messages
+                        .push(format!("dropped the queens of {}", dropped.join(", ")));
+                }
+            }
+            Err(e) => self.messages.push(e.to_string()),
+        }
+    }
+
+    /// Loads the next famous solution, resizing the board and clamping the cursor to it.
+    fn load_famous(&mut self) {
+        let (name, permutation) = FAMOUS[self.famous];
+        self.famous = (self.famous + 1) % FAMOUS.len();
+
+        match Board::from_permutation(permutation) {
+            Ok(board) => {
+                let max = board.width().saturating_sub(1) as u16;
+                self.pos = (self.pos.0.min(max), self.pos.1.min(max));
+                self.board = board;
+                self.messages.push(format!("loaded {name}"));
+            }
+            Err(e) => self.messages.push(e.to_string()),
+        }
+    }
+
     fn render(&mut self) -> io::Result<()> {
         execute!(
             self.stdout,
@@ -133,7 +200,7 @@ impl State {
         queue!(
             self.stdout,
             MoveTo(0, i),
-            Print("hjkl - move; c - clear; r - resize; x - solve; space - toggle queen; q - quit")
+            Print("hjkl - move; c - clear; r - resize; f - famous; x - solve; space - toggle queen; q - quit")
         )?;
         self.messages.iter().try_for_each(|m| {
             i += 1;
@@ -173,3 +240,51 @@ fn main() -> io::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn load_famous_works() {
+    let mut state = State::new(12);
+    state.pos = (11, 3);
+
+    state.load_famous();
+    assert_eq!(
+        state.board.sorted_queens().collect::<Vec<_>>(),
+        vec![2, 12, 17, 31, 32, 46, 51, 61]
+    );
+    assert!(state.board.is_solved());
+    assert_eq!(state.pos, (7, 3));
+
+    // every solution is valid and the cycle wraps around
+    for _ in 1..FAMOUS.len() {
+        state.load_famous();
+        assert!(state.board.is_solved(), "{:?}", state.messages);
+    }
+    state.load_famous();
+    assert_eq!(state.board.to_permutation().as_deref(), Some(FAMOUS[0].1));
+}
+
+#[test]
+fn resize_keeps_the_queens() {
+    let mut state = State::new(8);
+    state.board = Board::from_permutation(&[0, 4, 7, 5, 2, 6, 1, 3]).unwrap();
+    state.pos = (7, 2);
+
+    state.resize(6);
+    assert_eq!(
+        state.board.sorted_queens().collect::<Vec<_>>(),
+        vec![0, 10, 23, 26]
+    );
+    assert_eq!(state.pos, (5, 2));
+    assert_eq!(
+        state.messages,
+        ["dropped the queens of (7, 2), (6, 5), (1, 6), (3, 7)"]
+    );
+
+    state.messages.clear();
+    state.resize(10);
+    assert_eq!(
+        state.board.sorted_queens().collect::<Vec<_>>(),
+        vec![0, 14, 35, 42]
+    );
+    assert!(state.messages.is_empty());
+}
@@ -0,0 +1,41 @@
+//! Example of an evaluator plugin for the WebAssembly sandbox of the CLI; see
+//! `reginae_plugin_abi::wasm`.
+//!
+//! ```shell
+//! cargo build -p reginae-wasm-plugin --target wasm32-unknown-unknown --release
+//! ```
+
+use reginae_core::Board;
+use reginae_plugin_abi::wasm;
+use std::cell::RefCell;
+
+thread_local! {
+    static BUFFER: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the buffer the host writes the encoded board to.
+#[no_mangle]
+pub extern "C" fn reginae_board_buffer(len: u32) -> *mut u8 {
+    BUFFER.with(|b| {
+        let mut buffer = b.borrow_mut();
+        buffer.clear();
+        buffer.resize(len as usize, 0);
+        buffer.as_mut_ptr()
+    })
+}
+
+/// Evaluates the board of the buffer with the `overlapping` evaluator.
+#[no_mangle]
+pub extern "C" fn evaluate(last_move: u32) -> f64 {
+    BUFFER.with(|b| {
+        let buffer = b.borrow();
+        let Some((width, queens)) = wasm::decode(&buffer) else {
+            return 0.0;
+        };
+        let mut board = Board::new(width);
+        queens.for_each(|q| {
+            board.toggle(q);
+        });
+        reginae_evaluators::overlapping(&board, last_move as usize)
+    })
+}
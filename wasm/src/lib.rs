@@ -0,0 +1,204 @@
+use reginae_solver::{Board, Outcome, Solution, Solver, ToggleResult};
+use wasm_bindgen::prelude::*;
+
+const QUEEN: char = '\u{2588}';
+const ATTACKED: char = '\u{2593}';
+const FREE: char = '\u{2591}';
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct WasmBoard {
+    board: Board,
+}
+
+#[wasm_bindgen]
+impl WasmBoard {
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize) -> WasmBoard {
+        Self {
+            board: Board::new(width),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.board.width()
+    }
+
+    /// Toggles the queen of the given index, returning `true` if the cell holds a queen after the
+    /// operation. Out of bounds indexes are ignored.
+    pub fn toggle(&mut self, index: usize) -> bool {
+        self.board.try_toggle(index) == Ok(ToggleResult::Placed)
+    }
+
+    pub fn clear(&mut self) {
+        self.board.clear();
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.board.is_solved()
+    }
+
+    /// Sorted indexes of the queens, exposed as `Uint32Array`.
+    pub fn queens(&self) -> Vec<u32> {
+        self.board.sorted_queens().map(|q| q as u32).collect()
+    }
+
+    /// Renders the board with one line per row, using the same glyphs as the terminal UI.
+    pub fn render(&self) -> String {
+        self.board
+            .rows()
+            .flat_map(|row| {
+                row.iter()
+                    .map(|c| {
+                        if c.is_queen() {
+                            QUEEN
+                        } else if c.is_attacked() {
+                            ATTACKED
+                        } else {
+                            FREE
+                        }
+                    })
+                    .chain(Some('\n'))
+            })
+            .collect()
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmSolution {
+    queens: Vec<u32>,
+    outcome: String,
+    jumps: usize,
+}
+
+#[wasm_bindgen]
+impl WasmSolution {
+    /// Sorted indexes of the queens of the resulting board, exposed as `Uint32Array`.
+    #[wasm_bindgen(getter)]
+    pub fn queens(&self) -> Vec<u32> {
+        self.queens.clone()
+    }
+
+    /// One of `solved`, `unsolvable`, or `interrupted`.
+    #[wasm_bindgen(getter)]
+    pub fn outcome(&self) -> String {
+        self.outcome.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn jumps(&self) -> usize {
+        self.jumps
+    }
+}
+
+impl From<Solution> for WasmSolution {
+    fn from(solution: Solution) -> Self {
+        let outcome = match solution.outcome() {
+            Outcome::Solved => "solved",
+            Outcome::Unsolvable => "unsolvable",
+            Outcome::Interrupted => "interrupted",
+        };
+        Self {
+            queens: solution.board.sorted_queens().map(|q| q as u32).collect(),
+            outcome: outcome.to_string(),
+            jumps: solution.jumps,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Default, Clone)]
+pub struct WasmSolver {
+    solver: Solver,
+    board: Option<Board>,
+}
+
+#[wasm_bindgen]
+impl WasmSolver {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmSolver {
+        Self::default()
+    }
+
+    /// Registers a built-in evaluator by its name, returning `false` if the name is unknown.
+    pub fn with_evaluator(&mut self, name: &str, weight: f64) -> bool {
+        match reginae_evaluators::by_name(name) {
+            Some(f) => {
+                self.solver.with_evaluator(f, weight);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Solves the board in a single call, optionally bounded by a jump budget.
+    pub fn solve(&mut self, board: &WasmBoard, max_jumps: Option<usize>) -> WasmSolution {
+        self.solver.with_max_jumps(max_jumps.unwrap_or(usize::MAX));
+        self.solver.solve(board.board.clone()).into()
+    }
+
+    /// Sets the board to be solved by subsequent [`WasmSolver::step`] calls.
+    pub fn start(&mut self, board: &WasmBoard) {
+        self.board.replace(board.board.clone());
+    }
+
+    /// The queens placed by the deepest path reached by the slices of the board, so far; see
+    /// [`Solver::best_partial`].
+    pub fn best_partial(&self) -> Vec<u32> {
+        self.solver
+            .best_partial()
+            .iter()
+            .map(|q| *q as u32)
+            .collect()
+    }
+
+    /// Resumes the search of the board set via [`WasmSolver::start`], performing at most
+    /// `max_jumps` jumps so the caller can yield between slices.
+    ///
+    /// The search progresses as long as `max_jumps` is greater than the width of the board.
+    /// Returns `interrupted` until the board is either solved or exhausted, and `unsolvable` if
+    /// no board was set.
+    pub fn step(&mut self, max_jumps: usize) -> WasmSolution {
+        let board = match self.board.clone() {
+            Some(board) => board,
+            None => {
+                return WasmSolution {
+                    queens: Vec::new(),
+                    outcome: "unsolvable".to_string(),
+                    jumps: self.solver.jumps(),
+                }
+            }
+        };
+        self.solver.with_max_jumps(max_jumps);
+        self.solver.solve(board).into()
+    }
+}
+
+#[test]
+fn step_resumes_the_search() {
+    let mut board = WasmBoard::new(8);
+    board.toggle(1);
+
+    let mut solver = WasmSolver::new();
+    let expected = solver.solve(&board, None);
+    assert_eq!(expected.outcome(), "solved");
+
+    let mut solver = WasmSolver::new();
+    solver.start(&board);
+    let mut slices = 0;
+    let mut depth = 0;
+    let solution = loop {
+        let solution = solver.step(50);
+        slices += 1;
+        assert!(solver.best_partial().len() >= depth);
+        depth = solver.best_partial().len();
+        if solution.outcome() != "interrupted" {
+            break solution;
+        }
+    };
+
+    assert!(slices > 1);
+    assert_eq!(solution.queens(), expected.queens());
+    assert_eq!(depth, 7);
+}
@@ -0,0 +1,32 @@
+#![cfg(target_arch = "wasm32")]
+
+use reginae_wasm::{WasmBoard, WasmSolver};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn solves_width_8() {
+    let board = WasmBoard::new(8);
+    let mut solver = WasmSolver::new();
+    assert!(solver.with_evaluator("overlapping", 10.0));
+    assert!(!solver.with_evaluator("unknown", 1.0));
+
+    let solution = solver.solve(&board, None);
+    assert_eq!(solution.outcome(), "solved");
+    assert_eq!(solution.queens().len(), 8);
+}
+
+#[wasm_bindgen_test]
+fn steps_width_8() {
+    let board = WasmBoard::new(8);
+    let mut solver = WasmSolver::new();
+    solver.start(&board);
+
+    let solution = loop {
+        let solution = solver.step(100);
+        if solution.outcome() != "interrupted" {
+            break solution;
+        }
+    };
+    assert_eq!(solution.outcome(), "solved");
+    assert_eq!(solution.queens().len(), 8);
+}